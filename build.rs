@@ -1,6 +1,12 @@
 use std::env;
 
 fn main() {
+    // The simulated HAL is pure Rust, so there is nothing to link unless we are building for the
+    // roboRIO.
+    if env::var_os("CARGO_FEATURE_ATHENA").is_none() {
+        return;
+    }
+
     for lib in ["HALAthena",
                 "wpiutil",
                 "FRC_NetworkCommunication",
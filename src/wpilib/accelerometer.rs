@@ -0,0 +1,47 @@
+/// The range of accelerations an accelerometer measures. A smaller range gives more resolution.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AccelerometerRange {
+    /// -2g to 2g.
+    TwoG,
+    /// -4g to 4g.
+    FourG,
+    /// -8g to 8g.
+    EightG,
+}
+
+/// An acceleration along each of three axes, in g.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Acceleration {
+    /// The acceleration along the X axis.
+    pub x: f64,
+    /// The acceleration along the Y axis.
+    pub y: f64,
+    /// The acceleration along the Z axis.
+    pub z: f64,
+}
+
+/// A generic interface to a three axis accelerometer. Accelerations are in g.
+pub trait Accelerometer {
+    /// The error returned when the accelerometer cannot be read or configured.
+    type Error;
+
+    /// Set the range of accelerations to measure, returning an error if the accelerometer does
+    /// not support it.
+    fn set_range(&mut self, range: AccelerometerRange) -> Result<(), Self::Error>;
+
+    /// Get the acceleration along the X axis.
+    fn get_x(&self) -> Result<f64, Self::Error>;
+    /// Get the acceleration along the Y axis.
+    fn get_y(&self) -> Result<f64, Self::Error>;
+    /// Get the acceleration along the Z axis.
+    fn get_z(&self) -> Result<f64, Self::Error>;
+
+    /// Get the acceleration along every axis.
+    fn get_all(&self) -> Result<Acceleration, Self::Error> {
+        Ok(Acceleration {
+            x: self.get_x()?,
+            y: self.get_y()?,
+            z: self.get_z()?,
+        })
+    }
+}
@@ -0,0 +1,255 @@
+use wpilib::wpilib_hal::*;
+use wpilib::accelerometer::*;
+use wpilib::bus::*;
+use wpilib::sync::lock;
+use wpilib::i2c::{I2cInterface, I2cPort};
+use wpilib::spi::{SpiInterface, SpiMode, SpiPort};
+use wpilib::usage::*;
+use std::sync::Mutex;
+
+const POWER_CTL_REGISTER: u8 = 0x2d;
+const POWER_CTL_MEASURE: u8 = 0x08;
+
+const DATA_FORMAT_REGISTER: u8 = 0x31;
+const DATA_FORMAT_FULL_RES: u8 = 0x08;
+
+/// The first of six data registers, holding X, Y and Z in turn, least significant byte first.
+const DATA_REGISTER: u8 = 0x32;
+const X_OFFSET: u8 = 0;
+const Y_OFFSET: u8 = 2;
+const Z_OFFSET: u8 = 4;
+
+/// The sensitivity in full resolution mode, which is the same for every range.
+const GS_PER_LSB: f64 = 0.003_906_25;
+
+/// Flags set in the register address of SPI reads.
+const SPI_READ: u8 = 0x80;
+const SPI_MULTI_BYTE: u8 = 0x40;
+
+/// The I2C address of an ADXL345 with its address pin low, as on the kit of parts board.
+const DEFAULT_I2C_ADDRESS: i32 = 0x1d;
+
+/// The range of accelerations an ADXL345 measures. Unlike most accelerometers, it can measure up
+/// to 16g. Every `AccelerometerRange` converts into one of these.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ADXL345Range {
+    /// -2g to 2g.
+    TwoG,
+    /// -4g to 4g.
+    FourG,
+    /// -8g to 8g.
+    EightG,
+    /// -16g to 16g.
+    SixteenG,
+}
+
+impl From<AccelerometerRange> for ADXL345Range {
+    fn from(range: AccelerometerRange) -> ADXL345Range {
+        match range {
+            AccelerometerRange::TwoG => ADXL345Range::TwoG,
+            AccelerometerRange::FourG => ADXL345Range::FourG,
+            AccelerometerRange::EightG => ADXL345Range::EightG,
+        }
+    }
+}
+
+/// The data format register value for a range, with full resolution on.
+fn data_format(range: ADXL345Range) -> u8 {
+    DATA_FORMAT_FULL_RES |
+    match range {
+        ADXL345Range::TwoG => 0,
+        ADXL345Range::FourG => 1,
+        ADXL345Range::EightG => 2,
+        ADXL345Range::SixteenG => 3,
+    }
+}
+
+/// Convert a reading from the data registers to g.
+fn to_gs(data: &[u8]) -> f64 {
+    i16::from_le_bytes([data[0], data[1]]) as f64 * GS_PER_LSB
+}
+
+/// Convert all six data registers to an acceleration.
+fn to_acceleration(data: &[u8]) -> Acceleration {
+    Acceleration {
+        x: to_gs(&data[X_OFFSET as usize..]),
+        y: to_gs(&data[Y_OFFSET as usize..]),
+        z: to_gs(&data[Z_OFFSET as usize..]),
+    }
+}
+
+/// An ADXL345 accelerometer on the SPI bus. It measures up to 16g.
+///
+/// # Usage
+/// ```
+/// # use wpilib::*;
+/// let accelerometer = ADXL345Spi::new(SpiPort::Onboard0, ADXL345Range::SixteenG).unwrap();
+/// # sim::spi(SpiPort::Onboard0).queue_response(&[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01]);
+///
+/// let acceleration = accelerometer.get_all().unwrap();
+/// # assert_eq!(acceleration.z, 1.0);
+/// ```
+pub struct ADXL345Spi {
+    spi: Mutex<SpiInterface>,
+}
+
+impl ADXL345Spi {
+    /// Start an accelerometer on the specified port with the given range, which is either an
+    /// `ADXL345Range` or an `AccelerometerRange`, returning an error if initialization fails.
+    pub fn new<R>(port: SpiPort, range: R) -> Result<ADXL345Spi, DeviceError>
+        where R: Into<ADXL345Range>
+    {
+        let mut spi = SpiInterface::new(port)?;
+        spi.set_clock_rate(500_000);
+        spi.set_mode(SpiMode::Mode3);
+
+        let mut accelerometer = ADXL345Spi { spi: Mutex::new(spi) };
+        accelerometer.write_register(POWER_CTL_REGISTER, POWER_CTL_MEASURE)?;
+        accelerometer.set_range(range)?;
+
+        report_usage(ResourceType::ADXL345, kADXL345_SPI as i32);
+        Ok(accelerometer)
+    }
+
+    /// Set the range of accelerations to measure, which is either an `ADXL345Range` or an
+    /// `AccelerometerRange`.
+    pub fn set_range<R>(&mut self, range: R) -> Result<(), DeviceError>
+        where R: Into<ADXL345Range>
+    {
+        self.write_register(DATA_FORMAT_REGISTER, data_format(range.into()))
+    }
+
+    fn write_register(&mut self, register: u8, value: u8) -> Result<(), DeviceError> {
+        Ok(spi_write(&mut lock(&self.spi), &[register, value])?)
+    }
+
+    /// Read consecutive registers into `data`.
+    fn read_registers(&self, register: u8, data: &mut [u8]) -> Result<(), DeviceError> {
+        Ok(spi_read(&mut lock(&self.spi), &[register | SPI_READ | SPI_MULTI_BYTE], data)?)
+    }
+
+    fn get_axis(&self, offset: u8) -> Result<f64, DeviceError> {
+        let mut data = [0; 2];
+        self.read_registers(DATA_REGISTER + offset, &mut data)?;
+        Ok(to_gs(&data))
+    }
+}
+
+impl Accelerometer for ADXL345Spi {
+    type Error = DeviceError;
+
+    fn set_range(&mut self, range: AccelerometerRange) -> Result<(), DeviceError> {
+        ADXL345Spi::set_range(self, range)
+    }
+
+    fn get_x(&self) -> Result<f64, DeviceError> {
+        self.get_axis(X_OFFSET)
+    }
+
+    fn get_y(&self) -> Result<f64, DeviceError> {
+        self.get_axis(Y_OFFSET)
+    }
+
+    fn get_z(&self) -> Result<f64, DeviceError> {
+        self.get_axis(Z_OFFSET)
+    }
+
+    /// Get the acceleration along every axis, all from the same sample.
+    fn get_all(&self) -> Result<Acceleration, DeviceError> {
+        let mut data = [0; 6];
+        self.read_registers(DATA_REGISTER, &mut data)?;
+        Ok(to_acceleration(&data))
+    }
+}
+
+/// An ADXL345 accelerometer on the I2C bus, such as the one on the kit of parts accelerometer
+/// board. It measures up to 16g.
+///
+/// # Usage
+/// ```
+/// # use wpilib::*;
+/// # sim::i2c(I2cPort::OnBoard).add_device(0x1d);
+/// let accelerometer = ADXL345I2c::new(I2cPort::OnBoard, AccelerometerRange::FourG).unwrap();
+/// # sim::i2c(I2cPort::OnBoard).set_registers(0x1d, 0x32, &[0x00, 0x01, 0x00, 0x00, 0x00, 0x01]);
+/// let tilt = accelerometer.get_x().unwrap().atan2(accelerometer.get_z().unwrap());
+/// # assert!((tilt - std::f64::consts::FRAC_PI_4).abs() < 1e-9);
+/// ```
+pub struct ADXL345I2c {
+    i2c: Mutex<I2cInterface>,
+}
+
+impl ADXL345I2c {
+    /// Start an accelerometer on the specified port with the given range, which is either an
+    /// `ADXL345Range` or an `AccelerometerRange`, returning an error if initialization fails or the
+    /// device does not respond.
+    pub fn new<R>(port: I2cPort, range: R) -> Result<ADXL345I2c, DeviceError>
+        where R: Into<ADXL345Range>
+    {
+        ADXL345I2c::with_address(port, DEFAULT_I2C_ADDRESS, range)
+    }
+
+    /// Start an accelerometer at a different address, such as `0x53` for one with its address
+    /// pin high.
+    pub fn with_address<R>(port: I2cPort, address: i32, range: R) -> Result<ADXL345I2c, DeviceError>
+        where R: Into<ADXL345Range>
+    {
+        let i2c = I2cInterface::new(port, address)?;
+
+        let mut accelerometer = ADXL345I2c { i2c: Mutex::new(i2c) };
+        accelerometer.write_register(POWER_CTL_REGISTER, POWER_CTL_MEASURE)?;
+        accelerometer.set_range(range)?;
+
+        report_usage(ResourceType::ADXL345, kADXL345_I2C as i32);
+        Ok(accelerometer)
+    }
+
+    /// Set the range of accelerations to measure, which is either an `ADXL345Range` or an
+    /// `AccelerometerRange`.
+    pub fn set_range<R>(&mut self, range: R) -> Result<(), DeviceError>
+        where R: Into<ADXL345Range>
+    {
+        self.write_register(DATA_FORMAT_REGISTER, data_format(range.into()))
+    }
+
+    fn write_register(&mut self, register: u8, value: u8) -> Result<(), DeviceError> {
+        Ok(lock(&self.i2c).write_register(register, value)?)
+    }
+
+    /// Read consecutive registers into `data`.
+    fn read_registers(&self, register: u8, data: &mut [u8]) -> Result<(), DeviceError> {
+        Ok(lock(&self.i2c).read_register(register, data)?)
+    }
+
+    fn get_axis(&self, offset: u8) -> Result<f64, DeviceError> {
+        let mut data = [0; 2];
+        self.read_registers(DATA_REGISTER + offset, &mut data)?;
+        Ok(to_gs(&data))
+    }
+}
+
+impl Accelerometer for ADXL345I2c {
+    type Error = DeviceError;
+
+    fn set_range(&mut self, range: AccelerometerRange) -> Result<(), DeviceError> {
+        ADXL345I2c::set_range(self, range)
+    }
+
+    fn get_x(&self) -> Result<f64, DeviceError> {
+        self.get_axis(X_OFFSET)
+    }
+
+    fn get_y(&self) -> Result<f64, DeviceError> {
+        self.get_axis(Y_OFFSET)
+    }
+
+    fn get_z(&self) -> Result<f64, DeviceError> {
+        self.get_axis(Z_OFFSET)
+    }
+
+    /// Get the acceleration along every axis, all from the same sample.
+    fn get_all(&self) -> Result<Acceleration, DeviceError> {
+        let mut data = [0; 6];
+        self.read_registers(DATA_REGISTER, &mut data)?;
+        Ok(to_acceleration(&data))
+    }
+}
@@ -0,0 +1,131 @@
+use wpilib::accelerometer::*;
+use wpilib::bus::*;
+use wpilib::sync::lock;
+use wpilib::spi::{SpiInterface, SpiMode, SpiPort};
+use wpilib::usage::*;
+use std::sync::Mutex;
+
+/// The commands that start every transfer.
+const REGISTER_WRITE: u8 = 0x0a;
+const REGISTER_READ: u8 = 0x0b;
+
+const PART_ID_REGISTER: u8 = 0x02;
+const PART_ID: u8 = 0xf2;
+
+/// The first of six data registers, holding X, Y and Z in turn, least significant byte first.
+const DATA_REGISTER: u8 = 0x0e;
+const X_OFFSET: u8 = 0;
+const Y_OFFSET: u8 = 2;
+const Z_OFFSET: u8 = 4;
+
+const FILTER_CTL_REGISTER: u8 = 0x2c;
+const FILTER_CTL_RANGE_2G: u8 = 0x00;
+const FILTER_CTL_RANGE_4G: u8 = 0x40;
+const FILTER_CTL_RANGE_8G: u8 = 0x80;
+const FILTER_CTL_ODR_100HZ: u8 = 0x03;
+
+const POWER_CTL_REGISTER: u8 = 0x2d;
+const POWER_CTL_ULTRA_LOW_NOISE: u8 = 0x20;
+const POWER_CTL_MEASURE: u8 = 0x02;
+
+/// An ADXL362 accelerometer on the SPI bus. It measures up to 8g.
+///
+/// # Usage
+/// ```
+/// # use wpilib::*;
+/// # sim::spi(SpiPort::MXP).queue_response(&[0x00, 0x00, 0xf2]);
+/// let accelerometer = ADXL362::new(SpiPort::MXP, AccelerometerRange::TwoG).unwrap();
+/// # sim::spi(SpiPort::MXP).queue_response(&[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xe8, 0x03]);
+///
+/// let acceleration = accelerometer.get_all().unwrap();
+/// # assert_eq!(acceleration.z, 1.0);
+/// ```
+pub struct ADXL362 {
+    spi: Mutex<SpiInterface>,
+    gs_per_lsb: f64,
+}
+
+impl ADXL362 {
+    /// Start an accelerometer on the specified port with the given range, returning an error if
+    /// initialization fails or there is no ADXL362 on the port.
+    pub fn new(port: SpiPort, range: AccelerometerRange) -> Result<ADXL362, DeviceError> {
+        let mut spi = SpiInterface::new(port)?;
+        spi.set_clock_rate(3_000_000);
+        spi.set_mode(SpiMode::Mode3);
+
+        let mut accelerometer = ADXL362 {
+            spi: Mutex::new(spi),
+            gs_per_lsb: 0.0,
+        };
+        let mut part_id = [0; 1];
+        accelerometer.read_registers(PART_ID_REGISTER, &mut part_id)?;
+        if part_id[0] != PART_ID {
+            return Err(DeviceError::WrongDevice);
+        }
+
+        accelerometer.set_range(range)?;
+        accelerometer.write_register(POWER_CTL_REGISTER,
+                                     POWER_CTL_MEASURE | POWER_CTL_ULTRA_LOW_NOISE)?;
+
+        report_usage(ResourceType::ADXL362, port as i32);
+        Ok(accelerometer)
+    }
+
+    fn write_register(&mut self, register: u8, value: u8) -> Result<(), DeviceError> {
+        Ok(spi_write(&mut lock(&self.spi), &[REGISTER_WRITE, register, value])?)
+    }
+
+    /// Read consecutive registers into `data`.
+    fn read_registers(&self, register: u8, data: &mut [u8]) -> Result<(), DeviceError> {
+        Ok(spi_read(&mut lock(&self.spi), &[REGISTER_READ, register], data)?)
+    }
+
+    /// Convert a reading from the data registers to g.
+    fn to_gs(&self, data: &[u8]) -> f64 {
+        i16::from_le_bytes([data[0], data[1]]) as f64 * self.gs_per_lsb
+    }
+
+    fn get_axis(&self, offset: u8) -> Result<f64, DeviceError> {
+        let mut data = [0; 2];
+        self.read_registers(DATA_REGISTER + offset, &mut data)?;
+        Ok(self.to_gs(&data))
+    }
+}
+
+impl Accelerometer for ADXL362 {
+    type Error = DeviceError;
+
+    fn set_range(&mut self, range: AccelerometerRange) -> Result<(), DeviceError> {
+        let (range_bits, gs_per_lsb) = match range {
+            AccelerometerRange::TwoG => (FILTER_CTL_RANGE_2G, 0.001),
+            AccelerometerRange::FourG => (FILTER_CTL_RANGE_4G, 0.002),
+            AccelerometerRange::EightG => (FILTER_CTL_RANGE_8G, 0.004),
+        };
+        self.write_register(FILTER_CTL_REGISTER, range_bits | FILTER_CTL_ODR_100HZ)?;
+        self.gs_per_lsb = gs_per_lsb;
+        Ok(())
+    }
+
+    fn get_x(&self) -> Result<f64, DeviceError> {
+        self.get_axis(X_OFFSET)
+    }
+
+    fn get_y(&self) -> Result<f64, DeviceError> {
+        self.get_axis(Y_OFFSET)
+    }
+
+    fn get_z(&self) -> Result<f64, DeviceError> {
+        self.get_axis(Z_OFFSET)
+    }
+
+    /// Get the acceleration along every axis, all from the same sample.
+    fn get_all(&self) -> Result<Acceleration, DeviceError> {
+        let mut data = [0; 6];
+        self.read_registers(DATA_REGISTER, &mut data)?;
+        Ok(Acceleration {
+            x: self.to_gs(&data[X_OFFSET as usize..]),
+            y: self.to_gs(&data[Y_OFFSET as usize..]),
+            z: self.to_gs(&data[Z_OFFSET as usize..]),
+        })
+    }
+}
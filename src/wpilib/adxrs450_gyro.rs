@@ -0,0 +1,129 @@
+use wpilib::hal_call::*;
+use wpilib::bus::DeviceError;
+use wpilib::gyro::Gyro;
+use wpilib::spi::{SpiAccumulatorConfig, SpiError, SpiInterface, SpiMode, SpiPort};
+use wpilib::usage::*;
+use std::{thread, time};
+
+/// The time between samples taken by the FPGA, in seconds.
+const SAMPLE_PERIOD: f64 = 0.0005;
+
+/// How long to average the rate over when calibrating, in seconds.
+const CALIBRATION_SAMPLE_TIME: f64 = 5.0;
+
+/// The sensitivity of the gyro.
+const DEGREES_PER_SECOND_PER_LSB: f64 = 0.0125;
+
+/// The register holding the part ID, and the ID an ADXRS450 reports in its high byte.
+const PART_ID_REGISTER: u32 = 0x0c;
+const PART_ID: u32 = 0x5200;
+
+/// An ADXRS450 gyro on the SPI bus, such as the one in the kit of parts. The FPGA reads the rate
+/// every half millisecond and integrates it, so the angle stays accurate however slowly robot code
+/// runs.
+///
+/// # Usage
+/// ```
+/// # use wpilib::*;
+/// # sim::spi(SpiPort::Onboard0).queue_response(&[0x00, 0x0a, 0x40, 0x00]);
+/// // Calibration blocks for several seconds, so create the gyro in robot_init while the robot is
+/// // disabled.
+/// let mut gyro = ADXRS450Gyro::new(SpiPort::Onboard0).unwrap();
+/// # for _ in 0..2000 {
+/// #     sim::spi(SpiPort::Onboard0).accumulate(8000);
+/// # }
+///
+/// let heading = gyro.get_angle().unwrap();
+/// # assert!((heading - 100.0).abs() < 1e-9);
+/// gyro.reset().unwrap();
+/// ```
+pub struct ADXRS450Gyro {
+    spi: SpiInterface,
+    port: SpiPort,
+    center: i32,
+}
+
+impl ADXRS450Gyro {
+    /// Create a new gyro on the specified port and calibrate it, returning an error if
+    /// initialization fails, or `DeviceError::WrongDevice` if there is no ADXRS450 on the port.
+    pub fn new(port: SpiPort) -> Result<ADXRS450Gyro, DeviceError> {
+        let mut spi = SpiInterface::new(port)?;
+        spi.set_clock_rate(3_000_000);
+        spi.set_mode(SpiMode::Mode0);
+
+        let mut gyro = ADXRS450Gyro {
+            spi,
+            port,
+            center: 0,
+        };
+        if gyro.read_register(PART_ID_REGISTER)? & 0xff00 != PART_ID {
+            return Err(DeviceError::WrongDevice);
+        }
+
+        // Ask for the rate, and accept only responses flagged as valid sensor data.
+        gyro.spi
+            .init_accumulator(&SpiAccumulatorConfig {
+                                  period: SAMPLE_PERIOD,
+                                  command: 0x2000_0000,
+                                  transfer_size: 4,
+                                  valid_mask: 0x0c00_000e,
+                                  valid_value: 0x0400_0000,
+                                  data_shift: 10,
+                                  data_size: 16,
+                                  is_signed: true,
+                                  big_endian: true,
+                              })?;
+        gyro.calibrate()?;
+
+        report_usage(ResourceType::ADXRS450, port as i32);
+        Ok(gyro)
+    }
+
+    /// Get the port this gyro is on.
+    pub fn get_port(&self) -> SpiPort {
+        self.port
+    }
+
+    /// Read a 16 bit register.
+    fn read_register(&mut self, register: u32) -> Result<u32, SpiError> {
+        let mut command = 0x8000_0000 | register << 17;
+        // Commands have odd parity.
+        if command.count_ones() & 1 == 0 {
+            command |= 1;
+        }
+
+        let mut received = [0; 4];
+        self.spi.transaction(&command.to_be_bytes(), &mut received)?;
+        Ok(u32::from_be_bytes(received) >> 5 & 0xffff)
+    }
+}
+
+impl Gyro for ADXRS450Gyro {
+    type Error = HalError;
+
+    fn calibrate(&mut self) -> HalResult<()> {
+        // Let the gyro settle before sampling it.
+        thread::sleep(time::Duration::from_millis(100));
+
+        self.spi.set_accumulator_center(0)?;
+        self.spi.reset_accumulator()?;
+        thread::sleep(time::Duration::from_millis((CALIBRATION_SAMPLE_TIME * 1000.0) as u64));
+
+        self.center = self.spi.get_accumulator_average()? as i32;
+        self.spi.set_accumulator_center(self.center)?;
+        self.spi.reset_accumulator()
+    }
+
+    fn reset(&mut self) -> HalResult<()> {
+        self.spi.reset_accumulator()
+    }
+
+    fn get_angle(&self) -> HalResult<f64> {
+        Ok(self.spi.get_accumulator_value()? as f64 * DEGREES_PER_SECOND_PER_LSB * SAMPLE_PERIOD)
+    }
+
+    fn get_rate(&self) -> HalResult<f64> {
+        let rate = self.spi.get_accumulator_last_value()? - self.center;
+        Ok(rate as f64 * DEGREES_PER_SECOND_PER_LSB)
+    }
+}
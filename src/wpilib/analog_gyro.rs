@@ -0,0 +1,127 @@
+use wpilib::wpilib_hal::*;
+use wpilib::hal_call::*;
+use wpilib::analog_input::AnalogInput;
+use wpilib::gyro::Gyro;
+use wpilib::usage::*;
+
+/// The sensitivity of the gyros in the kit of parts.
+const DEFAULT_VOLTS_PER_DEGREE_PER_SECOND: f64 = 0.007;
+
+/// An analog gyro, such as the ADXRS652 or ADW22307, integrated by an accumulator in the FPGA.
+/// Only the analog inputs with an accumulator, channels 0 and 1, can be used.
+///
+/// # Usage
+/// ```
+/// # use wpilib::*;
+/// // Calibration blocks, so create the gyro in robot_init while the robot is disabled.
+/// let mut gyro = AnalogGyro::new(0).unwrap();
+/// # sim::analog_gyro(0).set_angle(-90.0);
+///
+/// let heading = gyro.get_angle().unwrap();
+/// # assert_eq!(heading, -90.0);
+/// gyro.reset().unwrap();
+/// ```
+pub struct AnalogGyro {
+    input: AnalogInput,
+    handle: HAL_GyroHandle,
+}
+
+impl AnalogGyro {
+    /// Create a new gyro on the specified channel and calibrate it, returning an error if
+    /// initialization fails.
+    pub fn new(channel: i32) -> HalResult<AnalogGyro> {
+        AnalogGyro::with_input(AnalogInput::new(channel)?)
+    }
+
+    /// Create a new gyro on an existing analog input and calibrate it, returning an error if
+    /// initialization fails or the input has no accumulator.
+    pub fn with_input(input: AnalogInput) -> HalResult<AnalogGyro> {
+        let mut gyro = AnalogGyro::initialize(input)?;
+        gyro.calibrate()?;
+        Ok(gyro)
+    }
+
+    /// Create a new gyro on an existing analog input using a center and offset from an earlier
+    /// calibration, instead of calibrating again. See `get_center` and `get_offset`.
+    pub fn with_calibration(input: AnalogInput, center: i32, offset: f64) -> HalResult<AnalogGyro> {
+        let mut gyro = AnalogGyro::initialize(input)?;
+        hal_call!(HAL_SetAnalogGyroParameters(gyro.handle,
+                                              DEFAULT_VOLTS_PER_DEGREE_PER_SECOND,
+                                              offset,
+                                              center))?;
+        gyro.reset()?;
+        Ok(gyro)
+    }
+
+    fn initialize(input: AnalogInput) -> HalResult<AnalogGyro> {
+        if !input.is_accumulator_channel()? {
+            return Err(HalError(0));
+        }
+
+        let handle = hal_call!(HAL_InitializeAnalogGyro(input.get_handle()))?;
+        let gyro = AnalogGyro {
+            input,
+            handle,
+        };
+        hal_call!(HAL_SetupAnalogGyro(gyro.handle))?;
+
+        report_usage(ResourceType::Gyro, gyro.input.get_channel());
+        Ok(gyro)
+    }
+
+    /// Set the sensitivity of the gyro, in volts per degree per second. This only needs to be
+    /// changed for gyros other than those in the kit of parts.
+    pub fn set_sensitivity(&mut self, volts_per_degree_per_second: f64) -> HalResult<()> {
+        hal_call!(HAL_SetAnalogGyroVoltsPerDegreePerSecond(self.handle,
+                                                           volts_per_degree_per_second))
+    }
+
+    /// Ignore rotation while the input is within `volts` of the center. This reduces drift at the
+    /// cost of missing very slow rotation.
+    pub fn set_deadband(&mut self, volts: f64) -> HalResult<()> {
+        hal_call!(HAL_SetAnalogGyroDeadband(self.handle, volts))
+    }
+
+    /// Get the drift measured by the last calibration, in raw accumulator units per sample.
+    pub fn get_offset(&self) -> HalResult<f64> {
+        hal_call!(HAL_GetAnalogGyroOffset(self.handle))
+    }
+
+    /// Get the raw value of the input while still, as measured by the last calibration.
+    pub fn get_center(&self) -> HalResult<i32> {
+        hal_call!(HAL_GetAnalogGyroCenter(self.handle))
+    }
+
+    /// Get the analog input this gyro reads.
+    pub fn get_input(&self) -> &AnalogInput {
+        &self.input
+    }
+}
+
+impl Gyro for AnalogGyro {
+    type Error = HalError;
+
+    fn calibrate(&mut self) -> HalResult<()> {
+        hal_call!(HAL_CalibrateAnalogGyro(self.handle))
+    }
+
+    fn reset(&mut self) -> HalResult<()> {
+        hal_call!(HAL_ResetAnalogGyro(self.handle))
+    }
+
+    fn get_angle(&self) -> HalResult<f64> {
+        hal_call!(HAL_GetAnalogGyroAngle(self.handle))
+    }
+
+    fn get_rate(&self) -> HalResult<f64> {
+        hal_call!(HAL_GetAnalogGyroRate(self.handle))
+    }
+}
+
+impl Drop for AnalogGyro {
+    fn drop(&mut self) {
+        unsafe {
+            Hal::HAL_FreeAnalogGyro(self.handle);
+        }
+    }
+}
@@ -19,12 +19,12 @@ impl AnalogInput {
             return Err(HalError(0));
         }
 
-        let port = hal_call!(HAL_InitializeAnalogInputPort(HAL_GetPort(channel)))?;
+        let port = hal_call!(HAL_InitializeAnalogInputPort(Hal::HAL_GetPort(channel)))?;
 
         report_usage(ResourceType::AnalogChannel, channel);
 
         Ok(AnalogInput {
-            channel: channel,
+            channel,
             handle: port,
             accumulator_offset: 0,
         })
@@ -67,6 +67,11 @@ impl AnalogInput {
         self.channel
     }
 
+    /// Get a handle to this analog input.
+    pub fn get_handle(&self) -> HAL_AnalogInputHandle {
+        self.handle
+    }
+
     /// Set the number of bits to use in averaging. Averaging will sample 2^bits actual reads.
     pub fn set_average_bits(&mut self, bits: i32) -> HalResult<()> {
         hal_call!(HAL_SetAnalogAverageBits(self.handle, bits))
@@ -107,7 +112,7 @@ impl AnalogInput {
     pub fn reset_accumulator(&mut self) -> HalResult<()> {
         hal_call!(HAL_ResetAccumulator(self.handle))?;
 
-        let sample_time = 1f64 / AnalogInput::get_sample_rate()? as f64;
+        let sample_time = 1f64 / AnalogInput::get_sample_rate()?;
         let over_samples = 1 << self.get_oversample_bits()?;
         let average_samples = 1 << self.get_average_bits()?;
         thread::sleep(time::Duration::from_millis(
@@ -157,7 +162,7 @@ impl AnalogInput {
 impl Drop for AnalogInput {
     fn drop(&mut self) {
         unsafe {
-            HAL_FreeAnalogInputPort(self.handle);
+            Hal::HAL_FreeAnalogInputPort(self.handle);
         }
     }
 }
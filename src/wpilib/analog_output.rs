@@ -0,0 +1,52 @@
+use wpilib::wpilib_hal::*;
+use wpilib::hal_call::*;
+use wpilib::sensor;
+use wpilib::usage::*;
+
+/// One of the analog outputs on the MXP port, which can drive 0-5V.
+pub struct AnalogOutput {
+    channel: i32,
+    handle: HAL_AnalogOutputHandle,
+}
+
+impl AnalogOutput {
+    /// Create a new analog output on the specified channel, returning an error if initialization
+    /// fails.
+    pub fn new(channel: i32) -> HalResult<AnalogOutput> {
+        if !sensor::check_analog_output_channel(channel) {
+            return Err(HalError(0));
+        }
+
+        let handle = hal_call!(HAL_InitializeAnalogOutputPort(Hal::HAL_GetPort(channel)))?;
+
+        report_usage(ResourceType::AnalogOutput, channel);
+
+        Ok(AnalogOutput {
+            channel,
+            handle,
+        })
+    }
+
+    /// Set the output voltage. Values outside 0-5V are clamped.
+    pub fn set_voltage(&mut self, voltage: f64) -> HalResult<()> {
+        hal_call!(HAL_SetAnalogOutput(self.handle, voltage))
+    }
+
+    /// Get the voltage last written to the output.
+    pub fn get_voltage(&self) -> HalResult<f64> {
+        hal_call!(HAL_GetAnalogOutput(self.handle))
+    }
+
+    /// Get the channel for this analog output.
+    pub fn get_channel(&self) -> i32 {
+        self.channel
+    }
+}
+
+impl Drop for AnalogOutput {
+    fn drop(&mut self) {
+        unsafe {
+            Hal::HAL_FreeAnalogOutputPort(self.handle);
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use wpilib::hal_call::*;
+use wpilib::analog_input::AnalogInput;
+use wpilib::robot_state;
+
+/// A potentiometer read by an analog input, scaled to a useful range such as degrees.
+///
+/// The potentiometer should be wired across the 5V rail, so its output is a fraction of the rail
+/// voltage. Readings are divided by the measured rail voltage, so a sagging rail does not change
+/// them.
+///
+/// # Usage
+/// ```
+/// # use wpilib::*;
+/// // A 10 turn potentiometer on the arm pivot, which reads 90 degrees at 0V.
+/// let arm = AnalogPotentiometer::new(0, 3600.0, 90.0).unwrap();
+/// # sim::analog_in(0).set_voltage(0.25);
+///
+/// let degrees = arm.get().unwrap();
+/// # assert!((degrees - 270.0).abs() < 1e-9);
+/// ```
+pub struct AnalogPotentiometer {
+    input: AnalogInput,
+    full_range: f64,
+    offset: f64,
+}
+
+impl AnalogPotentiometer {
+    /// Create a new potentiometer on the specified analog input channel, returning an error if
+    /// initialization fails. `full_range` is the change in the value across the whole travel of
+    /// the potentiometer, and `offset` is the value at 0V.
+    pub fn new(channel: i32, full_range: f64, offset: f64) -> HalResult<AnalogPotentiometer> {
+        Ok(AnalogPotentiometer::with_input(AnalogInput::new(channel)?, full_range, offset))
+    }
+
+    /// Create a new potentiometer on an existing analog input.
+    pub fn with_input(input: AnalogInput, full_range: f64, offset: f64) -> AnalogPotentiometer {
+        AnalogPotentiometer {
+            input,
+            full_range,
+            offset,
+        }
+    }
+
+    /// Get the scaled value of the potentiometer. This uses the averaged voltage, to smooth out
+    /// noise. Returns an error if the 5V rail is off, as it is during a brownout, since the
+    /// potentiometer has no reading then.
+    pub fn get(&self) -> HalResult<f64> {
+        let rail_voltage = robot_state::get_user_voltage_5v()?;
+        if rail_voltage <= 0.0 {
+            return Err(HalError(0));
+        }
+        let fraction = self.input.get_average_voltage()? / rail_voltage;
+        Ok(fraction * self.full_range + self.offset)
+    }
+
+    /// Get the analog input this potentiometer reads.
+    pub fn get_input(&self) -> &AnalogInput {
+        &self.input
+    }
+}
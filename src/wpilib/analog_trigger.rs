@@ -0,0 +1,183 @@
+use wpilib::wpilib_hal::*;
+use wpilib::hal_call::*;
+use wpilib::analog_input::AnalogInput;
+use wpilib::digital_source::DigitalSource;
+use wpilib::usage::*;
+use std::ptr;
+use std::sync::Arc;
+
+/// An output of an analog trigger.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AnalogTriggerType {
+    /// True while the input is between the lower and upper limits.
+    InWindow,
+    /// True once the input rises above the upper limit, until it falls below the lower limit.
+    State,
+    /// Pulses when the input jumps straight across the window, as an absolute sensor does when it
+    /// rolls over. The rising and falling pulses are for opposite directions of the jump. This can
+    /// only be routed to a counter, encoder or interrupt, not read directly.
+    RisingPulse,
+    /// Pulses when the input jumps straight across the window in the opposite direction to
+    /// `RisingPulse`. This can only be routed to a counter, encoder or interrupt, not read
+    /// directly.
+    FallingPulse,
+}
+
+impl AnalogTriggerType {
+    fn into_hal(self) -> HAL_AnalogTriggerType {
+        match self {
+            AnalogTriggerType::InWindow => HAL_AnalogTriggerType::HAL_Trigger_kInWindow,
+            AnalogTriggerType::State => HAL_AnalogTriggerType::HAL_Trigger_kState,
+            AnalogTriggerType::RisingPulse => HAL_AnalogTriggerType::HAL_Trigger_kRisingPulse,
+            AnalogTriggerType::FallingPulse => HAL_AnalogTriggerType::HAL_Trigger_kFallingPulse,
+        }
+    }
+}
+
+/// Compares an analog input against a window in the FPGA, producing digital outputs that can be
+/// read directly or fed to a counter, encoder or interrupt.
+///
+/// # Usage
+/// ```
+/// # use wpilib::*;
+/// use std::sync::Arc;
+///
+/// // Count the turns of an absolute analog encoder by catching it rolling over.
+/// let mut trigger = AnalogTrigger::from_channel(0).unwrap();
+/// trigger.set_limits_voltage(1.0, 4.0).unwrap();
+/// let trigger = Arc::new(trigger);
+///
+/// let mut turns = Counter::new(CounterMode::TwoPulse).unwrap();
+/// turns.set_up_source(AnalogTriggerOutput::new(trigger.clone(), AnalogTriggerType::RisingPulse))
+///     .unwrap();
+/// turns.set_down_source(AnalogTriggerOutput::new(trigger.clone(),
+///                                                AnalogTriggerType::FallingPulse))
+///     .unwrap();
+/// ```
+pub struct AnalogTrigger {
+    input: AnalogInput,
+    index: i32,
+    handle: HAL_AnalogTriggerHandle,
+}
+
+impl AnalogTrigger {
+    /// Create a new analog trigger on an existing analog input, returning an error if
+    /// initialization fails.
+    pub fn new(input: AnalogInput) -> HalResult<AnalogTrigger> {
+        let mut index = 0;
+        let handle = hal_call!(HAL_InitializeAnalogTrigger(input.get_handle(), &mut index))?;
+
+        report_usage(ResourceType::AnalogTrigger, input.get_channel());
+
+        Ok(AnalogTrigger {
+            input,
+            index,
+            handle,
+        })
+    }
+
+    /// Create a new analog trigger on a new analog input on the specified channel, returning an
+    /// error if initialization fails.
+    pub fn from_channel(channel: i32) -> HalResult<AnalogTrigger> {
+        AnalogTrigger::new(AnalogInput::new(channel)?)
+    }
+
+    /// Set the limits of the window as raw values of the analog input.
+    pub fn set_limits_raw(&mut self, lower: i32, upper: i32) -> HalResult<()> {
+        hal_call!(HAL_SetAnalogTriggerLimitsRaw(self.handle, lower, upper))
+    }
+
+    /// Set the limits of the window in volts.
+    pub fn set_limits_voltage(&mut self, lower: f64, upper: f64) -> HalResult<()> {
+        hal_call!(HAL_SetAnalogTriggerLimitsVoltage(self.handle, lower, upper))
+    }
+
+    /// Compare the averaged value of the input instead of the raw value. This cannot be used
+    /// together with filtering.
+    pub fn set_averaged(&mut self, use_averaged_value: bool) -> HalResult<()> {
+        hal_call!(HAL_SetAnalogTriggerAveraged(self.handle, use_averaged_value as i32))
+    }
+
+    /// Compare a filtered value of the input, which rejects single-sample glitches, instead of
+    /// the raw value. This cannot be used together with averaging.
+    pub fn set_filtered(&mut self, use_filtered_value: bool) -> HalResult<()> {
+        hal_call!(HAL_SetAnalogTriggerFiltered(self.handle, use_filtered_value as i32))
+    }
+
+    /// Is the input between the lower and upper limits?
+    pub fn get_in_window(&self) -> HalResult<bool> {
+        Ok(hal_call!(HAL_GetAnalogTriggerInWindow(self.handle))? != 0)
+    }
+
+    /// Get the trigger state, which turns on when the input rises above the upper limit and off
+    /// when it falls below the lower limit.
+    pub fn get_trigger_state(&self) -> HalResult<bool> {
+        Ok(hal_call!(HAL_GetAnalogTriggerTriggerState(self.handle))? != 0)
+    }
+
+    /// Get the FPGA index of this trigger.
+    pub fn get_index(&self) -> i32 {
+        self.index
+    }
+
+    /// Get the analog input this trigger watches.
+    pub fn get_input(&self) -> &AnalogInput {
+        &self.input
+    }
+}
+
+impl Drop for AnalogTrigger {
+    fn drop(&mut self) {
+        let _ = hal_call!(HAL_CleanAnalogTrigger(self.handle));
+    }
+}
+
+/// One output of an analog trigger, usable as a digital source.
+pub struct AnalogTriggerOutput {
+    trigger: Arc<AnalogTrigger>,
+    output_type: AnalogTriggerType,
+}
+
+impl AnalogTriggerOutput {
+    /// Create an output of a trigger. The trigger stays alive as long as any of its outputs do.
+    pub fn new(trigger: Arc<AnalogTrigger>, output_type: AnalogTriggerType) -> AnalogTriggerOutput {
+        report_usage_extras(ResourceType::AnalogTriggerOutput,
+                            trigger.index,
+                            output_type as i32,
+                            ptr::null());
+        AnalogTriggerOutput {
+            trigger,
+            output_type,
+        }
+    }
+
+    /// Read the output. This fails for the pulse outputs, which can only be routed.
+    pub fn get(&self) -> HalResult<bool> {
+        Ok(hal_call!(HAL_GetAnalogTriggerOutput(self.trigger.handle,
+                                                self.output_type.into_hal()))? != 0)
+    }
+
+    /// Get which output of the trigger this is.
+    pub fn get_type(&self) -> AnalogTriggerType {
+        self.output_type
+    }
+
+    /// Get the trigger this is an output of.
+    pub fn get_trigger(&self) -> &Arc<AnalogTrigger> {
+        &self.trigger
+    }
+}
+
+impl DigitalSource for AnalogTriggerOutput {
+    fn get_handle_for_routing(&self) -> HAL_Handle {
+        self.trigger.handle
+    }
+
+    fn get_analog_trigger_type_for_routing(&self) -> HAL_AnalogTriggerType {
+        self.output_type.into_hal()
+    }
+
+    fn is_analog_trigger(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,64 @@
+use wpilib::hal_call::*;
+use wpilib::analog_input::AnalogInput;
+
+/// An ultrasonic rangefinder with an analog output proportional to range, such as the MaxBotix
+/// sensors.
+///
+/// The scale depends on the sensor and its supply voltage. For example, the LV-MaxSonar-EZ
+/// series outputs Vcc/512 per inch, which is about 0.0098 volts per inch on a 5V supply.
+///
+/// # Usage
+/// ```
+/// # use wpilib::*;
+/// let rangefinder = AnalogUltrasonic::new(2, 5.0 / 512.0).unwrap();
+/// # sim::analog_in(2).set_voltage(0.5);
+///
+/// let inches = rangefinder.get_range_inches().unwrap();
+/// # assert!((inches - 51.2).abs() < 1e-9);
+/// ```
+pub struct AnalogUltrasonic {
+    input: AnalogInput,
+    volts_per_inch: f64,
+}
+
+impl AnalogUltrasonic {
+    /// Create a new sensor on the specified analog input channel with the given scale, returning
+    /// an error if initialization fails.
+    pub fn new(channel: i32, volts_per_inch: f64) -> HalResult<AnalogUltrasonic> {
+        Ok(AnalogUltrasonic::with_input(AnalogInput::new(channel)?, volts_per_inch))
+    }
+
+    /// Create a new sensor on an existing analog input with the given scale.
+    pub fn with_input(input: AnalogInput, volts_per_inch: f64) -> AnalogUltrasonic {
+        AnalogUltrasonic {
+            input,
+            volts_per_inch,
+        }
+    }
+
+    /// Set the scale of the sensor, in volts per inch.
+    pub fn set_volts_per_inch(&mut self, volts_per_inch: f64) {
+        self.volts_per_inch = volts_per_inch;
+    }
+
+    /// Get the scale of the sensor, in volts per inch.
+    pub fn get_volts_per_inch(&self) -> f64 {
+        self.volts_per_inch
+    }
+
+    /// Get the range to the nearest object, in inches. This uses the averaged voltage, to smooth
+    /// out noise.
+    pub fn get_range_inches(&self) -> HalResult<f64> {
+        Ok(self.input.get_average_voltage()? / self.volts_per_inch)
+    }
+
+    /// Get the range to the nearest object, in millimeters.
+    pub fn get_range_mm(&self) -> HalResult<f64> {
+        Ok(self.get_range_inches()? * 25.4)
+    }
+
+    /// Get the analog input this sensor reads.
+    pub fn get_input(&self) -> &AnalogInput {
+        &self.input
+    }
+}
@@ -0,0 +1,66 @@
+use wpilib::wpilib_hal::*;
+use wpilib::hal_call::*;
+use wpilib::accelerometer::*;
+use wpilib::usage::*;
+use std::ffi;
+
+/// The accelerometer built into the roboRIO. The axes are marked on the roboRIO's case. It measures
+/// up to 8g.
+///
+/// # Usage
+/// ```
+/// # use wpilib::*;
+/// let accelerometer = BuiltInAccelerometer::new(AccelerometerRange::EightG).unwrap();
+/// # sim::roborio().set_acceleration(0.2, -3.5, 1.0);
+///
+/// let acceleration = accelerometer.get_all().unwrap();
+/// if acceleration.x.hypot(acceleration.y) > 3.0 {
+///     DriverStation::instance().report_throttled(false, "Collision detected");
+/// }
+/// ```
+pub struct BuiltInAccelerometer {
+    _private: (),
+}
+
+impl BuiltInAccelerometer {
+    /// Start the built-in accelerometer with the given range.
+    pub fn new(range: AccelerometerRange) -> HalResult<BuiltInAccelerometer> {
+        let mut accelerometer = BuiltInAccelerometer { _private: () };
+        accelerometer.set_range(range)?;
+
+        let feature = ffi::CString::new("Built-in accelerometer").unwrap();
+        report_usage_extras(ResourceType::Accelerometer, 0, 0, feature.as_ptr());
+        Ok(accelerometer)
+    }
+}
+
+impl Accelerometer for BuiltInAccelerometer {
+    type Error = HalError;
+
+    fn set_range(&mut self, range: AccelerometerRange) -> HalResult<()> {
+        let range = match range {
+            AccelerometerRange::TwoG => HAL_AccelerometerRange::HAL_AccelerometerRange_k2G,
+            AccelerometerRange::FourG => HAL_AccelerometerRange::HAL_AccelerometerRange_k4G,
+            AccelerometerRange::EightG => HAL_AccelerometerRange::HAL_AccelerometerRange_k8G,
+        };
+        // The range can only be changed while the accelerometer is inactive.
+        unsafe {
+            Hal::HAL_SetAccelerometerActive(false as i32);
+            Hal::HAL_SetAccelerometerRange(range);
+            Hal::HAL_SetAccelerometerActive(true as i32);
+        }
+        Ok(())
+    }
+
+    fn get_x(&self) -> HalResult<f64> {
+        Ok(unsafe { Hal::HAL_GetAccelerometerX() })
+    }
+
+    fn get_y(&self) -> HalResult<f64> {
+        Ok(unsafe { Hal::HAL_GetAccelerometerY() })
+    }
+
+    fn get_z(&self) -> HalResult<f64> {
+        Ok(unsafe { Hal::HAL_GetAccelerometerZ() })
+    }
+}
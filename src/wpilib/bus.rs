@@ -0,0 +1,86 @@
+use wpilib::hal_call::*;
+use wpilib::i2c::I2cError;
+use wpilib::spi::{SpiError, SpiInterface};
+use std::{error, fmt, io};
+
+/// An error from a device driver that talks to its device over a bus.
+#[derive(Debug)]
+pub enum DeviceError {
+    /// A HAL call failed, for example while opening the port.
+    Hal(HalError),
+    /// An SPI transfer failed.
+    Spi(SpiError),
+    /// An I2C transfer failed.
+    I2c(I2cError),
+    /// Reading from or writing to a serial port failed.
+    Serial(io::Error),
+    /// The device did not identify itself as the expected part, so there is a different device
+    /// or none at all on the bus.
+    WrongDevice,
+    /// A response from the device failed its checksum.
+    Checksum,
+    /// An argument was outside the range the device supports.
+    OutOfRange,
+    /// The device did not become ready in time.
+    Timeout,
+}
+
+impl fmt::Display for DeviceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DeviceError::Hal(ref error) => write!(f, "{:?}", error),
+            DeviceError::Spi(ref error) => write!(f, "{}", error),
+            DeviceError::I2c(ref error) => write!(f, "{}", error),
+            DeviceError::Serial(ref error) => write!(f, "serial port error: {}", error),
+            DeviceError::WrongDevice => write!(f, "unexpected device on the bus"),
+            DeviceError::Checksum => write!(f, "device response failed its checksum"),
+            DeviceError::OutOfRange => write!(f, "argument out of range for the device"),
+            DeviceError::Timeout => write!(f, "device timed out"),
+        }
+    }
+}
+
+impl error::Error for DeviceError {}
+
+impl From<HalError> for DeviceError {
+    fn from(error: HalError) -> DeviceError {
+        DeviceError::Hal(error)
+    }
+}
+
+impl From<SpiError> for DeviceError {
+    fn from(error: SpiError) -> DeviceError {
+        DeviceError::Spi(error)
+    }
+}
+
+impl From<I2cError> for DeviceError {
+    fn from(error: I2cError) -> DeviceError {
+        DeviceError::I2c(error)
+    }
+}
+
+impl From<io::Error> for DeviceError {
+    fn from(error: io::Error) -> DeviceError {
+        DeviceError::Serial(error)
+    }
+}
+
+/// Send `header` followed by `data.len()` padding bytes, and fill `data` with the bytes received
+/// after the header. This reads registers from devices that expect a command, then clock out
+/// their data.
+pub fn spi_read(spi: &mut SpiInterface, header: &[u8], data: &mut [u8]) -> Result<(), SpiError> {
+    let mut send = vec![0; header.len() + data.len()];
+    send[..header.len()].copy_from_slice(header);
+    let mut received = vec![0; send.len()];
+    spi.transaction(&send, &mut received)?;
+    data.copy_from_slice(&received[header.len()..]);
+    Ok(())
+}
+
+/// Send `data` in a single transfer, ignoring the bytes received.
+pub fn spi_write(spi: &mut SpiInterface, data: &[u8]) -> Result<(), SpiError> {
+    let mut received = vec![0; data.len()];
+    spi.transaction(data, &mut received)?;
+    Ok(())
+}
@@ -0,0 +1,627 @@
+use wpilib::driverstation::{DriverStation, RobotState};
+use wpilib::Timer;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Identifies a subsystem registered with a `Scheduler`. Commands list the subsystems they require,
+/// and only one command may require a subsystem at a time.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SubsystemId(usize);
+
+/// Identifies a command added to a `Scheduler`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct CommandId(usize);
+
+/// A part of the robot, like a drivetrain or an arm, that only one command can control at a time.
+pub trait Subsystem {
+    /// Called on every run of the scheduler, before any commands are executed.
+    fn periodic(&mut self) {}
+}
+
+/// An action the robot can take. Once scheduled, `initialize` is called once, then `execute` is
+/// called on every run of the scheduler until `is_finished` returns true or the command is
+/// interrupted, after which `end` is called.
+pub trait Command {
+    /// Called once when the command is scheduled.
+    fn initialize(&mut self) {}
+
+    /// Called on every run of the scheduler while the command is scheduled.
+    fn execute(&mut self) {}
+
+    /// Whether the command has finished. Checked after every call to `execute`.
+    fn is_finished(&mut self) -> bool {
+        false
+    }
+
+    /// Called once when the command ends. `interrupted` is true if the command was cancelled or
+    /// replaced by another command instead of finishing.
+    fn end(&mut self, _interrupted: bool) {}
+
+    /// The subsystems this command requires.
+    fn requirements(&self) -> Vec<SubsystemId> {
+        Vec::new()
+    }
+
+    /// Whether the command should keep running while the robot is disabled.
+    fn runs_when_disabled(&self) -> bool {
+        false
+    }
+}
+
+/// When a binding should act on its condition.
+enum BindingKind {
+    WhenPressed,
+    WhenReleased,
+    WhileHeld,
+}
+
+/// A command triggered by a condition, like a joystick button.
+struct Binding {
+    kind: BindingKind,
+    condition: Box<dyn FnMut() -> bool>,
+    command: CommandId,
+    last: bool,
+}
+
+/// Runs commands, making sure that no two running commands require the same subsystem. Call `run`
+/// on every iteration of the robot's main loop, for example from
+/// `IterativeRobot::robot_periodic`.
+///
+/// # Usage
+/// ```no_run
+/// use wpilib::*;
+/// use wpilib::command::*;
+/// use std::cell::RefCell;
+/// use std::rc::Rc;
+///
+/// struct Arm {
+///     motor: PwmSpeedController,
+/// }
+///
+/// impl Subsystem for Arm {}
+///
+/// struct Raise {
+///     arm: Rc<RefCell<Arm>>,
+///     requirement: SubsystemId,
+/// }
+///
+/// impl Command for Raise {
+///     fn execute(&mut self) {
+///         self.arm.borrow_mut().motor.set(0.5);
+///     }
+///
+///     fn end(&mut self, _interrupted: bool) {
+///         self.arm.borrow_mut().motor.set(0.0);
+///     }
+///
+///     fn requirements(&self) -> Vec<SubsystemId> {
+///         vec![self.requirement]
+///     }
+/// }
+///
+/// let mut scheduler = Scheduler::new();
+/// let motor = PwmSpeedController::victor_sp(0, false).unwrap();
+/// let arm = Rc::new(RefCell::new(Arm { motor }));
+/// let arm_id = scheduler.register_subsystem(arm.clone());
+///
+/// let raise = scheduler.add(Raise { arm: arm.clone(), requirement: arm_id });
+/// let mut xbox = XBoxController::new(0);
+/// scheduler.while_held(move || xbox.get_a_button().unwrap_or(false), raise);
+///
+/// loop {
+///     DriverStation::instance().wait_for_data();
+///     scheduler.run();
+/// }
+/// ```
+#[derive(Default)]
+pub struct Scheduler {
+    subsystems: Vec<Rc<RefCell<dyn Subsystem>>>,
+    default_commands: HashMap<SubsystemId, CommandId>,
+    commands: Vec<Box<dyn Command>>,
+    bindings: Vec<Binding>,
+
+    scheduled: Vec<CommandId>,
+    requirements: HashMap<SubsystemId, CommandId>,
+}
+
+impl Scheduler {
+    /// Create a scheduler with no subsystems or commands.
+    pub fn new() -> Scheduler {
+        Scheduler::default()
+    }
+
+    /// Register a subsystem, returning the id commands should use to require it. The subsystem's
+    /// `periodic` function will be called on every run.
+    pub fn register_subsystem<S>(&mut self, subsystem: Rc<RefCell<S>>) -> SubsystemId
+        where S: Subsystem + 'static
+    {
+        self.subsystems.push(subsystem);
+        SubsystemId(self.subsystems.len() - 1)
+    }
+
+    /// Add a command to the scheduler so that it can be scheduled or bound to a trigger. The
+    /// command does not run until it is scheduled.
+    pub fn add<C>(&mut self, command: C) -> CommandId
+        where C: Command + 'static
+    {
+        self.commands.push(Box::new(command));
+        CommandId(self.commands.len() - 1)
+    }
+
+    /// Set the command that runs on a subsystem whenever no other command requires it. The command
+    /// must require the subsystem.
+    pub fn set_default_command(&mut self, subsystem: SubsystemId, command: CommandId) {
+        assert!(self.commands[command.0].requirements().contains(&subsystem),
+                "a default command must require its subsystem");
+        self.default_commands.insert(subsystem, command);
+    }
+
+    /// Schedule a command when `condition` becomes true.
+    pub fn when_pressed<F>(&mut self, condition: F, command: CommandId)
+        where F: FnMut() -> bool + 'static
+    {
+        self.bind(BindingKind::WhenPressed, Box::new(condition), command);
+    }
+
+    /// Schedule a command when `condition` becomes false.
+    pub fn when_released<F>(&mut self, condition: F, command: CommandId)
+        where F: FnMut() -> bool + 'static
+    {
+        self.bind(BindingKind::WhenReleased, Box::new(condition), command);
+    }
+
+    /// Schedule a command when `condition` becomes true, and cancel it when `condition` becomes
+    /// false.
+    pub fn while_held<F>(&mut self, condition: F, command: CommandId)
+        where F: FnMut() -> bool + 'static
+    {
+        self.bind(BindingKind::WhileHeld, Box::new(condition), command);
+    }
+
+    fn bind(&mut self, kind: BindingKind, condition: Box<dyn FnMut() -> bool>, command: CommandId) {
+        self.bindings.push(Binding {
+            kind,
+            condition,
+            command,
+            last: false,
+        });
+    }
+
+    fn is_disabled() -> bool {
+        matches!(DriverStation::instance().get_state(),
+                 RobotState::Disabled | RobotState::EStop)
+    }
+
+    /// Start running a command, interrupting any commands that require the same subsystems. Does
+    /// nothing if the command is already running, or if the robot is disabled and the command does
+    /// not run when disabled.
+    pub fn schedule(&mut self, command: CommandId) {
+        if self.is_scheduled(command) ||
+           (Scheduler::is_disabled() && !self.commands[command.0].runs_when_disabled()) {
+            return;
+        }
+
+        let requirements = self.commands[command.0].requirements();
+        for requirement in &requirements {
+            if let Some(&current) = self.requirements.get(requirement) {
+                self.end(current, true);
+            }
+        }
+        for requirement in requirements {
+            self.requirements.insert(requirement, command);
+        }
+
+        self.commands[command.0].initialize();
+        self.scheduled.push(command);
+    }
+
+    /// Stop a command if it is running, calling its `end` function.
+    pub fn cancel(&mut self, command: CommandId) {
+        if self.is_scheduled(command) {
+            self.end(command, true);
+        }
+    }
+
+    /// Stop every running command.
+    pub fn cancel_all(&mut self) {
+        while let Some(&command) = self.scheduled.first() {
+            self.end(command, true);
+        }
+    }
+
+    /// Is a command running?
+    pub fn is_scheduled(&self, command: CommandId) -> bool {
+        self.scheduled.contains(&command)
+    }
+
+    /// Get the command currently requiring a subsystem, if any.
+    pub fn requiring(&self, subsystem: SubsystemId) -> Option<CommandId> {
+        self.requirements.get(&subsystem).cloned()
+    }
+
+    /// End a running command and release its requirements.
+    fn end(&mut self, command: CommandId, interrupted: bool) {
+        self.scheduled.retain(|&scheduled| scheduled != command);
+        self.requirements.retain(|_, &mut owner| owner != command);
+        self.commands[command.0].end(interrupted);
+    }
+
+    /// Run one iteration of the scheduler: run each subsystem's periodic function, check bindings,
+    /// execute every running command, and schedule default commands for idle subsystems.
+    pub fn run(&mut self) {
+        let disabled = Scheduler::is_disabled();
+        if disabled {
+            for command in self.scheduled.clone() {
+                if !self.commands[command.0].runs_when_disabled() {
+                    self.end(command, true);
+                }
+            }
+        }
+
+        for subsystem in &self.subsystems {
+            subsystem.borrow_mut().periodic();
+        }
+
+        for i in 0..self.bindings.len() {
+            let pressed = (self.bindings[i].condition)();
+            let last = self.bindings[i].last;
+            self.bindings[i].last = pressed;
+
+            let command = self.bindings[i].command;
+            match self.bindings[i].kind {
+                BindingKind::WhenPressed if pressed && !last => self.schedule(command),
+                BindingKind::WhenReleased if !pressed && last => self.schedule(command),
+                BindingKind::WhileHeld if pressed && !last => self.schedule(command),
+                BindingKind::WhileHeld if !pressed && last => self.cancel(command),
+                _ => {}
+            }
+        }
+
+        for command in self.scheduled.clone() {
+            // An earlier command may have cancelled this one.
+            if !self.is_scheduled(command) {
+                continue;
+            }
+            self.commands[command.0].execute();
+            if self.commands[command.0].is_finished() {
+                self.end(command, false);
+            }
+        }
+
+        for i in 0..self.subsystems.len() {
+            let subsystem = SubsystemId(i);
+            if self.requirements.contains_key(&subsystem) {
+                continue;
+            }
+            if let Some(&command) = self.default_commands.get(&subsystem) {
+                if !disabled || self.commands[command.0].runs_when_disabled() {
+                    self.schedule(command);
+                }
+            }
+        }
+    }
+}
+
+/// Collect the requirements of several commands.
+fn all_requirements(commands: &[Box<dyn Command>]) -> Vec<SubsystemId> {
+    let mut requirements = Vec::new();
+    for command in commands {
+        for requirement in command.requirements() {
+            if !requirements.contains(&requirement) {
+                requirements.push(requirement);
+            }
+        }
+    }
+    requirements
+}
+
+/// A command that runs a function once and finishes immediately.
+pub struct InstantCommand<F> {
+    action: F,
+    requirements: Vec<SubsystemId>,
+}
+
+impl<F: FnMut()> InstantCommand<F> {
+    /// Create a command that calls `action` when it is scheduled.
+    pub fn new(requirements: &[SubsystemId], action: F) -> InstantCommand<F> {
+        InstantCommand {
+            action,
+            requirements: requirements.to_vec(),
+        }
+    }
+}
+
+impl<F: FnMut()> Command for InstantCommand<F> {
+    fn initialize(&mut self) {
+        (self.action)();
+    }
+
+    fn is_finished(&mut self) -> bool {
+        true
+    }
+
+    fn requirements(&self) -> Vec<SubsystemId> {
+        self.requirements.clone()
+    }
+}
+
+/// A command that runs a function on every run of the scheduler and never finishes on its own.
+/// Useful as a default command.
+pub struct RunCommand<F> {
+    action: F,
+    requirements: Vec<SubsystemId>,
+}
+
+impl<F: FnMut()> RunCommand<F> {
+    /// Create a command that calls `action` every time it is executed.
+    pub fn new(requirements: &[SubsystemId], action: F) -> RunCommand<F> {
+        RunCommand {
+            action,
+            requirements: requirements.to_vec(),
+        }
+    }
+}
+
+impl<F: FnMut()> Command for RunCommand<F> {
+    fn execute(&mut self) {
+        (self.action)();
+    }
+
+    fn requirements(&self) -> Vec<SubsystemId> {
+        self.requirements.clone()
+    }
+}
+
+/// A command that does nothing for some time.
+pub struct WaitCommand {
+    timer: Timer,
+    seconds: f64,
+}
+
+impl WaitCommand {
+    /// Create a command that finishes `seconds` after it is scheduled.
+    pub fn new(seconds: f64) -> WaitCommand {
+        WaitCommand {
+            timer: Timer::new(),
+            seconds,
+        }
+    }
+}
+
+impl Command for WaitCommand {
+    fn initialize(&mut self) {
+        self.timer.start();
+    }
+
+    fn is_finished(&mut self) -> bool {
+        self.timer.has_period_passed(self.seconds)
+    }
+
+    fn end(&mut self, _interrupted: bool) {
+        self.timer.stop();
+    }
+
+    fn runs_when_disabled(&self) -> bool {
+        true
+    }
+}
+
+/// Runs commands one after another, finishing when the last one does.
+pub struct SequentialGroup {
+    commands: Vec<Box<dyn Command>>,
+    current: usize,
+}
+
+impl SequentialGroup {
+    /// Create a group that runs `commands` in order.
+    pub fn new(commands: Vec<Box<dyn Command>>) -> SequentialGroup {
+        SequentialGroup {
+            commands,
+            current: 0,
+        }
+    }
+}
+
+impl Command for SequentialGroup {
+    fn initialize(&mut self) {
+        self.current = 0;
+        if let Some(command) = self.commands.first_mut() {
+            command.initialize();
+        }
+    }
+
+    fn execute(&mut self) {
+        if self.current >= self.commands.len() {
+            return;
+        }
+        self.commands[self.current].execute();
+        if self.commands[self.current].is_finished() {
+            self.commands[self.current].end(false);
+            self.current += 1;
+            if self.current < self.commands.len() {
+                self.commands[self.current].initialize();
+            }
+        }
+    }
+
+    fn is_finished(&mut self) -> bool {
+        self.current >= self.commands.len()
+    }
+
+    fn end(&mut self, interrupted: bool) {
+        if interrupted && self.current < self.commands.len() {
+            self.commands[self.current].end(true);
+        }
+    }
+
+    fn requirements(&self) -> Vec<SubsystemId> {
+        all_requirements(&self.commands)
+    }
+
+    fn runs_when_disabled(&self) -> bool {
+        self.commands.iter().all(|command| command.runs_when_disabled())
+    }
+}
+
+/// When a parallel group of commands finishes.
+enum ParallelKind {
+    /// When every command has finished.
+    All,
+    /// When any command finishes.
+    Race,
+    /// When the first command finishes.
+    Deadline,
+}
+
+/// Runs several commands at the same time.
+pub struct ParallelGroup {
+    kind: ParallelKind,
+    commands: Vec<Box<dyn Command>>,
+    running: Vec<bool>,
+}
+
+impl ParallelGroup {
+    fn with_kind(kind: ParallelKind, commands: Vec<Box<dyn Command>>) -> ParallelGroup {
+        let running = vec![false; commands.len()];
+        ParallelGroup {
+            kind,
+            commands,
+            running,
+        }
+    }
+
+    /// Create a group that finishes when all of `commands` have finished.
+    pub fn new(commands: Vec<Box<dyn Command>>) -> ParallelGroup {
+        ParallelGroup::with_kind(ParallelKind::All, commands)
+    }
+
+    /// Create a group that finishes as soon as any of `commands` finishes, interrupting the rest.
+    /// A race with no commands finishes immediately.
+    pub fn race(commands: Vec<Box<dyn Command>>) -> ParallelGroup {
+        ParallelGroup::with_kind(ParallelKind::Race, commands)
+    }
+
+    /// Create a group that finishes when `deadline` finishes, interrupting any of `others` that
+    /// are still running.
+    pub fn deadline(deadline: Box<dyn Command>, others: Vec<Box<dyn Command>>) -> ParallelGroup {
+        let mut commands = vec![deadline];
+        commands.extend(others);
+        ParallelGroup::with_kind(ParallelKind::Deadline, commands)
+    }
+}
+
+impl Command for ParallelGroup {
+    fn initialize(&mut self) {
+        for (command, running) in self.commands.iter_mut().zip(self.running.iter_mut()) {
+            command.initialize();
+            *running = true;
+        }
+    }
+
+    fn execute(&mut self) {
+        for (command, running) in self.commands.iter_mut().zip(self.running.iter_mut()) {
+            if !*running {
+                continue;
+            }
+            command.execute();
+            if command.is_finished() {
+                command.end(false);
+                *running = false;
+            }
+        }
+    }
+
+    fn is_finished(&mut self) -> bool {
+        match self.kind {
+            ParallelKind::All => self.running.iter().all(|running| !running),
+            ParallelKind::Race => {
+                self.running.is_empty() || self.running.iter().any(|running| !running)
+            }
+            ParallelKind::Deadline => !self.running[0],
+        }
+    }
+
+    fn end(&mut self, _interrupted: bool) {
+        for (command, running) in self.commands.iter_mut().zip(self.running.iter_mut()) {
+            if *running {
+                command.end(true);
+                *running = false;
+            }
+        }
+    }
+
+    fn requirements(&self) -> Vec<SubsystemId> {
+        all_requirements(&self.commands)
+    }
+
+    fn runs_when_disabled(&self) -> bool {
+        self.commands.iter().all(|command| command.runs_when_disabled())
+    }
+}
+
+/// Runs one of two commands, chosen when it is scheduled.
+pub struct ConditionalCommand<F> {
+    on_true: Box<dyn Command>,
+    on_false: Box<dyn Command>,
+    condition: F,
+    chose_true: bool,
+}
+
+impl<F: FnMut() -> bool> ConditionalCommand<F> {
+    /// Create a command that runs `on_true` if `condition` returns true when scheduled, and
+    /// `on_false` otherwise.
+    pub fn new(on_true: Box<dyn Command>,
+               on_false: Box<dyn Command>,
+               condition: F)
+               -> ConditionalCommand<F> {
+        ConditionalCommand {
+            on_true,
+            on_false,
+            condition,
+            chose_true: false,
+        }
+    }
+
+    fn selected(&mut self) -> &mut Box<dyn Command> {
+        if self.chose_true {
+            &mut self.on_true
+        } else {
+            &mut self.on_false
+        }
+    }
+}
+
+impl<F: FnMut() -> bool> Command for ConditionalCommand<F> {
+    fn initialize(&mut self) {
+        self.chose_true = (self.condition)();
+        self.selected().initialize();
+    }
+
+    fn execute(&mut self) {
+        self.selected().execute();
+    }
+
+    fn is_finished(&mut self) -> bool {
+        self.selected().is_finished()
+    }
+
+    fn end(&mut self, interrupted: bool) {
+        self.selected().end(interrupted);
+    }
+
+    fn requirements(&self) -> Vec<SubsystemId> {
+        let mut requirements = self.on_true.requirements();
+        for requirement in self.on_false.requirements() {
+            if !requirements.contains(&requirement) {
+                requirements.push(requirement);
+            }
+        }
+        requirements
+    }
+
+    fn runs_when_disabled(&self) -> bool {
+        self.on_true.runs_when_disabled() && self.on_false.runs_when_disabled()
+    }
+}
@@ -0,0 +1,164 @@
+use wpilib::wpilib_hal::*;
+use wpilib::hal_call::*;
+use wpilib::sensor;
+use wpilib::usage::*;
+
+/// The faults a compressor can report. Sticky faults stay set until they are cleared with
+/// `Compressor::clear_all_pcm_sticky_faults`.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct CompressorFaults {
+    /// The compressor is drawing too much current.
+    pub current_too_high: bool,
+    /// The compressor has drawn too much current since sticky faults were cleared.
+    pub current_too_high_sticky: bool,
+    /// The compressor output is shorted.
+    pub shorted: bool,
+    /// The compressor output has been shorted since sticky faults were cleared.
+    pub shorted_sticky: bool,
+    /// The compressor is not connected.
+    pub not_connected: bool,
+    /// The compressor has been disconnected since sticky faults were cleared.
+    pub not_connected_sticky: bool,
+}
+
+impl CompressorFaults {
+    /// Is any fault, sticky or not, set?
+    pub fn any(&self) -> bool {
+        *self != CompressorFaults::default()
+    }
+}
+
+/// The compressor attached to a PCM. In closed loop control, which is on by default, the PCM runs
+/// the compressor whenever the robot is enabled and the pressure switch says the tanks are not
+/// full.
+///
+/// # Usage
+/// ```
+/// # use wpilib::*;
+/// let mut compressor = Compressor::default().unwrap();
+///
+/// // Don't let the compressor draw current while climbing.
+/// compressor.stop().unwrap();
+///
+/// let faults = compressor.get_faults().unwrap();
+/// if faults.any() {
+///     DriverStation::instance().report_throttled(true, &format!("Compressor: {:?}", faults));
+/// }
+/// ```
+pub struct Compressor {
+    module: i32,
+    handle: HAL_CompressorHandle,
+}
+
+impl Compressor {
+    /// Create a new interface to the compressor on the specified PCM, returning an error if
+    /// initialization fails.
+    pub fn new(module: i32) -> HalResult<Compressor> {
+        if !sensor::check_compressor_module(module) {
+            return Err(HalError(0));
+        }
+
+        let handle = hal_call!(HAL_InitializeCompressor(module))?;
+        hal_call!(HAL_SetCompressorClosedLoopControl(handle, true as i32))?;
+
+        report_usage(ResourceType::Compressor, module);
+
+        Ok(Compressor {
+            module,
+            handle,
+        })
+    }
+
+    /// Use the default module (0).
+    #[allow(clippy::should_implement_trait)]
+    pub fn default() -> HalResult<Compressor> {
+        Compressor::new(0)
+    }
+
+    /// Get the PCM module for this compressor.
+    pub fn get_module(&self) -> i32 {
+        self.module
+    }
+
+    /// Let the PCM run the compressor automatically using the pressure switch.
+    pub fn start(&mut self) -> HalResult<()> {
+        self.set_closed_loop_control(true)
+    }
+
+    /// Stop the compressor, and keep it off until `start` is called.
+    pub fn stop(&mut self) -> HalResult<()> {
+        self.set_closed_loop_control(false)
+    }
+
+    /// Is the compressor running right now?
+    pub fn enabled(&self) -> HalResult<bool> {
+        Ok(hal_call!(HAL_GetCompressor(self.handle))? != 0)
+    }
+
+    /// Get the state of the pressure switch. True means the tanks are full.
+    pub fn get_pressure_switch_value(&self) -> HalResult<bool> {
+        Ok(hal_call!(HAL_GetCompressorPressureSwitch(self.handle))? != 0)
+    }
+
+    /// Get the current drawn by the compressor, in amps.
+    pub fn get_compressor_current(&self) -> HalResult<f64> {
+        hal_call!(HAL_GetCompressorCurrent(self.handle))
+    }
+
+    /// Turn closed loop control on or off.
+    pub fn set_closed_loop_control(&mut self, on: bool) -> HalResult<()> {
+        hal_call!(HAL_SetCompressorClosedLoopControl(self.handle, on as i32))
+    }
+
+    /// Is closed loop control on?
+    pub fn get_closed_loop_control(&self) -> HalResult<bool> {
+        Ok(hal_call!(HAL_GetCompressorClosedLoopControl(self.handle))? != 0)
+    }
+
+    /// Is the compressor drawing too much current?
+    pub fn get_compressor_current_too_high_fault(&self) -> HalResult<bool> {
+        Ok(hal_call!(HAL_GetCompressorCurrentTooHighFault(self.handle))? != 0)
+    }
+
+    /// Has the compressor drawn too much current since sticky faults were cleared?
+    pub fn get_compressor_current_too_high_sticky_fault(&self) -> HalResult<bool> {
+        Ok(hal_call!(HAL_GetCompressorCurrentTooHighStickyFault(self.handle))? != 0)
+    }
+
+    /// Is the compressor output shorted?
+    pub fn get_compressor_shorted_fault(&self) -> HalResult<bool> {
+        Ok(hal_call!(HAL_GetCompressorShortedFault(self.handle))? != 0)
+    }
+
+    /// Has the compressor output been shorted since sticky faults were cleared?
+    pub fn get_compressor_shorted_sticky_fault(&self) -> HalResult<bool> {
+        Ok(hal_call!(HAL_GetCompressorShortedStickyFault(self.handle))? != 0)
+    }
+
+    /// Is the compressor disconnected?
+    pub fn get_compressor_not_connected_fault(&self) -> HalResult<bool> {
+        Ok(hal_call!(HAL_GetCompressorNotConnectedFault(self.handle))? != 0)
+    }
+
+    /// Has the compressor been disconnected since sticky faults were cleared?
+    pub fn get_compressor_not_connected_sticky_fault(&self) -> HalResult<bool> {
+        Ok(hal_call!(HAL_GetCompressorNotConnectedStickyFault(self.handle))? != 0)
+    }
+
+    /// Get every compressor fault at once.
+    pub fn get_faults(&self) -> HalResult<CompressorFaults> {
+        Ok(CompressorFaults {
+            current_too_high: self.get_compressor_current_too_high_fault()?,
+            current_too_high_sticky: self.get_compressor_current_too_high_sticky_fault()?,
+            shorted: self.get_compressor_shorted_fault()?,
+            shorted_sticky: self.get_compressor_shorted_sticky_fault()?,
+            not_connected: self.get_compressor_not_connected_fault()?,
+            not_connected_sticky: self.get_compressor_not_connected_sticky_fault()?,
+        })
+    }
+
+    /// Clear the sticky faults on the PCM, including those for its solenoids.
+    pub fn clear_all_pcm_sticky_faults(&mut self) -> HalResult<()> {
+        hal_call!(HAL_ClearAllPCMStickyFaults(self.module))
+    }
+}
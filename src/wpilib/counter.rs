@@ -0,0 +1,257 @@
+use wpilib::wpilib_hal::*;
+use wpilib::hal_call::*;
+use wpilib::digital_input::DigitalInput;
+use wpilib::digital_source::DigitalSource;
+use wpilib::usage::*;
+use std::ptr;
+
+/// What a counter measures.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CounterMode {
+    /// Count up on edges of the up source and down on edges of the down source.
+    TwoPulse,
+    /// Measure the time the up source spends high, or low.
+    Semiperiod,
+    /// Count pulses of the up source, up for pulses shorter than a threshold and down for longer
+    /// ones. Some gear tooth sensors use this to report direction.
+    PulseLength,
+    /// Count edges of the up source, with the down source selecting the direction.
+    ExternalDirection,
+}
+
+impl CounterMode {
+    fn into_hal(self) -> HAL_Counter_Mode {
+        match self {
+            CounterMode::TwoPulse => HAL_Counter_Mode::HAL_Counter_kTwoPulse,
+            CounterMode::Semiperiod => HAL_Counter_Mode::HAL_Counter_kSemiperiod,
+            CounterMode::PulseLength => HAL_Counter_Mode::HAL_Counter_kPulseLength,
+            CounterMode::ExternalDirection => HAL_Counter_Mode::HAL_Counter_kExternalDirection,
+        }
+    }
+}
+
+type Source = Box<dyn DigitalSource + Send>;
+
+/// A general purpose counter, which counts edges of up to two digital sources and measures the
+/// time between them. All counting is done by the FPGA.
+///
+/// # Usage
+/// ```
+/// # use wpilib::*;
+/// // A hall effect sensor that sees one magnet per revolution of the shooter wheel.
+/// let mut shooter = Counter::from_channel(4).unwrap();
+/// shooter.set_max_period(0.1).unwrap();
+/// # sim::counter(0).set_period(0.02);
+///
+/// let rpm = if shooter.get_stopped().unwrap() {
+///     0.0
+/// } else {
+///     60.0 / shooter.get_period().unwrap()
+/// };
+/// # assert!((rpm - 3000.0).abs() < 1e-6);
+/// ```
+pub struct Counter {
+    handle: HAL_CounterHandle,
+    index: i32,
+    up_source: Option<Source>,
+    down_source: Option<Source>,
+    distance_per_pulse: f64,
+}
+
+impl Counter {
+    /// Create a new counter with no sources, returning an error if initialization fails.
+    pub fn new(mode: CounterMode) -> HalResult<Counter> {
+        let mut index = 0;
+        let handle = hal_call!(HAL_InitializeCounter(mode.into_hal(), &mut index))?;
+        let mut counter = Counter {
+            handle,
+            index,
+            up_source: None,
+            down_source: None,
+            distance_per_pulse: 1.0,
+        };
+        counter.set_max_period(0.5)?;
+
+        report_usage_extras(ResourceType::Counter, index, mode as i32, ptr::null());
+        Ok(counter)
+    }
+
+    /// Create a new counter that counts rising edges of a new digital input on the specified
+    /// channel, returning an error if initialization fails.
+    pub fn from_channel(channel: i32) -> HalResult<Counter> {
+        Counter::from_source(DigitalInput::new(channel)?)
+    }
+
+    /// Create a new counter that counts rising edges of an existing source, returning an error if
+    /// initialization fails.
+    pub fn from_source<S>(source: S) -> HalResult<Counter>
+        where S: DigitalSource + Send + 'static
+    {
+        let mut counter = Counter::new(CounterMode::TwoPulse)?;
+        counter.set_up_source(source)?;
+        Ok(counter)
+    }
+
+    /// Get the FPGA index of this counter.
+    pub fn get_fpga_index(&self) -> i32 {
+        self.index
+    }
+
+    /// Count edges of `source` as up counts, replacing any previous up source.
+    pub fn set_up_source<S>(&mut self, source: S) -> HalResult<()>
+        where S: DigitalSource + Send + 'static
+    {
+        hal_call!(HAL_SetCounterUpSource(self.handle,
+                                         source.get_handle_for_routing(),
+                                         source.get_analog_trigger_type_for_routing()))?;
+        self.up_source = Some(Box::new(source));
+        Ok(())
+    }
+
+    /// Count edges of a new digital input on the specified channel as up counts.
+    pub fn set_up_source_channel(&mut self, channel: i32) -> HalResult<()> {
+        self.set_up_source(DigitalInput::new(channel)?)
+    }
+
+    /// Set which edges of the up source are counted.
+    pub fn set_up_source_edge(&mut self, rising: bool, falling: bool) -> HalResult<()> {
+        hal_call!(HAL_SetCounterUpSourceEdge(self.handle, rising as i32, falling as i32))
+    }
+
+    /// Stop counting the up source, and release it.
+    pub fn clear_up_source(&mut self) -> HalResult<()> {
+        hal_call!(HAL_ClearCounterUpSource(self.handle))?;
+        self.up_source = None;
+        Ok(())
+    }
+
+    /// Count edges of `source` as down counts, replacing any previous down source. Only the
+    /// two-pulse and external direction modes use a down source.
+    pub fn set_down_source<S>(&mut self, source: S) -> HalResult<()>
+        where S: DigitalSource + Send + 'static
+    {
+        hal_call!(HAL_SetCounterDownSource(self.handle,
+                                           source.get_handle_for_routing(),
+                                           source.get_analog_trigger_type_for_routing()))?;
+        self.down_source = Some(Box::new(source));
+        Ok(())
+    }
+
+    /// Count edges of a new digital input on the specified channel as down counts.
+    pub fn set_down_source_channel(&mut self, channel: i32) -> HalResult<()> {
+        self.set_down_source(DigitalInput::new(channel)?)
+    }
+
+    /// Set which edges of the down source are counted.
+    pub fn set_down_source_edge(&mut self, rising: bool, falling: bool) -> HalResult<()> {
+        hal_call!(HAL_SetCounterDownSourceEdge(self.handle, rising as i32, falling as i32))
+    }
+
+    /// Stop counting the down source, and release it.
+    pub fn clear_down_source(&mut self) -> HalResult<()> {
+        hal_call!(HAL_ClearCounterDownSource(self.handle))?;
+        self.down_source = None;
+        Ok(())
+    }
+
+    /// Count up on the up source and down on the down source.
+    pub fn set_up_down_counter_mode(&mut self) -> HalResult<()> {
+        hal_call!(HAL_SetCounterUpDownMode(self.handle))
+    }
+
+    /// Count edges of the up source, with the level of the down source setting the direction.
+    pub fn set_external_direction_mode(&mut self) -> HalResult<()> {
+        hal_call!(HAL_SetCounterExternalDirectionMode(self.handle))
+    }
+
+    /// Measure the time the up source spends high, or low if `high_semi_period` is false.
+    pub fn set_semi_period_mode(&mut self, high_semi_period: bool) -> HalResult<()> {
+        hal_call!(HAL_SetCounterSemiPeriodMode(self.handle, high_semi_period as i32))
+    }
+
+    /// Count pulses of the up source, up for pulses shorter than `threshold` seconds and down
+    /// for longer ones.
+    pub fn set_pulse_length_mode(&mut self, threshold: f64) -> HalResult<()> {
+        hal_call!(HAL_SetCounterPulseLengthMode(self.handle, threshold))
+    }
+
+    /// Get the current count.
+    pub fn get(&self) -> HalResult<i32> {
+        hal_call!(HAL_GetCounter(self.handle))
+    }
+
+    /// Reset the count to zero.
+    pub fn reset(&mut self) -> HalResult<()> {
+        hal_call!(HAL_ResetCounter(self.handle))
+    }
+
+    /// Get the time between the last two counts, in seconds, averaged over the configured number
+    /// of samples. This is infinite if the counter is stopped.
+    pub fn get_period(&self) -> HalResult<f64> {
+        hal_call!(HAL_GetCounterPeriod(self.handle))
+    }
+
+    /// Set the longest period, in seconds, before the counter is considered stopped.
+    pub fn set_max_period(&mut self, max_period: f64) -> HalResult<()> {
+        hal_call!(HAL_SetCounterMaxPeriod(self.handle, max_period))
+    }
+
+    /// Set whether the period keeps updating after the counter stops, so that it grows until the
+    /// next count instead of holding the last measurement.
+    pub fn set_update_when_empty(&mut self, enabled: bool) -> HalResult<()> {
+        hal_call!(HAL_SetCounterUpdateWhenEmpty(self.handle, enabled as i32))
+    }
+
+    /// Has the counter gone longer than the max period without a count?
+    pub fn get_stopped(&self) -> HalResult<bool> {
+        Ok(hal_call!(HAL_GetCounterStopped(self.handle))? != 0)
+    }
+
+    /// Get the direction of the last count. True means up.
+    pub fn get_direction(&self) -> HalResult<bool> {
+        Ok(hal_call!(HAL_GetCounterDirection(self.handle))? != 0)
+    }
+
+    /// Reverse the sense of the direction source. This only applies in external direction mode.
+    pub fn set_reverse_direction(&mut self, reverse: bool) -> HalResult<()> {
+        hal_call!(HAL_SetCounterReverseDirection(self.handle, reverse as i32))
+    }
+
+    /// Set the number of periods to average in `get_period`, from 1 to 127.
+    pub fn set_samples_to_average(&mut self, samples: i32) -> HalResult<()> {
+        hal_call!(HAL_SetCounterSamplesToAverage(self.handle, samples))
+    }
+
+    /// Get the number of periods averaged in `get_period`.
+    pub fn get_samples_to_average(&self) -> HalResult<i32> {
+        hal_call!(HAL_GetCounterSamplesToAverage(self.handle))
+    }
+
+    /// Set the distance travelled for each count, used by `get_distance` and `get_rate`.
+    pub fn set_distance_per_pulse(&mut self, distance_per_pulse: f64) {
+        self.distance_per_pulse = distance_per_pulse;
+    }
+
+    /// Get the distance travelled, scaled by the distance per pulse.
+    pub fn get_distance(&self) -> HalResult<f64> {
+        Ok(self.get()? as f64 * self.distance_per_pulse)
+    }
+
+    /// Get the rate of travel in distance per second, scaled by the distance per pulse. This is
+    /// zero if the counter is stopped.
+    pub fn get_rate(&self) -> HalResult<f64> {
+        Ok(self.distance_per_pulse / self.get_period()?)
+    }
+
+    /// Get the up and down sources that are set.
+    pub(crate) fn get_sources(&self) -> Vec<&dyn DigitalSource> {
+        self.up_source.iter().chain(&self.down_source).map(|source| &**source as _).collect()
+    }
+}
+
+impl Drop for Counter {
+    fn drop(&mut self) {
+        // The sources are dropped after this, once nothing is routed from them.
+        let _ = hal_call!(HAL_FreeCounter(self.handle));
+    }
+}
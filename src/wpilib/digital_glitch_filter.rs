@@ -0,0 +1,149 @@
+use wpilib::wpilib_hal::*;
+use wpilib::hal_call::*;
+use wpilib::counter::Counter;
+use wpilib::digital_source::DigitalSource;
+use wpilib::encoder::Encoder;
+use wpilib::usage::*;
+use std::sync::Mutex;
+
+/// The number of glitch filters in the FPGA.
+const NUM_FILTERS: usize = 3;
+
+/// The number of system clock ticks in each cycle of a filter's counter.
+const TICKS_PER_FILTER_CYCLE: i64 = 4;
+
+/// Which filters are in use.
+static ALLOCATED: Mutex<[bool; NUM_FILTERS]> = Mutex::new([false; NUM_FILTERS]);
+
+/// One of the FPGA's three digital glitch filters. A filtered input ignores any pulse shorter than
+/// the filter's period, which cleans up noisy switches and encoder signals.
+///
+/// Any number of digital inputs, encoders and counters can share a filter. Analog trigger
+/// outputs cannot be filtered.
+///
+/// # Usage
+/// ```
+/// # use wpilib::*;
+/// let limit_switch = DigitalInput::new(0).unwrap();
+/// let encoder = Encoder::new(1, 2, EncodingType::FourX).unwrap();
+///
+/// let mut filter = DigitalGlitchFilter::new().unwrap();
+/// filter.set_period_nanoseconds(1_000).unwrap();
+/// filter.add(&limit_switch).unwrap();
+/// filter.add_encoder(&encoder).unwrap();
+/// # assert_eq!(sim::dio(2).filter(), 1);
+/// ```
+pub struct DigitalGlitchFilter {
+    index: usize,
+}
+
+impl DigitalGlitchFilter {
+    /// Allocate a free filter, returning an error if all of them are in use.
+    pub fn new() -> HalResult<DigitalGlitchFilter> {
+        let mut allocated = ALLOCATED.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let index = match allocated.iter().position(|allocated| !allocated) {
+            Some(index) => index,
+            None => return Err(HalError(NO_AVAILABLE_RESOURCES as i32)),
+        };
+        allocated[index] = true;
+
+        report_usage(ResourceType::DigitalFilter, index as i32);
+        Ok(DigitalGlitchFilter { index })
+    }
+
+    /// Get the index of this filter, from 0 to 2.
+    pub fn get_index(&self) -> i32 {
+        self.index as i32
+    }
+
+    /// Select the filter for a source, where 0 means no filter.
+    fn select<S>(source: &S, filter: i32) -> HalResult<()>
+        where S: DigitalSource + ?Sized
+    {
+        if source.is_analog_trigger() {
+            return Err(HalError(0));
+        }
+        hal_call!(HAL_SetFilterSelect(source.get_handle_for_routing(), filter))
+    }
+
+    /// Filter a source, such as a `DigitalInput`. Analog trigger outputs cannot be filtered.
+    pub fn add<S>(&mut self, source: &S) -> HalResult<()>
+        where S: DigitalSource + ?Sized
+    {
+        DigitalGlitchFilter::select(source, self.index as i32 + 1)
+    }
+
+    /// Filter both channels of an encoder.
+    pub fn add_encoder(&mut self, encoder: &Encoder) -> HalResult<()> {
+        for source in &encoder.get_sources() {
+            self.add(*source)?;
+        }
+        Ok(())
+    }
+
+    /// Filter the up and down sources of a counter. Sources set later are not filtered.
+    pub fn add_counter(&mut self, counter: &Counter) -> HalResult<()> {
+        for source in counter.get_sources() {
+            self.add(source)?;
+        }
+        Ok(())
+    }
+
+    /// Stop filtering a source.
+    pub fn remove<S>(&mut self, source: &S) -> HalResult<()>
+        where S: DigitalSource + ?Sized
+    {
+        DigitalGlitchFilter::select(source, 0)
+    }
+
+    /// Stop filtering both channels of an encoder.
+    pub fn remove_encoder(&mut self, encoder: &Encoder) -> HalResult<()> {
+        for source in &encoder.get_sources() {
+            self.remove(*source)?;
+        }
+        Ok(())
+    }
+
+    /// Stop filtering the up and down sources of a counter.
+    pub fn remove_counter(&mut self, counter: &Counter) -> HalResult<()> {
+        for source in counter.get_sources() {
+            self.remove(source)?;
+        }
+        Ok(())
+    }
+
+    /// Get the rate of the FPGA's system clock.
+    fn ticks_per_microsecond() -> i64 {
+        unsafe { Hal::HAL_GetSystemClockTicksPerMicrosecond() as i64 }
+    }
+
+    /// Set the shortest pulse that gets through the filter, in FPGA cycles. Each cycle is four
+    /// ticks of the system clock.
+    pub fn set_period_cycles(&mut self, fpga_cycles: i64) -> HalResult<()> {
+        hal_call!(HAL_SetFilterPeriod(self.index as i32, fpga_cycles))
+    }
+
+    /// Set the shortest pulse that gets through the filter, in nanoseconds. This is rounded down
+    /// to a whole number of FPGA cycles.
+    pub fn set_period_nanoseconds(&mut self, nanoseconds: i64) -> HalResult<()> {
+        self.set_period_cycles(nanoseconds * DigitalGlitchFilter::ticks_per_microsecond() /
+                               TICKS_PER_FILTER_CYCLE / 1000)
+    }
+
+    /// Get the shortest pulse that gets through the filter, in FPGA cycles.
+    pub fn get_period_cycles(&self) -> HalResult<i64> {
+        hal_call!(HAL_GetFilterPeriod(self.index as i32))
+    }
+
+    /// Get the shortest pulse that gets through the filter, in nanoseconds.
+    pub fn get_period_nanoseconds(&self) -> HalResult<i64> {
+        Ok(self.get_period_cycles()? * TICKS_PER_FILTER_CYCLE * 1000 /
+           DigitalGlitchFilter::ticks_per_microsecond())
+    }
+}
+
+impl Drop for DigitalGlitchFilter {
+    fn drop(&mut self) {
+        ALLOCATED.lock().unwrap_or_else(|poisoned| poisoned.into_inner())[self.index] = false;
+    }
+}
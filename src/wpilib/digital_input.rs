@@ -1,5 +1,6 @@
 use wpilib::wpilib_hal::*;
 use wpilib::hal_call::*;
+use wpilib::digital_source::DigitalSource;
 use wpilib::sensor;
 use wpilib::usage::*;
 
@@ -17,13 +18,13 @@ impl DigitalInput {
             return Err(HalError(0));
         }
 
-        let handle = hal_call!(HAL_InitializeDIOPort(HAL_GetPort(channel), true as i32))?;
+        let handle = hal_call!(HAL_InitializeDIOPort(Hal::HAL_GetPort(channel), true as i32))?;
 
         report_usage(ResourceType::DigitalInput, channel);
 
         Ok(DigitalInput {
-            channel: channel,
-            handle: handle,
+            channel,
+            handle,
         })
     }
 
@@ -46,7 +47,13 @@ impl DigitalInput {
 impl Drop for DigitalInput {
     fn drop(&mut self) {
         unsafe {
-            HAL_FreeDIOPort(self.handle);
+            Hal::HAL_FreeDIOPort(self.handle);
         }
     }
 }
+
+impl DigitalSource for DigitalInput {
+    fn get_handle_for_routing(&self) -> HAL_Handle {
+        self.handle
+    }
+}
@@ -18,13 +18,13 @@ impl DigitalOutput {
             return Err(HalError(0));
         }
 
-        let handle = hal_call!(HAL_InitializeDIOPort(HAL_GetPort(channel), false as i32))?;
+        let handle = hal_call!(HAL_InitializeDIOPort(Hal::HAL_GetPort(channel), false as i32))?;
 
         report_usage(ResourceType::DigitalOutput, channel);
 
         Ok(DigitalOutput {
-            channel: channel,
-            handle: handle,
+            channel,
+            handle,
             pwm: None,
         })
     }
@@ -98,7 +98,7 @@ impl Drop for DigitalOutput {
     fn drop(&mut self) {
         let _ = self.disable_pwm();
         unsafe {
-            HAL_FreeDIOPort(self.handle);
+            Hal::HAL_FreeDIOPort(self.handle);
         }
     }
 }
@@ -0,0 +1,33 @@
+use wpilib::wpilib_hal::*;
+use std::sync::Arc;
+
+/// A signal that the FPGA can route to a counter, encoder or interrupt, such as a digital input.
+pub trait DigitalSource {
+    /// Get the HAL handle used to route this source.
+    fn get_handle_for_routing(&self) -> HAL_Handle;
+
+    /// Get which output of an analog trigger to route. Ignored unless this is an analog trigger.
+    fn get_analog_trigger_type_for_routing(&self) -> HAL_AnalogTriggerType {
+        HAL_AnalogTriggerType::HAL_Trigger_kInWindow
+    }
+
+    /// Is this source an analog trigger output rather than a digital input?
+    fn is_analog_trigger(&self) -> bool {
+        false
+    }
+}
+
+/// A shared source, so that one input can feed several consumers.
+impl<T: DigitalSource + ?Sized> DigitalSource for Arc<T> {
+    fn get_handle_for_routing(&self) -> HAL_Handle {
+        (**self).get_handle_for_routing()
+    }
+
+    fn get_analog_trigger_type_for_routing(&self) -> HAL_AnalogTriggerType {
+        (**self).get_analog_trigger_type_for_routing()
+    }
+
+    fn is_analog_trigger(&self) -> bool {
+        (**self).is_analog_trigger()
+    }
+}
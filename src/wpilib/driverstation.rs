@@ -2,7 +2,7 @@ use wpilib::wpilib_hal::*;
 use wpilib::hal_call::*;
 use wpilib::Throttler;
 
-use std::{thread, time, mem, ffi, sync};
+use std::{thread, time, ptr, ffi, sync};
 
 use atom::Atom;
 
@@ -18,7 +18,7 @@ struct Joysticks {
     descriptor: [HAL_JoystickDescriptor; MAX_JOYSTICK_PORTS],
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 /// The robot's state
 pub enum RobotState {
     Disabled,
@@ -41,13 +41,14 @@ pub struct DriverStation {
 
     report_throttler: Throttler<f64>,
 
-    waiter: sync::Arc<(sync::Mutex<bool>, sync::Condvar)>,
+    // The number of packets received so far, and a condition notified on each new packet.
+    waiter: sync::Arc<(sync::Mutex<u64>, sync::Condvar)>,
 
     join: Option<thread::JoinHandle<()>>,
 }
 
-static CREATE_DS: sync::Once = sync::ONCE_INIT;
-static mut DRIVER_STATION: *mut DriverStation = 0 as *mut DriverStation;
+static CREATE_DS: sync::Once = sync::Once::new();
+static mut DRIVER_STATION: *mut DriverStation = ptr::null_mut();
 
 #[derive(Debug, Copy, Clone)]
 /// Some error involving joysticks
@@ -68,7 +69,7 @@ pub enum AllianceId {
 impl DriverStation {
     fn new() -> DriverStation {
         let data_atom = sync::Arc::new(Atom::empty());
-        let waiter = sync::Arc::new((sync::Mutex::new(false), sync::Condvar::new()));
+        let waiter = sync::Arc::new((sync::Mutex::new(0), sync::Condvar::new()));
 
         let mut ds = DriverStation {
             data: data_atom,
@@ -81,7 +82,7 @@ impl DriverStation {
             // timer isn't implemented yet.
             report_throttler: Throttler::new(0.0, 0.0),
 
-            waiter: waiter,
+            waiter,
 
             join: None,
         };
@@ -99,21 +100,23 @@ impl DriverStation {
             loop {
                 // Wait for the HAL to get new data
                 unsafe {
-                    HAL_WaitForDSData();
+                    Hal::HAL_WaitForDSData();
                 }
 
                 // Update the joysticks and control word using the new data.
                 let mut joysticks = Joysticks::default();
                 for stick in 0..MAX_JOYSTICK_PORTS {
                     unsafe {
-                        HAL_GetJoystickAxes(stick as i32,
-                                            &mut joysticks.axes[stick] as *mut HAL_JoystickAxes);
-                        HAL_GetJoystickPOVs(stick as i32,
-                                            &mut joysticks.povs[stick] as *mut HAL_JoystickPOVs);
-                        HAL_GetJoystickButtons(stick as i32,
+                        Hal::HAL_GetJoystickAxes(stick as i32,
+                                                 &mut joysticks.axes[stick] as
+                                                 *mut HAL_JoystickAxes);
+                        Hal::HAL_GetJoystickPOVs(stick as i32,
+                                                 &mut joysticks.povs[stick] as
+                                                 *mut HAL_JoystickPOVs);
+                        Hal::HAL_GetJoystickButtons(stick as i32,
                                                     &mut joysticks.buttons[stick] as
                                                     *mut HAL_JoystickButtons);
-                        HAL_GetJoystickDescriptor(stick as i32,
+                        Hal::HAL_GetJoystickDescriptor(stick as i32,
                                                        &mut joysticks.descriptor[stick] as
                                                        *mut HAL_JoystickDescriptor);
                     }
@@ -121,7 +124,7 @@ impl DriverStation {
 
                 let mut control_word: HAL_ControlWord = HAL_ControlWord::default();
                 unsafe {
-                    HAL_GetControlWord(&mut control_word as *mut HAL_ControlWord);
+                    Hal::HAL_GetControlWord(&mut control_word as *mut HAL_ControlWord);
                 }
 
                 // Write that data into the atom for usage by callers
@@ -130,7 +133,7 @@ impl DriverStation {
                 // Notify any threads waiting for data
                 {
                     let mut guard = waiter.0.lock().unwrap();
-                    *guard = true;
+                    *guard += 1;
                     waiter.1.notify_all();
                 }
             }
@@ -143,7 +146,9 @@ impl DriverStation {
             let new_control_word = boxed_data.1;
             self.joysticks = boxed_data.0;
             self.state = if new_control_word.enabled() {
-                if new_control_word.autonomous() {
+                if new_control_word.test() {
+                    RobotState::Test
+                } else if new_control_word.autonomous() {
                     RobotState::Autonomous
                 } else {
                     RobotState::Teleop
@@ -162,13 +167,13 @@ impl DriverStation {
     /// instead use it in other error reporting methods.
     fn report(&self, is_error: bool, code: i32, error: &str, location: &str, stack: &str) {
         unsafe {
-            HAL_SendError(is_error as i32,
-                          code,
-                          false as i32,
-                          ffi::CString::new(error).unwrap().into_raw(),
-                          ffi::CString::new(location).unwrap().into_raw(),
-                          ffi::CString::new(stack).unwrap().into_raw(),
-                          true as i32);
+            Hal::HAL_SendError(is_error as i32,
+                               code,
+                               false as i32,
+                               ffi::CString::new(error).unwrap().into_raw(),
+                               ffi::CString::new(location).unwrap().into_raw(),
+                               ffi::CString::new(stack).unwrap().into_raw(),
+                               true as i32);
         }
     }
 
@@ -194,7 +199,7 @@ impl DriverStation {
     pub fn instance() -> &'static mut DriverStation {
         unsafe {
             CREATE_DS.call_once(|| {
-                DRIVER_STATION = mem::transmute(Box::new(DriverStation::new()));
+                DRIVER_STATION = Box::into_raw(Box::new(DriverStation::new()));
             });
             &mut *DRIVER_STATION
         }
@@ -223,10 +228,10 @@ impl DriverStation {
     pub fn get_joystick_pov(&mut self, stick: usize, pov: usize) -> Result<i16, JoystickError> {
         self.update_data();
 
-        if stick >= MAX_JOYSTICK_POVS {
+        if stick >= MAX_JOYSTICK_PORTS {
             self.report_throttled(true, "Bad joystick");
             Err(JoystickError::JoystickDNE)
-        } else if pov >= MAX_JOYSTICK_AXES {
+        } else if pov >= MAX_JOYSTICK_POVS {
             self.report_throttled(true, "Bad joystick pov");
             Err(JoystickError::ChannelDNE)
         } else if pov >= self.joysticks.povs[stick].count as usize {
@@ -245,13 +250,13 @@ impl DriverStation {
                                -> Result<bool, JoystickError> {
         self.update_data();
 
-        if stick >= MAX_JOYSTICK_POVS {
+        if stick >= MAX_JOYSTICK_PORTS {
             self.report_throttled(true, "Bad joystick");
             Err(JoystickError::JoystickDNE)
         } else if button == 0 {
             self.report_throttled(true, "Bad joystick button (button IDs start from 1)");
             Err(JoystickError::ChannelDNE)
-        } else if button >= self.joysticks.povs[stick].count as usize {
+        } else if button > self.joysticks.buttons[stick].count as usize {
             self.report_throttled(true,
                                   "Joystick button missing, check if all controllers are plugged \
                                    in");
@@ -288,25 +293,27 @@ impl DriverStation {
 
     /// Wait for a new driver station packet.
     pub fn wait_for_data(&self) {
-        let &(ref wait_lock, ref wait_cond) = &*self.waiter;
-        let mut has_data = wait_lock.lock().unwrap();
-        while !*has_data {
-            has_data = wait_cond.wait(has_data).unwrap();
+        let (wait_lock, wait_cond) = &*self.waiter;
+        let mut packets = wait_lock.lock().unwrap();
+        let last_packet = *packets;
+        while *packets == last_packet {
+            packets = wait_cond.wait(packets).unwrap();
         }
     }
 
     /// Waits for a new driver station packet and returns true, or returns false if timeout is
     /// exceeded.
     pub fn wait_for_data_or_timeout(&self, timeout: time::Duration) -> bool {
-        let &(ref wait_lock, ref wait_cond) = &*self.waiter;
-        let mut has_data = wait_lock.lock().unwrap();
+        let (wait_lock, wait_cond) = &*self.waiter;
+        let mut packets = wait_lock.lock().unwrap();
+        let last_packet = *packets;
 
-        while !*has_data {
-            let result = wait_cond.wait_timeout(has_data, timeout).unwrap();
+        while *packets == last_packet {
+            let result = wait_cond.wait_timeout(packets, timeout).unwrap();
             if result.1.timed_out() {
                 return false;
             } else {
-                has_data = result.0;
+                packets = result.0;
             }
         }
         true
@@ -321,7 +328,7 @@ impl DriverStation {
     /// Does the robot have connection to the driver station?
     pub fn is_ds_attached(&mut self) -> bool {
         self.update_data();
-        self.fms_attached
+        self.ds_attached
     }
 
     /// Get the state of the robot.
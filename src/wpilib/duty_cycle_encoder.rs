@@ -0,0 +1,157 @@
+use wpilib::hal_call::*;
+use wpilib::counter::{Counter, CounterMode};
+use wpilib::digital_input::DigitalInput;
+use wpilib::digital_source::DigitalSource;
+use std::sync::Arc;
+
+/// The lowest frequency, in Hz, at which the encoder is considered connected, by default.
+const DEFAULT_CONNECTED_FREQUENCY: f64 = 100.0;
+
+/// An absolute encoder with a PWM output, such as the US Digital MA3, the CTRE Mag Encoder or the
+/// REV Through Bore Encoder. The fraction of each cycle that the output is high gives the angle
+/// of the shaft within one rotation, so the position is known as soon as the robot turns on.
+///
+/// Two FPGA counters measure the signal: one the time it spends high, and the other the time
+/// between rising edges.
+///
+/// # Usage
+/// ```
+/// # use wpilib::*;
+/// // A turret geared 10:1 to the encoder shaft, which is straight ahead at 0.6 of a rotation.
+/// let mut turret = DutyCycleEncoder::new(0).unwrap();
+/// turret.set_distance_per_rotation(36.0);
+/// turret.set_position_offset(0.6);
+/// # sim::counter(0).set_period(0.00085);
+/// # sim::counter(1).set_period(0.001);
+///
+/// let degrees = turret.get_distance().unwrap();
+/// # assert!((degrees - 9.0).abs() < 1e-9);
+/// ```
+pub struct DutyCycleEncoder {
+    high_time: Counter,
+    period: Counter,
+    min_duty_cycle: f64,
+    max_duty_cycle: f64,
+    position_offset: f64,
+    distance_per_rotation: f64,
+}
+
+impl DutyCycleEncoder {
+    /// Create a new encoder on a new digital input on the specified channel, returning an error
+    /// if initialization fails.
+    pub fn new(channel: i32) -> HalResult<DutyCycleEncoder> {
+        DutyCycleEncoder::from_source(DigitalInput::new(channel)?)
+    }
+
+    /// Create a new encoder from a source that is already owned, returning an error if
+    /// initialization fails.
+    pub fn from_source<S>(source: S) -> HalResult<DutyCycleEncoder>
+        where S: DigitalSource + Send + Sync + 'static
+    {
+        let source = Arc::new(source);
+
+        let mut high_time = Counter::new(CounterMode::Semiperiod)?;
+        high_time.set_up_source(source.clone())?;
+        high_time.set_semi_period_mode(true)?;
+
+        let mut period = Counter::new(CounterMode::TwoPulse)?;
+        period.set_up_source(source)?;
+        period.set_up_source_edge(true, false)?;
+
+        let mut encoder = DutyCycleEncoder {
+            high_time,
+            period,
+            min_duty_cycle: 0.0,
+            max_duty_cycle: 1.0,
+            position_offset: 0.0,
+            distance_per_rotation: 1.0,
+        };
+        encoder.set_connected_frequency_threshold(DEFAULT_CONNECTED_FREQUENCY)?;
+        Ok(encoder)
+    }
+
+    /// Get the frequency of the signal, in Hz. This is zero if the signal has stopped.
+    pub fn get_frequency(&self) -> HalResult<f64> {
+        if self.period.get_stopped()? {
+            return Ok(0.0);
+        }
+        Ok(1.0 / self.period.get_period()?)
+    }
+
+    /// Is the encoder connected? It is if its frequency is above the connected frequency
+    /// threshold.
+    pub fn is_connected(&self) -> HalResult<bool> {
+        Ok(!self.period.get_stopped()?)
+    }
+
+    /// Set the lowest frequency, in Hz, at which the encoder is considered connected. This is
+    /// 100Hz by default, which suits most encoders. Returns an error unless `frequency` is
+    /// positive.
+    pub fn set_connected_frequency_threshold(&mut self, frequency: f64) -> HalResult<()> {
+        if frequency.is_nan() || frequency <= 0.0 {
+            return Err(HalError(0));
+        }
+        self.high_time.set_max_period(1.0 / frequency)?;
+        self.period.set_max_period(1.0 / frequency)
+    }
+
+    /// Get the fraction of each cycle the signal is high, from 0 to 1.
+    pub fn get_duty_cycle(&self) -> HalResult<f64> {
+        if self.period.get_stopped()? {
+            return Ok(0.0);
+        }
+        let duty_cycle = self.high_time.get_period()? / self.period.get_period()?;
+        Ok(duty_cycle.clamp(0.0, 1.0))
+    }
+
+    /// Set the duty cycles the encoder outputs at the ends of its rotation. Many encoders never
+    /// output exactly 0 or 1; for example, the MA3 ranges from 1/4098 to 4097/4098. Returns an
+    /// error unless `0 <= min < max <= 1`.
+    pub fn set_duty_cycle_range(&mut self, min: f64, max: f64) -> HalResult<()> {
+        if !(0.0 <= min && min < max && max <= 1.0) {
+            return Err(HalError(0));
+        }
+        self.min_duty_cycle = min;
+        self.max_duty_cycle = max;
+        Ok(())
+    }
+
+    /// Get the position of the shaft within one rotation, from 0 to 1, ignoring the position
+    /// offset.
+    pub fn get_absolute_position(&self) -> HalResult<f64> {
+        let duty_cycle = self.get_duty_cycle()?;
+        let position = (duty_cycle - self.min_duty_cycle) /
+                       (self.max_duty_cycle - self.min_duty_cycle);
+        Ok(position.clamp(0.0, 1.0))
+    }
+
+    /// Set the absolute position, from 0 to 1, that counts as zero.
+    pub fn set_position_offset(&mut self, offset: f64) {
+        self.position_offset = offset.clamp(0.0, 1.0);
+    }
+
+    /// Get the absolute position that counts as zero.
+    pub fn get_position_offset(&self) -> f64 {
+        self.position_offset
+    }
+
+    /// Get the position relative to the offset, in rotations from 0 to 1.
+    pub fn get(&self) -> HalResult<f64> {
+        Ok((self.get_absolute_position()? - self.position_offset).rem_euclid(1.0))
+    }
+
+    /// Set the distance travelled for each rotation of the shaft, used by `get_distance`.
+    pub fn set_distance_per_rotation(&mut self, distance_per_rotation: f64) {
+        self.distance_per_rotation = distance_per_rotation;
+    }
+
+    /// Get the distance travelled for each rotation of the shaft.
+    pub fn get_distance_per_rotation(&self) -> f64 {
+        self.distance_per_rotation
+    }
+
+    /// Get the position relative to the offset, scaled by the distance per rotation.
+    pub fn get_distance(&self) -> HalResult<f64> {
+        Ok(self.get()? * self.distance_per_rotation)
+    }
+}
@@ -1,48 +1,111 @@
 use wpilib::wpilib_hal::*;
 use wpilib::hal_call::*;
 use wpilib::digital_input::DigitalInput;
+use wpilib::digital_source::DigitalSource;
 use wpilib::usage::*;
 use std::ptr;
-use std::mem;
+
+/// How many counts an encoder makes for each cycle of its two channels.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EncodingType {
+    /// Count once per cycle, on the rising edge of channel A.
+    OneX,
+    /// Count twice per cycle, on both edges of channel A.
+    TwoX,
+    /// Count four times per cycle, on both edges of both channels. This gives the most resolution.
+    FourX,
+}
+
+impl EncodingType {
+    fn into_hal(self) -> HAL_EncoderEncodingType {
+        match self {
+            EncodingType::OneX => HAL_EncoderEncodingType::HAL_Encoder_k1X,
+            EncodingType::TwoX => HAL_EncoderEncodingType::HAL_Encoder_k2X,
+            EncodingType::FourX => HAL_EncoderEncodingType::HAL_Encoder_k4X,
+        }
+    }
+}
 
 /// The indexing type for an encoder
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum IndexingType {
+    /// Hold the count at zero while the index source is high.
     ResetWhileHigh,
+    /// Hold the count at zero while the index source is low.
     ResetWhileLow,
+    /// Reset the count to zero on a falling edge of the index source.
     ResetOnFallingEdge,
+    /// Reset the count to zero on a rising edge of the index source.
     ResetOnRisingEdge,
 }
 
+impl IndexingType {
+    fn into_hal(self) -> HAL_EncoderIndexingType {
+        match self {
+            IndexingType::ResetWhileHigh => HAL_EncoderIndexingType::HAL_kResetWhileHigh,
+            IndexingType::ResetWhileLow => HAL_EncoderIndexingType::HAL_kResetWhileLow,
+            IndexingType::ResetOnFallingEdge => HAL_EncoderIndexingType::HAL_kResetOnFallingEdge,
+            IndexingType::ResetOnRisingEdge => HAL_EncoderIndexingType::HAL_kResetOnRisingEdge,
+        }
+    }
+}
+
+type Source = Box<dyn DigitalSource + Send>;
+
 /// An encoder.
 ///
 /// Uses quadrature on two separate channels to read the distance and direction travelled by a
 /// shaft. All integration is done by the FPGA.
+///
+/// # Usage
+/// ```
+/// # use wpilib::*;
+/// use std::f64::consts::PI;
+///
+/// // A 360 count-per-revolution encoder on a 6 inch wheel.
+/// let mut left = Encoder::new(0, 1, EncodingType::FourX).unwrap();
+/// left.set_distance_per_pulse(6.0 * PI / 360.0).unwrap();
+/// left.set_reverse_direction(true).unwrap();
+/// # sim::encoder(0).set_count(-360);
+///
+/// assert!((left.get_distance().unwrap() - 6.0 * PI).abs() < 1e-9);
+/// ```
 pub struct Encoder {
-    source_a: DigitalInput,
-    source_b: DigitalInput,
-    source_index: Option<DigitalInput>,
+    source_a: Source,
+    source_b: Source,
+    source_index: Option<Source>,
     encoder: HAL_EncoderHandle,
 }
 
 impl Encoder {
     /// Create a new encoder given two channels and an encoding type, returning an error if
     /// initialization fails.
-    pub fn new(channel_a: i32,
-               channel_b: i32,
-               encoding: HAL_EncoderEncodingType)
-               -> HalResult<Encoder> {
+    pub fn new(channel_a: i32, channel_b: i32, encoding: EncodingType) -> HalResult<Encoder> {
         let source_a = DigitalInput::new(channel_a)?;
         let source_b = DigitalInput::new(channel_b)?;
+        Encoder::from_sources(source_a, source_b, encoding)
+    }
 
-        let handle = hal_call!(HAL_InitializeEncoder(source_a.get_handle(),
-                                                     mem::transmute(0),
-                                                     source_b.get_handle(),
-                                                     mem::transmute(0),
+    /// Create a new encoder from two sources that are already owned, such as `DigitalInput`s or
+    /// `AnalogTriggerOutput`s, returning an error if initialization fails. The encoder takes
+    /// ownership of the sources, and releases them when it is dropped.
+    pub fn from_sources<A, B>(source_a: A,
+                              source_b: B,
+                              encoding: EncodingType)
+                              -> HalResult<Encoder>
+        where A: DigitalSource + Send + 'static,
+              B: DigitalSource + Send + 'static
+    {
+        let handle = hal_call!(HAL_InitializeEncoder(source_a.get_handle_for_routing(),
+                                                     source_a.get_analog_trigger_type_for_routing(),
+                                                     source_b.get_handle_for_routing(),
+                                                     source_b.get_analog_trigger_type_for_routing(),
                                                      false as i32,
-                                                     encoding))?;
+                                                     encoding.into_hal()))?;
         let encoder = Encoder {
-            source_a: source_a,
-            source_b: source_b,
+            source_a: Box::new(source_a),
+            source_b: Box::new(source_b),
             source_index: None,
             encoder: handle,
         };
@@ -75,20 +138,106 @@ impl Encoder {
         hal_call!(HAL_GetEncoderEncodingScale(self.encoder))
     }
 
-    /// Get the current (estimated) speed this encoder is travelling at.
+    /// Get the factor that converts raw counts to counts, the reciprocal of the encoding scale.
+    pub fn get_decoding_scale_factor(&self) -> HalResult<f64> {
+        hal_call!(HAL_GetEncoderDecodingScaleFactor(self.encoder))
+    }
+
+    /// Set the distance travelled for each count, used by `get_distance` and `get_rate`.
+    pub fn set_distance_per_pulse(&mut self, distance_per_pulse: f64) -> HalResult<()> {
+        hal_call!(HAL_SetEncoderDistancePerPulse(self.encoder, distance_per_pulse))
+    }
+
+    /// Get the distance travelled for each count.
+    pub fn get_distance_per_pulse(&self) -> HalResult<f64> {
+        hal_call!(HAL_GetEncoderDistancePerPulse(self.encoder))
+    }
+
+    /// Get the distance travelled, scaled by the distance per pulse.
+    pub fn get_distance(&self) -> HalResult<f64> {
+        hal_call!(HAL_GetEncoderDistance(self.encoder))
+    }
+
+    /// Get the current (estimated) speed this encoder is travelling at, in distance per second.
+    /// This is zero if the encoder is stopped.
     pub fn get_rate(&self) -> HalResult<f64> {
         hal_call!(HAL_GetEncoderRate(self.encoder))
     }
 
-    /// Set the minimum rate that this encoder must be moving at to be considered "moving".
+    /// Set the minimum rate, in distance per second, that this encoder must be moving at to be
+    /// considered "moving". This sets the max period from the distance per pulse, so set that
+    /// first.
     pub fn set_min_rate(&mut self, min_rate: f64) -> HalResult<()> {
         hal_call!(HAL_SetEncoderMinRate(self.encoder, min_rate))
     }
 
+    /// Get the time between the last two counts, in seconds, averaged over the configured number
+    /// of samples.
+    pub fn get_period(&self) -> HalResult<f64> {
+        hal_call!(HAL_GetEncoderPeriod(self.encoder))
+    }
+
+    /// Set the longest period, in seconds, before the encoder is considered stopped.
+    pub fn set_max_period(&mut self, max_period: f64) -> HalResult<()> {
+        hal_call!(HAL_SetEncoderMaxPeriod(self.encoder, max_period))
+    }
+
+    /// Has the encoder gone longer than the max period without a count?
+    pub fn get_stopped(&self) -> HalResult<bool> {
+        Ok(hal_call!(HAL_GetEncoderStopped(self.encoder))? != 0)
+    }
+
+    /// Get the direction of the last count. True means forward.
+    pub fn get_direction(&self) -> HalResult<bool> {
+        Ok(hal_call!(HAL_GetEncoderDirection(self.encoder))? != 0)
+    }
+
+    /// Reverse the sense of the encoder, so that counts, distance, rate and direction are all
+    /// negated.
+    pub fn set_reverse_direction(&mut self, reverse: bool) -> HalResult<()> {
+        hal_call!(HAL_SetEncoderReverseDirection(self.encoder, reverse as i32))
+    }
+
+    /// Set the number of periods to average in `get_period`, from 1 to 127.
+    pub fn set_samples_to_average(&mut self, samples: i32) -> HalResult<()> {
+        hal_call!(HAL_SetEncoderSamplesToAverage(self.encoder, samples))
+    }
+
+    /// Get the number of periods averaged in `get_period`.
+    pub fn get_samples_to_average(&self) -> HalResult<i32> {
+        hal_call!(HAL_GetEncoderSamplesToAverage(self.encoder))
+    }
+
+    /// Reset the count from an index source, such as the index channel of the encoder, replacing
+    /// any previous index source.
+    pub fn set_index_source<S>(&mut self, source: S, indexing_type: IndexingType) -> HalResult<()>
+        where S: DigitalSource + Send + 'static
+    {
+        hal_call!(HAL_SetEncoderIndexSource(self.encoder,
+                                            source.get_handle_for_routing(),
+                                            source.get_analog_trigger_type_for_routing(),
+                                            indexing_type.into_hal()))?;
+        self.source_index = Some(Box::new(source));
+        Ok(())
+    }
+
+    /// Reset the count from a new digital input on the specified channel.
+    pub fn set_index_source_channel(&mut self,
+                                    channel: i32,
+                                    indexing_type: IndexingType)
+                                    -> HalResult<()> {
+        self.set_index_source(DigitalInput::new(channel)?, indexing_type)
+    }
+
     /// Reset the count of this encoder.
     pub fn reset(&mut self) -> HalResult<()> {
         hal_call!(HAL_ResetEncoder(self.encoder))
     }
+
+    /// Get the A and B sources of this encoder.
+    pub(crate) fn get_sources(&self) -> [&dyn DigitalSource; 2] {
+        [&*self.source_a, &*self.source_b]
+    }
 }
 
 impl Drop for Encoder {
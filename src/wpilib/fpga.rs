@@ -1,4 +1,3 @@
-use wpilib::wpilib_hal::*;
 use wpilib::hal_call::*;
 
 /// Gets the FPGA version
@@ -0,0 +1,20 @@
+/// A generic interface to a gyro, which measures rotation about a single axis. Angles are in
+/// degrees and increase clockwise, as seen from above.
+pub trait Gyro {
+    /// The error returned when the gyro cannot be read or calibrated.
+    type Error;
+
+    /// Measure the gyro's drift while it is still, so that it can be compensated for. This blocks
+    /// for several seconds, so it should only be called while the robot is disabled.
+    fn calibrate(&mut self) -> Result<(), Self::Error>;
+
+    /// Reset the angle to zero.
+    fn reset(&mut self) -> Result<(), Self::Error>;
+
+    /// Get the angle turned since the last reset or calibration, in degrees. This is continuous,
+    /// so it goes past 360 rather than wrapping around.
+    fn get_angle(&self) -> Result<f64, Self::Error>;
+
+    /// Get the rate of rotation, in degrees per second.
+    fn get_rate(&self) -> Result<f64, Self::Error>;
+}
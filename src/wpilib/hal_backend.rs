@@ -0,0 +1,500 @@
+//! The HAL backend abstraction. Every call into the HAL goes through the `HalBackend` trait, so the
+//! same wrappers can run against the real roboRIO HAL or against an in-process simulation.
+//!
+//! The backend is selected at compile time: building with the `athena` feature links against the
+//! NI and WPILib shared libraries and forwards every call to them, while building without it uses
+//! the pure-Rust simulated HAL so robot code can be compiled and tested on any machine.
+
+use wpilib::wpilib_hal::*;
+use std::os::raw::{c_char, c_void};
+
+/// Declare the HAL entry points used by this crate. This generates the `HalBackend` trait and,
+/// when the `athena` feature is enabled, its implementation forwarding to the C HAL.
+macro_rules! hal_backend {
+    ($(fn $name:ident($($arg:ident: $ty:ty),*) $(-> $ret:ty)*;)*) => {
+        /// A HAL implementation. Methods mirror the C HAL one-to-one, including the trailing
+        /// `status` out-parameter, so that `hal_call!` can be used with any backend.
+        #[allow(non_snake_case, clippy::too_many_arguments)]
+        pub trait HalBackend {
+            $(unsafe fn $name($($arg: $ty),*) $(-> $ret)*;)*
+        }
+
+        #[cfg(feature = "athena")]
+        #[allow(non_snake_case, clippy::too_many_arguments)]
+        impl HalBackend for Athena {
+            $(#[inline]
+            unsafe fn $name($($arg: $ty),*) $(-> $ret)* {
+                ::wpilib::wpilib_hal::$name($($arg),*)
+            })*
+        }
+    };
+}
+
+/// The roboRIO HAL, backed by `libHALAthena` and the NI libraries.
+#[cfg(feature = "athena")]
+pub struct Athena;
+
+/// The backend used by this build of the library.
+#[cfg(feature = "athena")]
+pub type Hal = Athena;
+
+/// The backend used by this build of the library.
+#[cfg(not(feature = "athena"))]
+pub type Hal = ::wpilib::hal_sim::Sim;
+
+hal_backend! {
+    // Accelerometer
+    fn HAL_SetAccelerometerActive(active: HAL_Bool);
+    fn HAL_SetAccelerometerRange(range: HAL_AccelerometerRange);
+    fn HAL_GetAccelerometerX() -> f64;
+    fn HAL_GetAccelerometerY() -> f64;
+    fn HAL_GetAccelerometerZ() -> f64;
+
+    // Analog accumulator
+    fn HAL_IsAccumulatorChannel(handle: HAL_AnalogInputHandle, status: *mut i32) -> HAL_Bool;
+    fn HAL_InitAccumulator(handle: HAL_AnalogInputHandle, status: *mut i32);
+    fn HAL_ResetAccumulator(handle: HAL_AnalogInputHandle, status: *mut i32);
+    fn HAL_SetAccumulatorCenter(handle: HAL_AnalogInputHandle, center: i32, status: *mut i32);
+    fn HAL_SetAccumulatorDeadband(handle: HAL_AnalogInputHandle, deadband: i32, status: *mut i32);
+    fn HAL_GetAccumulatorValue(handle: HAL_AnalogInputHandle, status: *mut i32) -> i64;
+    fn HAL_GetAccumulatorCount(handle: HAL_AnalogInputHandle, status: *mut i32) -> i64;
+    fn HAL_GetAccumulatorOutput(handle: HAL_AnalogInputHandle,
+                                value: *mut i64,
+                                count: *mut i64,
+                                status: *mut i32);
+
+    // Analog gyro
+    fn HAL_InitializeAnalogGyro(handle: HAL_AnalogInputHandle, status: *mut i32) -> HAL_GyroHandle;
+    fn HAL_SetupAnalogGyro(handle: HAL_GyroHandle, status: *mut i32);
+    fn HAL_FreeAnalogGyro(handle: HAL_GyroHandle);
+    fn HAL_SetAnalogGyroParameters(handle: HAL_GyroHandle,
+                                   volts_per_degree_per_second: f64,
+                                   offset: f64,
+                                   center: i32,
+                                   status: *mut i32);
+    fn HAL_SetAnalogGyroVoltsPerDegreePerSecond(handle: HAL_GyroHandle,
+                                                volts_per_degree_per_second: f64,
+                                                status: *mut i32);
+    fn HAL_ResetAnalogGyro(handle: HAL_GyroHandle, status: *mut i32);
+    fn HAL_CalibrateAnalogGyro(handle: HAL_GyroHandle, status: *mut i32);
+    fn HAL_SetAnalogGyroDeadband(handle: HAL_GyroHandle, volts: f64, status: *mut i32);
+    fn HAL_GetAnalogGyroAngle(handle: HAL_GyroHandle, status: *mut i32) -> f64;
+    fn HAL_GetAnalogGyroRate(handle: HAL_GyroHandle, status: *mut i32) -> f64;
+    fn HAL_GetAnalogGyroOffset(handle: HAL_GyroHandle, status: *mut i32) -> f64;
+    fn HAL_GetAnalogGyroCenter(handle: HAL_GyroHandle, status: *mut i32) -> i32;
+
+    // Analog input
+    fn HAL_InitializeAnalogInputPort(port_handle: HAL_PortHandle,
+                                     status: *mut i32) -> HAL_AnalogInputHandle;
+    fn HAL_FreeAnalogInputPort(handle: HAL_AnalogInputHandle);
+    fn HAL_CheckAnalogInputChannel(channel: i32) -> HAL_Bool;
+    fn HAL_SetAnalogSampleRate(samples_per_second: f64, status: *mut i32);
+    fn HAL_GetAnalogSampleRate(status: *mut i32) -> f64;
+    fn HAL_SetAnalogAverageBits(handle: HAL_AnalogInputHandle, bits: i32, status: *mut i32);
+    fn HAL_GetAnalogAverageBits(handle: HAL_AnalogInputHandle, status: *mut i32) -> i32;
+    fn HAL_SetAnalogOversampleBits(handle: HAL_AnalogInputHandle, bits: i32, status: *mut i32);
+    fn HAL_GetAnalogOversampleBits(handle: HAL_AnalogInputHandle, status: *mut i32) -> i32;
+    fn HAL_GetAnalogValue(handle: HAL_AnalogInputHandle, status: *mut i32) -> i32;
+    fn HAL_GetAnalogAverageValue(handle: HAL_AnalogInputHandle, status: *mut i32) -> i32;
+    fn HAL_GetAnalogVoltage(handle: HAL_AnalogInputHandle, status: *mut i32) -> f64;
+    fn HAL_GetAnalogAverageVoltage(handle: HAL_AnalogInputHandle, status: *mut i32) -> f64;
+    fn HAL_GetAnalogLSBWeight(handle: HAL_AnalogInputHandle, status: *mut i32) -> i32;
+    fn HAL_GetAnalogOffset(handle: HAL_AnalogInputHandle, status: *mut i32) -> i32;
+    fn HAL_GetNumAnalogInputs() -> i32;
+
+    // Analog output
+    fn HAL_InitializeAnalogOutputPort(port_handle: HAL_PortHandle,
+                                      status: *mut i32) -> HAL_AnalogOutputHandle;
+    fn HAL_FreeAnalogOutputPort(handle: HAL_AnalogOutputHandle);
+    fn HAL_SetAnalogOutput(handle: HAL_AnalogOutputHandle, voltage: f64, status: *mut i32);
+    fn HAL_GetAnalogOutput(handle: HAL_AnalogOutputHandle, status: *mut i32) -> f64;
+    fn HAL_CheckAnalogOutputChannel(channel: i32) -> HAL_Bool;
+
+    // Analog trigger
+    fn HAL_InitializeAnalogTrigger(port_handle: HAL_AnalogInputHandle,
+                                   index: *mut i32,
+                                   status: *mut i32) -> HAL_AnalogTriggerHandle;
+    fn HAL_CleanAnalogTrigger(handle: HAL_AnalogTriggerHandle, status: *mut i32);
+    fn HAL_SetAnalogTriggerLimitsRaw(handle: HAL_AnalogTriggerHandle,
+                                     lower: i32,
+                                     upper: i32,
+                                     status: *mut i32);
+    fn HAL_SetAnalogTriggerLimitsVoltage(handle: HAL_AnalogTriggerHandle,
+                                         lower: f64,
+                                         upper: f64,
+                                         status: *mut i32);
+    fn HAL_SetAnalogTriggerAveraged(handle: HAL_AnalogTriggerHandle,
+                                    use_averaged_value: HAL_Bool,
+                                    status: *mut i32);
+    fn HAL_SetAnalogTriggerFiltered(handle: HAL_AnalogTriggerHandle,
+                                    use_filtered_value: HAL_Bool,
+                                    status: *mut i32);
+    fn HAL_GetAnalogTriggerInWindow(handle: HAL_AnalogTriggerHandle, status: *mut i32) -> HAL_Bool;
+    fn HAL_GetAnalogTriggerTriggerState(handle: HAL_AnalogTriggerHandle,
+                                        status: *mut i32) -> HAL_Bool;
+    fn HAL_GetAnalogTriggerOutput(handle: HAL_AnalogTriggerHandle,
+                                  trigger_type: HAL_AnalogTriggerType,
+                                  status: *mut i32) -> HAL_Bool;
+
+    // Compressor
+    fn HAL_InitializeCompressor(module: i32, status: *mut i32) -> HAL_CompressorHandle;
+    fn HAL_CheckCompressorModule(module: i32) -> HAL_Bool;
+    fn HAL_GetCompressor(handle: HAL_CompressorHandle, status: *mut i32) -> HAL_Bool;
+    fn HAL_SetCompressorClosedLoopControl(handle: HAL_CompressorHandle,
+                                          value: HAL_Bool,
+                                          status: *mut i32);
+    fn HAL_GetCompressorClosedLoopControl(handle: HAL_CompressorHandle,
+                                          status: *mut i32) -> HAL_Bool;
+    fn HAL_GetCompressorPressureSwitch(handle: HAL_CompressorHandle, status: *mut i32) -> HAL_Bool;
+    fn HAL_GetCompressorCurrent(handle: HAL_CompressorHandle, status: *mut i32) -> f64;
+    fn HAL_GetCompressorCurrentTooHighFault(handle: HAL_CompressorHandle,
+                                            status: *mut i32) -> HAL_Bool;
+    fn HAL_GetCompressorCurrentTooHighStickyFault(handle: HAL_CompressorHandle,
+                                                  status: *mut i32) -> HAL_Bool;
+    fn HAL_GetCompressorShortedStickyFault(handle: HAL_CompressorHandle,
+                                           status: *mut i32) -> HAL_Bool;
+    fn HAL_GetCompressorShortedFault(handle: HAL_CompressorHandle, status: *mut i32) -> HAL_Bool;
+    fn HAL_GetCompressorNotConnectedStickyFault(handle: HAL_CompressorHandle,
+                                                status: *mut i32) -> HAL_Bool;
+    fn HAL_GetCompressorNotConnectedFault(handle: HAL_CompressorHandle,
+                                          status: *mut i32) -> HAL_Bool;
+
+    // Counter
+    fn HAL_InitializeCounter(mode: HAL_Counter_Mode,
+                             index: *mut i32,
+                             status: *mut i32) -> HAL_CounterHandle;
+    fn HAL_FreeCounter(handle: HAL_CounterHandle, status: *mut i32);
+    fn HAL_SetCounterUpSource(handle: HAL_CounterHandle,
+                              digital_source_handle: HAL_Handle,
+                              analog_trigger_type: HAL_AnalogTriggerType,
+                              status: *mut i32);
+    fn HAL_SetCounterUpSourceEdge(handle: HAL_CounterHandle,
+                                  rising_edge: HAL_Bool,
+                                  falling_edge: HAL_Bool,
+                                  status: *mut i32);
+    fn HAL_ClearCounterUpSource(handle: HAL_CounterHandle, status: *mut i32);
+    fn HAL_SetCounterDownSource(handle: HAL_CounterHandle,
+                                digital_source_handle: HAL_Handle,
+                                analog_trigger_type: HAL_AnalogTriggerType,
+                                status: *mut i32);
+    fn HAL_SetCounterDownSourceEdge(handle: HAL_CounterHandle,
+                                    rising_edge: HAL_Bool,
+                                    falling_edge: HAL_Bool,
+                                    status: *mut i32);
+    fn HAL_ClearCounterDownSource(handle: HAL_CounterHandle, status: *mut i32);
+    fn HAL_SetCounterUpDownMode(handle: HAL_CounterHandle, status: *mut i32);
+    fn HAL_SetCounterExternalDirectionMode(handle: HAL_CounterHandle, status: *mut i32);
+    fn HAL_SetCounterSemiPeriodMode(handle: HAL_CounterHandle,
+                                    high_semi_period: HAL_Bool,
+                                    status: *mut i32);
+    fn HAL_SetCounterPulseLengthMode(handle: HAL_CounterHandle, threshold: f64, status: *mut i32);
+    fn HAL_GetCounterSamplesToAverage(handle: HAL_CounterHandle, status: *mut i32) -> i32;
+    fn HAL_SetCounterSamplesToAverage(handle: HAL_CounterHandle,
+                                      samples_to_average: i32,
+                                      status: *mut i32);
+    fn HAL_ResetCounter(handle: HAL_CounterHandle, status: *mut i32);
+    fn HAL_GetCounter(handle: HAL_CounterHandle, status: *mut i32) -> i32;
+    fn HAL_GetCounterPeriod(handle: HAL_CounterHandle, status: *mut i32) -> f64;
+    fn HAL_SetCounterMaxPeriod(handle: HAL_CounterHandle, max_period: f64, status: *mut i32);
+    fn HAL_SetCounterUpdateWhenEmpty(handle: HAL_CounterHandle,
+                                     enabled: HAL_Bool,
+                                     status: *mut i32);
+    fn HAL_GetCounterStopped(handle: HAL_CounterHandle, status: *mut i32) -> HAL_Bool;
+    fn HAL_GetCounterDirection(handle: HAL_CounterHandle, status: *mut i32) -> HAL_Bool;
+    fn HAL_SetCounterReverseDirection(handle: HAL_CounterHandle,
+                                      reverse_direction: HAL_Bool,
+                                      status: *mut i32);
+
+    // Digital I/O
+    fn HAL_InitializeDIOPort(port_handle: HAL_PortHandle,
+                             input: HAL_Bool,
+                             status: *mut i32) -> HAL_DigitalHandle;
+    fn HAL_CheckDIOChannel(channel: i32) -> HAL_Bool;
+    fn HAL_FreeDIOPort(handle: HAL_DigitalHandle);
+    fn HAL_AllocateDigitalPWM(status: *mut i32) -> HAL_DigitalPWMHandle;
+    fn HAL_FreeDigitalPWM(pwm_generator: HAL_DigitalPWMHandle, status: *mut i32);
+    fn HAL_SetDigitalPWMRate(rate: f64, status: *mut i32);
+    fn HAL_SetDigitalPWMDutyCycle(pwm_generator: HAL_DigitalPWMHandle,
+                                  duty_cycle: f64,
+                                  status: *mut i32);
+    fn HAL_SetDigitalPWMOutputChannel(pwm_generator: HAL_DigitalPWMHandle,
+                                      channel: i32,
+                                      status: *mut i32);
+    fn HAL_SetDIO(handle: HAL_DigitalHandle, value: HAL_Bool, status: *mut i32);
+    fn HAL_GetDIO(handle: HAL_DigitalHandle, status: *mut i32) -> HAL_Bool;
+    fn HAL_Pulse(handle: HAL_DigitalHandle, pulse_length: f64, status: *mut i32);
+    fn HAL_IsPulsing(handle: HAL_DigitalHandle, status: *mut i32) -> HAL_Bool;
+    fn HAL_SetFilterSelect(handle: HAL_DigitalHandle, filter_index: i32, status: *mut i32);
+    fn HAL_GetFilterSelect(handle: HAL_DigitalHandle, status: *mut i32) -> i32;
+    fn HAL_SetFilterPeriod(filter_index: i32, value: i64, status: *mut i32);
+    fn HAL_GetFilterPeriod(filter_index: i32, status: *mut i32) -> i64;
+
+    // Driver station
+    fn HAL_SendError(is_error: HAL_Bool,
+                     error_code: i32,
+                     is_l_v_code: HAL_Bool,
+                     details: *const c_char,
+                     location: *const c_char,
+                     call_stack: *const c_char,
+                     print_msg: HAL_Bool) -> i32;
+    fn HAL_GetControlWord(control_word: *mut HAL_ControlWord) -> i32;
+    fn HAL_GetAllianceStation(status: *mut i32) -> HAL_AllianceStationID;
+    fn HAL_GetJoystickAxes(joystick_num: i32, axes: *mut HAL_JoystickAxes) -> i32;
+    fn HAL_GetJoystickPOVs(joystick_num: i32, povs: *mut HAL_JoystickPOVs) -> i32;
+    fn HAL_GetJoystickButtons(joystick_num: i32, buttons: *mut HAL_JoystickButtons) -> i32;
+    fn HAL_GetJoystickDescriptor(joystick_num: i32, desc: *mut HAL_JoystickDescriptor) -> i32;
+    fn HAL_SetJoystickOutputs(joystick_num: i32,
+                              outputs: i64,
+                              left_rumble: i32,
+                              right_rumble: i32) -> i32;
+    fn HAL_WaitForDSData();
+    fn HAL_ObserveUserProgramStarting();
+    fn HAL_ObserveUserProgramDisabled();
+    fn HAL_ObserveUserProgramAutonomous();
+    fn HAL_ObserveUserProgramTeleop();
+    fn HAL_ObserveUserProgramTest();
+
+    // Encoder
+    fn HAL_InitializeEncoder(digital_source_handle_a: HAL_Handle,
+                             analog_trigger_type_a: HAL_AnalogTriggerType,
+                             digital_source_handle_b: HAL_Handle,
+                             analog_trigger_type_b: HAL_AnalogTriggerType,
+                             reverse_direction: HAL_Bool,
+                             encoding_type: HAL_EncoderEncodingType,
+                             status: *mut i32) -> HAL_EncoderHandle;
+    fn HAL_FreeEncoder(handle: HAL_EncoderHandle, status: *mut i32);
+    fn HAL_GetEncoder(handle: HAL_EncoderHandle, status: *mut i32) -> i32;
+    fn HAL_GetEncoderRaw(handle: HAL_EncoderHandle, status: *mut i32) -> i32;
+    fn HAL_GetEncoderEncodingScale(handle: HAL_EncoderHandle, status: *mut i32) -> i32;
+    fn HAL_ResetEncoder(handle: HAL_EncoderHandle, status: *mut i32);
+    fn HAL_GetEncoderPeriod(handle: HAL_EncoderHandle, status: *mut i32) -> f64;
+    fn HAL_SetEncoderMaxPeriod(handle: HAL_EncoderHandle, max_period: f64, status: *mut i32);
+    fn HAL_GetEncoderStopped(handle: HAL_EncoderHandle, status: *mut i32) -> HAL_Bool;
+    fn HAL_GetEncoderDirection(handle: HAL_EncoderHandle, status: *mut i32) -> HAL_Bool;
+    fn HAL_GetEncoderDistance(handle: HAL_EncoderHandle, status: *mut i32) -> f64;
+    fn HAL_GetEncoderRate(handle: HAL_EncoderHandle, status: *mut i32) -> f64;
+    fn HAL_SetEncoderMinRate(handle: HAL_EncoderHandle, min_rate: f64, status: *mut i32);
+    fn HAL_SetEncoderDistancePerPulse(handle: HAL_EncoderHandle,
+                                      distance_per_pulse: f64,
+                                      status: *mut i32);
+    fn HAL_SetEncoderReverseDirection(handle: HAL_EncoderHandle,
+                                      reverse_direction: HAL_Bool,
+                                      status: *mut i32);
+    fn HAL_SetEncoderSamplesToAverage(handle: HAL_EncoderHandle,
+                                      samples_to_average: i32,
+                                      status: *mut i32);
+    fn HAL_GetEncoderSamplesToAverage(handle: HAL_EncoderHandle, status: *mut i32) -> i32;
+    fn HAL_SetEncoderIndexSource(handle: HAL_EncoderHandle,
+                                 digital_source_handle: HAL_Handle,
+                                 analog_trigger_type: HAL_AnalogTriggerType,
+                                 indexing_type: HAL_EncoderIndexingType,
+                                 status: *mut i32);
+    fn HAL_GetEncoderFPGAIndex(handle: HAL_EncoderHandle, status: *mut i32) -> i32;
+    fn HAL_GetEncoderDecodingScaleFactor(handle: HAL_EncoderHandle, status: *mut i32) -> f64;
+    fn HAL_GetEncoderDistancePerPulse(handle: HAL_EncoderHandle, status: *mut i32) -> f64;
+
+    // I2C
+    fn HAL_InitializeI2C(port: i32, status: *mut i32);
+    fn HAL_TransactionI2C(port: i32,
+                          device_address: i32,
+                          data_to_send: *mut u8,
+                          send_size: i32,
+                          data_received: *mut u8,
+                          receive_size: i32) -> i32;
+    fn HAL_WriteI2C(port: i32, device_address: i32, data_to_send: *mut u8, send_size: i32) -> i32;
+    fn HAL_ReadI2C(port: i32, device_address: i32, buffer: *mut u8, count: i32) -> i32;
+    fn HAL_CloseI2C(port: i32);
+
+    // Interrupts
+    fn HAL_InitializeInterrupts(watcher: HAL_Bool, status: *mut i32) -> HAL_InterruptHandle;
+    fn HAL_CleanInterrupts(handle: HAL_InterruptHandle, status: *mut i32);
+    fn HAL_WaitForInterrupt(handle: HAL_InterruptHandle,
+                            timeout: f64,
+                            ignore_previous: HAL_Bool,
+                            status: *mut i32) -> i64;
+    fn HAL_EnableInterrupts(handle: HAL_InterruptHandle, status: *mut i32);
+    fn HAL_DisableInterrupts(handle: HAL_InterruptHandle, status: *mut i32);
+    fn HAL_ReadInterruptRisingTimestamp(handle: HAL_InterruptHandle, status: *mut i32) -> f64;
+    fn HAL_ReadInterruptFallingTimestamp(handle: HAL_InterruptHandle, status: *mut i32) -> f64;
+    fn HAL_RequestInterrupts(handle: HAL_InterruptHandle,
+                             digital_source_handle: HAL_Handle,
+                             analog_trigger_type: HAL_AnalogTriggerType,
+                             status: *mut i32);
+    fn HAL_AttachInterruptHandler(handle: HAL_InterruptHandle,
+                                  handler: HAL_InterruptHandlerFunction,
+                                  param: *mut c_void,
+                                  status: *mut i32);
+    fn HAL_AttachInterruptHandlerThreaded(handle: HAL_InterruptHandle,
+                                          handler: HAL_InterruptHandlerFunction,
+                                          param: *mut c_void,
+                                          status: *mut i32);
+    fn HAL_SetInterruptUpSourceEdge(handle: HAL_InterruptHandle,
+                                    rising_edge: HAL_Bool,
+                                    falling_edge: HAL_Bool,
+                                    status: *mut i32);
+
+    // Notifier
+    fn HAL_InitializeNotifier(process: HAL_NotifierProcessFunction,
+                              param: *mut c_void,
+                              status: *mut i32) -> HAL_NotifierHandle;
+    fn HAL_CleanNotifier(handle: HAL_NotifierHandle, status: *mut i32);
+    fn HAL_GetNotifierParam(handle: HAL_NotifierHandle, status: *mut i32) -> *mut c_void;
+    fn HAL_UpdateNotifierAlarm(handle: HAL_NotifierHandle, trigger_time: u64, status: *mut i32);
+    fn HAL_StopNotifierAlarm(handle: HAL_NotifierHandle, status: *mut i32);
+
+    // PDP
+    fn HAL_InitializePDP(module: i32, status: *mut i32);
+    fn HAL_CheckPDPModule(module: i32) -> HAL_Bool;
+    fn HAL_GetPDPTemperature(module: i32, status: *mut i32) -> f64;
+    fn HAL_GetPDPVoltage(module: i32, status: *mut i32) -> f64;
+    fn HAL_GetPDPChannelCurrent(module: i32, channel: i32, status: *mut i32) -> f64;
+    fn HAL_GetPDPTotalCurrent(module: i32, status: *mut i32) -> f64;
+    fn HAL_GetPDPTotalPower(module: i32, status: *mut i32) -> f64;
+    fn HAL_GetPDPTotalEnergy(module: i32, status: *mut i32) -> f64;
+    fn HAL_ResetPDPTotalEnergy(module: i32, status: *mut i32);
+    fn HAL_ClearPDPStickyFaults(module: i32, status: *mut i32);
+
+    // PWM
+    fn HAL_InitializePWMPort(port_handle: HAL_PortHandle, status: *mut i32) -> HAL_DigitalHandle;
+    fn HAL_FreePWMPort(handle: HAL_DigitalHandle, status: *mut i32);
+    fn HAL_CheckPWMChannel(channel: i32) -> HAL_Bool;
+    fn HAL_SetPWMConfig(handle: HAL_DigitalHandle,
+                        max_pwm: f64,
+                        deadband_max_pwm: f64,
+                        center_pwm: f64,
+                        deadband_min_pwm: f64,
+                        min_pwm: f64,
+                        status: *mut i32);
+    fn HAL_SetPWMConfigRaw(handle: HAL_DigitalHandle,
+                           max_pwm: i32,
+                           deadband_max_pwm: i32,
+                           center_pwm: i32,
+                           deadband_min_pwm: i32,
+                           min_pwm: i32,
+                           status: *mut i32);
+    fn HAL_SetPWMEliminateDeadband(handle: HAL_DigitalHandle,
+                                   eliminate_deadband: HAL_Bool,
+                                   status: *mut i32);
+    fn HAL_SetPWMRaw(handle: HAL_DigitalHandle, value: i32, status: *mut i32);
+    fn HAL_SetPWMSpeed(handle: HAL_DigitalHandle, speed: f64, status: *mut i32);
+    fn HAL_SetPWMPosition(handle: HAL_DigitalHandle, position: f64, status: *mut i32);
+    fn HAL_SetPWMDisabled(handle: HAL_DigitalHandle, status: *mut i32);
+    fn HAL_GetPWMRaw(handle: HAL_DigitalHandle, status: *mut i32) -> i32;
+    fn HAL_GetPWMSpeed(handle: HAL_DigitalHandle, status: *mut i32) -> f64;
+    fn HAL_LatchPWMZero(handle: HAL_DigitalHandle, status: *mut i32);
+    fn HAL_SetPWMPeriodScale(handle: HAL_DigitalHandle, squelch_mask: i32, status: *mut i32);
+    fn HAL_GetNumPWMChannels() -> i32;
+
+    // Ports
+    fn HAL_GetNumDigitalChannels() -> i32;
+    fn HAL_GetNumRelayHeaders() -> i32;
+    fn HAL_GetNumPCMModules() -> i32;
+    fn HAL_GetNumSolenoidChannels() -> i32;
+
+    // Power
+    fn HAL_GetVinVoltage(status: *mut i32) -> f64;
+    fn HAL_GetUserVoltage5V(status: *mut i32) -> f64;
+
+    // Relay
+    fn HAL_InitializeRelayPort(port_handle: HAL_PortHandle,
+                               fwd: HAL_Bool,
+                               status: *mut i32)
+                               -> HAL_RelayHandle;
+    fn HAL_FreeRelayPort(handle: HAL_RelayHandle);
+    fn HAL_CheckRelayChannel(channel: i32) -> HAL_Bool;
+    fn HAL_SetRelay(handle: HAL_RelayHandle, on: HAL_Bool, status: *mut i32);
+    fn HAL_GetRelay(handle: HAL_RelayHandle, status: *mut i32) -> HAL_Bool;
+
+    // Serial
+    fn HAL_InitializeSerialPort(port: HAL_SerialPort, status: *mut i32);
+    fn HAL_SetSerialBaudRate(port: HAL_SerialPort, baud: i32, status: *mut i32);
+    fn HAL_SetSerialDataBits(port: HAL_SerialPort, bits: i32, status: *mut i32);
+    fn HAL_SetSerialParity(port: HAL_SerialPort, parity: i32, status: *mut i32);
+    fn HAL_SetSerialStopBits(port: HAL_SerialPort, stop_bits: i32, status: *mut i32);
+    fn HAL_SetSerialWriteMode(port: HAL_SerialPort, mode: i32, status: *mut i32);
+    fn HAL_SetSerialFlowControl(port: HAL_SerialPort, flow: i32, status: *mut i32);
+    fn HAL_SetSerialTimeout(port: HAL_SerialPort, timeout: f64, status: *mut i32);
+    fn HAL_EnableSerialTermination(port: HAL_SerialPort, terminator: c_char, status: *mut i32);
+    fn HAL_DisableSerialTermination(port: HAL_SerialPort, status: *mut i32);
+    fn HAL_SetSerialReadBufferSize(port: HAL_SerialPort, size: i32, status: *mut i32);
+    fn HAL_SetSerialWriteBufferSize(port: HAL_SerialPort, size: i32, status: *mut i32);
+    fn HAL_GetSerialBytesReceived(port: HAL_SerialPort, status: *mut i32) -> i32;
+    fn HAL_ReadSerial(port: HAL_SerialPort,
+                      buffer: *mut c_char,
+                      count: i32,
+                      status: *mut i32) -> i32;
+    fn HAL_WriteSerial(port: HAL_SerialPort,
+                       buffer: *const c_char,
+                       count: i32,
+                       status: *mut i32) -> i32;
+    fn HAL_FlushSerial(port: HAL_SerialPort, status: *mut i32);
+    fn HAL_ClearSerial(port: HAL_SerialPort, status: *mut i32);
+    fn HAL_CloseSerial(port: HAL_SerialPort, status: *mut i32);
+
+    // SPI
+    fn HAL_InitializeSPI(port: i32, status: *mut i32);
+    fn HAL_TransactionSPI(port: i32,
+                          data_to_send: *mut u8,
+                          data_received: *mut u8,
+                          size: i32) -> i32;
+    fn HAL_WriteSPI(port: i32, data_to_send: *mut u8, send_size: i32) -> i32;
+    fn HAL_ReadSPI(port: i32, buffer: *mut u8, count: i32) -> i32;
+    fn HAL_CloseSPI(port: i32);
+    fn HAL_SetSPISpeed(port: i32, speed: i32);
+    fn HAL_SetSPIOpts(port: i32,
+                      msb_first: HAL_Bool,
+                      sample_on_trailing: HAL_Bool,
+                      clk_idle_high: HAL_Bool);
+    fn HAL_SetSPIChipSelectActiveHigh(port: i32, status: *mut i32);
+    fn HAL_SetSPIChipSelectActiveLow(port: i32, status: *mut i32);
+    fn HAL_InitSPIAccumulator(port: i32,
+                              period: i32,
+                              cmd: i32,
+                              xfer_size: i32,
+                              valid_mask: i32,
+                              valid_value: i32,
+                              data_shift: i32,
+                              data_size: i32,
+                              is_signed: HAL_Bool,
+                              big_endian: HAL_Bool,
+                              status: *mut i32);
+    fn HAL_FreeSPIAccumulator(port: i32, status: *mut i32);
+    fn HAL_ResetSPIAccumulator(port: i32, status: *mut i32);
+    fn HAL_SetSPIAccumulatorCenter(port: i32, center: i32, status: *mut i32);
+    fn HAL_SetSPIAccumulatorDeadband(port: i32, deadband: i32, status: *mut i32);
+    fn HAL_GetSPIAccumulatorLastValue(port: i32, status: *mut i32) -> i32;
+    fn HAL_GetSPIAccumulatorValue(port: i32, status: *mut i32) -> i64;
+    fn HAL_GetSPIAccumulatorCount(port: i32, status: *mut i32) -> i64;
+    fn HAL_GetSPIAccumulatorAverage(port: i32, status: *mut i32) -> f64;
+    fn HAL_GetSPIAccumulatorOutput(port: i32, value: *mut i64, count: *mut i64, status: *mut i32);
+
+    // Solenoid
+    fn HAL_InitializeSolenoidPort(port_handle: HAL_PortHandle,
+                                  status: *mut i32) -> HAL_SolenoidHandle;
+    fn HAL_FreeSolenoidPort(handle: HAL_SolenoidHandle);
+    fn HAL_CheckSolenoidModule(module: i32) -> HAL_Bool;
+    fn HAL_CheckSolenoidChannel(channel: i32) -> HAL_Bool;
+    fn HAL_GetSolenoid(handle: HAL_SolenoidHandle, status: *mut i32) -> HAL_Bool;
+    fn HAL_GetAllSolenoids(module: i32, status: *mut i32) -> i32;
+    fn HAL_SetSolenoid(handle: HAL_SolenoidHandle, value: HAL_Bool, status: *mut i32);
+    fn HAL_SetAllSolenoids(module: i32, state: i32, status: *mut i32);
+    fn HAL_GetPCMSolenoidBlackList(module: i32, status: *mut i32) -> i32;
+    fn HAL_GetPCMSolenoidVoltageStickyFault(module: i32, status: *mut i32) -> HAL_Bool;
+    fn HAL_GetPCMSolenoidVoltageFault(module: i32, status: *mut i32) -> HAL_Bool;
+    fn HAL_ClearAllPCMStickyFaults(module: i32, status: *mut i32);
+
+    // HAL
+    fn HAL_GetErrorMessage(code: i32) -> *const c_char;
+    fn HAL_GetFPGAVersion(status: *mut i32) -> i32;
+    fn HAL_GetFPGARevision(status: *mut i32) -> i64;
+    fn HAL_GetFPGAButton(status: *mut i32) -> HAL_Bool;
+    fn HAL_GetSystemActive(status: *mut i32) -> HAL_Bool;
+    fn HAL_GetBrownedOut(status: *mut i32) -> HAL_Bool;
+    fn HAL_GetPort(channel: i32) -> HAL_PortHandle;
+    fn HAL_GetPortWithModule(module: i32, channel: i32) -> HAL_PortHandle;
+    fn HAL_GetFPGATime(status: *mut i32) -> u64;
+    fn HAL_GetSystemClockTicksPerMicrosecond() -> i32;
+    fn HAL_Initialize(mode: i32) -> i32;
+    fn HAL_Report(resource: i32, instance_number: i32, context: i32, feature: *const c_char) -> i64;
+
+}
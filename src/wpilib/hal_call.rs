@@ -1,4 +1,4 @@
-use wpilib::wpilib_hal::*;
+pub use wpilib::hal_backend::{Hal, HalBackend};
 use std::{ffi, fmt};
 
 #[derive(Copy, Clone)]
@@ -12,7 +12,7 @@ impl From<i32> for HalError {
 
 impl fmt::Debug for HalError {
     fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
-        let error_string = unsafe { ffi::CStr::from_ptr(HAL_GetErrorMessage(self.0)) };
+        let error_string = unsafe { ffi::CStr::from_ptr(Hal::HAL_GetErrorMessage(self.0)) };
         write!(f, "HalError {{ {} }}", error_string.to_str().unwrap())
     }
 }
@@ -31,11 +31,12 @@ pub type HalResult<T> = Result<T, HalError>;
 //     }
 // }
 
-#[macro_export]
+/// Call a HAL function on the selected backend, converting its `status` out-parameter into a
+/// `HalResult`.
 macro_rules! hal_call {
     ($function:ident($($arg:expr),*)) => {{
         let mut status = 0;
-        let result = unsafe { $function($(
+        let result = unsafe { <Hal as HalBackend>::$function($(
             $arg,
         )* &mut status as *mut i32) };
         if status == 0 { Ok(result) } else { Err(HalError::from(status)) }
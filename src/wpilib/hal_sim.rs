@@ -0,0 +1,3158 @@
+//! A pure-Rust implementation of the HAL. Every device is backed by in-memory state instead of the
+//! FPGA, so code built on top of it can run on any machine.
+
+use wpilib::wpilib_hal::*;
+use wpilib::hal_backend::HalBackend;
+use std::os::raw::{c_char, c_void};
+use std::cell::Cell;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::{ffi, thread, time};
+
+pub const NUM_DIGITAL_CHANNELS: usize = 26;
+pub const NUM_PWM_CHANNELS: usize = 20;
+pub const NUM_ANALOG_INPUTS: usize = 8;
+pub const NUM_ANALOG_OUTPUTS: usize = 2;
+pub const NUM_ACCUMULATORS: usize = 2;
+pub const NUM_ANALOG_TRIGGERS: usize = 8;
+pub const NUM_RELAY_HEADERS: usize = 4;
+pub const NUM_ENCODERS: usize = 8;
+pub const NUM_COUNTERS: usize = 8;
+pub const NUM_INTERRUPTS: usize = 8;
+pub const NUM_NOTIFIERS: usize = 32;
+pub const NUM_DIGITAL_PWM: usize = 6;
+pub const NUM_DIGITAL_FILTERS: usize = 3;
+pub const NUM_PCM_MODULES: usize = 63;
+pub const NUM_SOLENOID_CHANNELS: usize = 8;
+pub const NUM_PDP_MODULES: usize = 63;
+pub const NUM_PDP_CHANNELS: usize = 16;
+pub const NUM_JOYSTICKS: usize = 6;
+pub const NUM_I2C_PORTS: usize = 2;
+pub const NUM_SPI_PORTS: usize = 5;
+pub const NUM_SERIAL_PORTS: usize = 4;
+
+/// The time between driver station packets.
+const DS_PACKET_PERIOD_MS: u64 = 20;
+
+/// The rate of the FPGA's system clock, which the roboRIO runs at 40MHz.
+const SYSTEM_CLOCK_TICKS_PER_MICROSECOND: i32 = 40;
+
+/// PWM timing constants, matching the roboRIO's FPGA.
+const PWM_CENTER_MS: f64 = 1.5;
+const PWM_STEPS_DOWN: i32 = 1000;
+const PWM_STEP_MS: f64 = 0.001;
+
+/// Factory scaling constants for the analog inputs, in nanovolts.
+const ANALOG_LSB_WEIGHT: i32 = 1220703;
+const ANALOG_OFFSET: i32 = 0;
+
+/// The kind of resource a handle refers to, stored in the upper byte of the handle.
+#[derive(Copy, Clone, PartialEq)]
+enum HandleKind {
+    Port = 1,
+    Digital = 2,
+    Pwm = 3,
+    AnalogInput = 4,
+    Encoder = 5,
+    DigitalPwm = 6,
+    Interrupt = 7,
+    Notifier = 8,
+    Solenoid = 9,
+    Compressor = 10,
+    Relay = 11,
+    Counter = 12,
+    AnalogTrigger = 13,
+    AnalogOutput = 14,
+    AnalogGyro = 15,
+}
+
+/// A fixed set of devices of one kind, each of which may be allocated to at most one user.
+pub struct Resources<T> {
+    kind: HandleKind,
+    pub devices: Vec<T>,
+    pub allocated: Vec<bool>,
+}
+
+impl<T: Default> Resources<T> {
+    fn new(kind: HandleKind, count: usize) -> Resources<T> {
+        Resources {
+            kind,
+            devices: (0..count).map(|_| T::default()).collect(),
+            allocated: vec![false; count],
+        }
+    }
+
+    /// Allocate the device at `index`, returning its handle.
+    fn allocate(&mut self, index: usize) -> Result<HAL_Handle, i32> {
+        if index >= self.devices.len() {
+            Err(RESOURCE_OUT_OF_RANGE as i32)
+        } else if self.allocated[index] {
+            Err(RESOURCE_IS_ALLOCATED as i32)
+        } else {
+            self.allocated[index] = true;
+            Ok(self.handle(index))
+        }
+    }
+
+    /// Get the handle for the device at `index`.
+    fn handle(&self, index: usize) -> HAL_Handle {
+        ((self.kind as i32) << 24) | index as i32
+    }
+
+    /// Allocate the first free device, returning its handle.
+    fn allocate_any(&mut self) -> Result<HAL_Handle, i32> {
+        match self.allocated.iter().position(|allocated| !allocated) {
+            Some(index) => self.allocate(index),
+            None => Err(NO_AVAILABLE_RESOURCES as i32),
+        }
+    }
+
+    /// Get the index of the allocated device a handle refers to.
+    fn index(&self, handle: HAL_Handle) -> Result<usize, i32> {
+        let index = (handle & 0xffffff) as usize;
+        if handle >> 24 != self.kind as i32 || index >= self.devices.len() ||
+           !self.allocated[index] {
+            Err(HAL_HANDLE_ERROR as i32)
+        } else {
+            Ok(index)
+        }
+    }
+
+    /// Get the allocated device a handle refers to.
+    fn get(&mut self, handle: HAL_Handle) -> Result<&mut T, i32> {
+        let index = self.index(handle)?;
+        Ok(&mut self.devices[index])
+    }
+
+    /// Release a device so that it can be allocated again.
+    fn free(&mut self, handle: HAL_Handle) -> Result<(), i32> {
+        let index = self.index(handle)?;
+        self.allocated[index] = false;
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct DigitalState {
+    pub is_input: bool,
+    pub value: bool,
+    pulse_end_us: u64,
+    /// The glitch filter applied to the input, or 0 for none. Filters don't affect the simulated
+    /// value.
+    pub filter: i32,
+}
+
+#[derive(Copy, Clone)]
+struct PwmConfig {
+    max: i32,
+    deadband_max: i32,
+    center: i32,
+    deadband_min: i32,
+    min: i32,
+}
+
+#[derive(Default)]
+pub struct PwmState {
+    pub raw: i32,
+    config: Option<PwmConfig>,
+    eliminate_deadband: bool,
+    pub zero_latch: bool,
+    pub period_scale: i32,
+}
+
+impl PwmState {
+    fn config(&self) -> Result<PwmConfig, i32> {
+        self.config.ok_or(HAL_PWM_SCALE_ERROR as i32)
+    }
+
+    fn min_positive(&self, config: &PwmConfig) -> i32 {
+        if self.eliminate_deadband {
+            config.deadband_max
+        } else {
+            config.center + 1
+        }
+    }
+
+    fn max_negative(&self, config: &PwmConfig) -> i32 {
+        if self.eliminate_deadband {
+            config.deadband_min
+        } else {
+            config.center - 1
+        }
+    }
+
+    /// The speed the current raw value represents, in [-1, 1].
+    pub fn speed(&self) -> Result<f64, i32> {
+        let config = self.config()?;
+        let min_positive = self.min_positive(&config);
+        let max_negative = self.max_negative(&config);
+        Ok(if self.raw == 0 {
+            0.0
+        } else if self.raw > config.max {
+            1.0
+        } else if self.raw < config.min {
+            -1.0
+        } else if self.raw > min_positive {
+            (self.raw - min_positive) as f64 / (config.max - min_positive) as f64
+        } else if self.raw < max_negative {
+            (self.raw - max_negative) as f64 / (max_negative - config.min) as f64
+        } else {
+            0.0
+        })
+    }
+
+    /// The servo position the current raw value represents, in [0, 1].
+    pub fn position(&self) -> Result<f64, i32> {
+        let config = self.config()?;
+        Ok(if self.raw < config.min {
+            0.0
+        } else if self.raw > config.max {
+            1.0
+        } else {
+            (self.raw - config.min) as f64 / (config.max - config.min) as f64
+        })
+    }
+}
+
+pub struct AccelerometerState {
+    active: bool,
+    range: HAL_AccelerometerRange,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Default for AccelerometerState {
+    fn default() -> AccelerometerState {
+        AccelerometerState {
+            active: false,
+            range: HAL_AccelerometerRange::HAL_AccelerometerRange_k8G,
+            x: 0.0,
+            y: 0.0,
+            // A roboRIO lying flat feels 1g upwards.
+            z: 1.0,
+        }
+    }
+}
+
+impl AccelerometerState {
+    /// Read an axis as the roboRIO would, saturating at the configured range.
+    fn read(&self, value: f64) -> f64 {
+        let range = match self.range {
+            HAL_AccelerometerRange::HAL_AccelerometerRange_k2G => 2.0,
+            HAL_AccelerometerRange::HAL_AccelerometerRange_k4G => 4.0,
+            HAL_AccelerometerRange::HAL_AccelerometerRange_k8G => 8.0,
+        };
+        if self.active { value.clamp(-range, range) } else { 0.0 }
+    }
+}
+
+#[derive(Default)]
+pub struct I2cState {
+    pub open: bool,
+    /// The devices on the bus, by address. They stay attached while the port is closed.
+    pub devices: BTreeMap<i32, I2cDeviceState>,
+}
+
+/// A device on the I2C bus which is a bank of registers. The first byte of each write sets the
+/// register pointer, and the rest are written from there. Reads start at the pointer. Either way
+/// the pointer moves on by one for each byte.
+pub struct I2cDeviceState {
+    pub registers: [u8; 256],
+    pointer: u8,
+}
+
+impl Default for I2cDeviceState {
+    fn default() -> I2cDeviceState {
+        I2cDeviceState {
+            registers: [0; 256],
+            pointer: 0,
+        }
+    }
+}
+
+impl I2cDeviceState {
+    fn write(&mut self, data: &[u8]) {
+        if let Some((&pointer, values)) = data.split_first() {
+            self.pointer = pointer;
+            for &value in values {
+                self.registers[self.pointer as usize] = value;
+                self.pointer = self.pointer.wrapping_add(1);
+            }
+        }
+    }
+
+    fn read(&mut self, buffer: &mut [u8]) {
+        for byte in buffer {
+            *byte = self.registers[self.pointer as usize];
+            self.pointer = self.pointer.wrapping_add(1);
+        }
+    }
+}
+
+pub struct SpiState {
+    pub open: bool,
+    pub speed: i32,
+    pub msb_first: bool,
+    pub sample_on_trailing: bool,
+    pub clk_idle_high: bool,
+    pub chip_select_active_high: bool,
+    /// Bytes the device will send back, in order. Once these run out, it sends zeros.
+    pub responses: VecDeque<u8>,
+    /// Bytes sent to the device.
+    pub sent: Vec<u8>,
+    pub accumulator: Option<SpiAccumulatorState>,
+}
+
+/// The FPGA's SPI accumulator. Instead of polling the device, samples are added directly.
+#[derive(Default)]
+pub struct SpiAccumulatorState {
+    center: i32,
+    deadband: i32,
+    last_value: i32,
+    value: i64,
+    count: i64,
+}
+
+impl SpiAccumulatorState {
+    /// Accumulate a sample, as the FPGA would after reading it from the device.
+    pub fn add_sample(&mut self, data: i32) {
+        self.last_value = data;
+        let offset = data - self.center;
+        if offset.abs() > self.deadband {
+            self.value += offset as i64;
+        }
+        self.count += 1;
+    }
+}
+
+impl Default for SpiState {
+    fn default() -> SpiState {
+        SpiState {
+            open: false,
+            speed: 500000,
+            msb_first: false,
+            sample_on_trailing: false,
+            clk_idle_high: false,
+            chip_select_active_high: false,
+            responses: VecDeque::new(),
+            sent: Vec::new(),
+            accumulator: None,
+        }
+    }
+}
+
+impl SpiState {
+    /// Shift `count` bytes in from the device.
+    fn receive(&mut self, count: usize) -> Vec<u8> {
+        (0..count).map(|_| self.responses.pop_front().unwrap_or(0)).collect()
+    }
+}
+
+pub struct SerialState {
+    pub open: bool,
+    pub baud_rate: i32,
+    write_mode: i32,
+    write_buffer_size: usize,
+    timeout: f64,
+    termination: Option<u8>,
+    /// Bytes waiting to be read by robot code.
+    pub received: VecDeque<u8>,
+    /// Bytes written by robot code that have not been flushed yet.
+    pending: Vec<u8>,
+    /// Bytes flushed out of the port.
+    pub sent: Vec<u8>,
+}
+
+impl Default for SerialState {
+    fn default() -> SerialState {
+        SerialState {
+            open: false,
+            baud_rate: 9600,
+            write_mode: 1,
+            write_buffer_size: 1024,
+            timeout: 5.0,
+            termination: None,
+            received: VecDeque::new(),
+            pending: Vec::new(),
+            sent: Vec::new(),
+        }
+    }
+}
+
+impl SerialState {
+    fn flush(&mut self) {
+        self.sent.append(&mut self.pending);
+    }
+}
+
+#[derive(Default)]
+pub struct AccumulatorState {
+    initialized: bool,
+    pub value: i64,
+    pub count: i64,
+    center: i32,
+    deadband: i32,
+}
+
+#[derive(Default)]
+pub struct AnalogInputState {
+    pub voltage: f64,
+    average_bits: i32,
+    oversample_bits: i32,
+    pub accumulator: AccumulatorState,
+}
+
+impl AnalogInputState {
+    fn value(&self) -> i32 {
+        volts_to_value(self.voltage)
+    }
+}
+
+/// Convert a voltage to the raw value the analog inputs would read.
+fn volts_to_value(voltage: f64) -> i32 {
+    let value = (voltage + ANALOG_OFFSET as f64 * 1e-9) / (ANALOG_LSB_WEIGHT as f64 * 1e-9);
+    (value.round() as i32).clamp(0, 4095)
+}
+
+/// A gyro on an accumulator channel. The angle and rate are set directly rather than integrated
+/// from the accumulator.
+#[derive(Default)]
+pub struct AnalogGyroState {
+    pub angle: f64,
+    pub rate: f64,
+    offset: f64,
+    center: i32,
+}
+
+#[derive(Default)]
+pub struct AnalogOutputState {
+    pub voltage: f64,
+}
+
+#[derive(Default)]
+struct AnalogTriggerState {
+    input: HAL_AnalogInputHandle,
+    lower: i32,
+    upper: i32,
+    averaged: bool,
+    filtered: bool,
+    // The trigger state has hysteresis: it only changes when the input leaves the window.
+    state: bool,
+}
+
+pub struct EncoderState {
+    pub raw: i32,
+    /// The rate in counts per second, before scaling by the distance per pulse.
+    pub rate: f64,
+    reverse_direction: bool,
+    encoding: HAL_EncoderEncodingType,
+    max_period: f64,
+    distance_per_pulse: f64,
+    samples_to_average: i32,
+    index: Option<(HAL_Handle, HAL_AnalogTriggerType, HAL_EncoderIndexingType)>,
+}
+
+impl Default for EncoderState {
+    fn default() -> EncoderState {
+        EncoderState {
+            raw: 0,
+            rate: 0.0,
+            reverse_direction: false,
+            encoding: HAL_EncoderEncodingType::HAL_Encoder_k4X,
+            max_period: 0.5,
+            distance_per_pulse: 1.0,
+            samples_to_average: 1,
+            index: None,
+        }
+    }
+}
+
+impl EncoderState {
+    pub fn scale(&self) -> i32 {
+        match self.encoding {
+            HAL_EncoderEncodingType::HAL_Encoder_k1X => 1,
+            HAL_EncoderEncodingType::HAL_Encoder_k2X => 2,
+            HAL_EncoderEncodingType::HAL_Encoder_k4X => 4,
+        }
+    }
+
+    fn period(&self) -> f64 {
+        1.0 / self.rate.abs()
+    }
+
+    fn stopped(&self) -> bool {
+        self.period() > self.max_period
+    }
+
+    /// Apply the reverse direction setting to a value.
+    fn directed<T: ::std::ops::Neg<Output = T>>(&self, value: T) -> T {
+        if self.reverse_direction { -value } else { value }
+    }
+}
+
+/// One input of a counter: the source it is routed from, and the edges it counts.
+#[derive(Default, Copy, Clone)]
+pub struct CounterSource {
+    pub source: Option<HAL_Handle>,
+    pub rising: bool,
+    pub falling: bool,
+}
+
+pub struct CounterState {
+    pub mode: HAL_Counter_Mode,
+    pub up: CounterSource,
+    pub down: CounterSource,
+    pub count: i32,
+    pub period: f64,
+    pub direction: bool,
+    max_period: f64,
+    samples_to_average: i32,
+}
+
+impl Default for CounterState {
+    fn default() -> CounterState {
+        CounterState {
+            mode: HAL_Counter_Mode::HAL_Counter_kTwoPulse,
+            up: CounterSource::default(),
+            down: CounterSource::default(),
+            count: 0,
+            // No edges have been seen yet, so the counter starts out stopped.
+            period: f64::INFINITY,
+            direction: false,
+            max_period: 0.5,
+            samples_to_average: 1,
+        }
+    }
+}
+
+impl CounterState {
+    fn is_stopped(&self) -> bool {
+        self.period > self.max_period
+    }
+
+    /// Does the mode allow a down source? The other modes measure the up source alone.
+    fn counts_down(&self) -> bool {
+        matches!(self.mode,
+                 HAL_Counter_Mode::HAL_Counter_kTwoPulse |
+                 HAL_Counter_Mode::HAL_Counter_kExternalDirection)
+    }
+}
+
+#[derive(Default)]
+struct DigitalPwmState {
+    duty_cycle: f64,
+    output_channel: Option<usize>,
+}
+
+#[derive(Default)]
+struct InterruptState {
+    watcher: bool,
+    source: Option<HAL_Handle>,
+    rising: bool,
+    falling: bool,
+    handler: HAL_InterruptHandlerFunction,
+    // Stored as an integer so that the state can be shared between threads.
+    param: usize,
+    enabled: bool,
+    /// The edges seen since the last wait, as a mask in the format `HAL_WaitForInterrupt` returns.
+    pending: i64,
+    rising_timestamp_us: u64,
+    falling_timestamp_us: u64,
+}
+
+#[derive(Default)]
+struct NotifierState {
+    process: HAL_NotifierProcessFunction,
+    // Stored as an integer so that the state can be shared between threads.
+    param: usize,
+    trigger_time: Option<u64>,
+}
+
+#[derive(Default)]
+pub struct RelayState {
+    pub on: bool,
+}
+
+#[derive(Default)]
+pub struct SolenoidState {
+    pub value: bool,
+}
+
+#[derive(Default)]
+pub struct PcmState {
+    pub solenoid_blacklist: i32,
+    pub solenoid_voltage_fault: bool,
+    pub solenoid_voltage_sticky_fault: bool,
+
+    pub closed_loop: bool,
+    pub pressure_switch: bool,
+    pub compressor_current: f64,
+    pub current_too_high_fault: bool,
+    pub current_too_high_sticky_fault: bool,
+    pub shorted_fault: bool,
+    pub shorted_sticky_fault: bool,
+    pub not_connected_fault: bool,
+    pub not_connected_sticky_fault: bool,
+}
+
+pub struct PdpState {
+    pub voltage: f64,
+    pub temperature: f64,
+    pub currents: [f64; NUM_PDP_CHANNELS],
+    pub total_energy: f64,
+}
+
+impl Default for PdpState {
+    fn default() -> PdpState {
+        PdpState {
+            voltage: 12.0,
+            temperature: 25.0,
+            currents: [0.0; NUM_PDP_CHANNELS],
+            total_energy: 0.0,
+        }
+    }
+}
+
+#[derive(Default, Copy, Clone)]
+pub struct JoystickState {
+    pub axes: HAL_JoystickAxes,
+    pub povs: HAL_JoystickPOVs,
+    pub buttons: HAL_JoystickButtons,
+    descriptor: HAL_JoystickDescriptor,
+    pub outputs: i64,
+    pub left_rumble: i32,
+    pub right_rumble: i32,
+}
+
+/// All of the simulated hardware.
+pub struct SimState {
+    start: time::Instant,
+
+    pub digital: Resources<DigitalState>,
+    pub pwm: Resources<PwmState>,
+    pub analog_inputs: Resources<AnalogInputState>,
+    analog_triggers: Resources<AnalogTriggerState>,
+    pub analog_outputs: Resources<AnalogOutputState>,
+    pub analog_gyros: Resources<AnalogGyroState>,
+    pub encoders: Resources<EncoderState>,
+    pub counters: Resources<CounterState>,
+    digital_pwm: Resources<DigitalPwmState>,
+    interrupts: Resources<InterruptState>,
+    notifiers: Resources<NotifierState>,
+    notifier_thread: bool,
+    /// The forward outputs of every header, followed by the reverse outputs.
+    pub relays: Resources<RelayState>,
+    pub solenoids: Resources<SolenoidState>,
+    pub pcm: Vec<PcmState>,
+    pub pdp: Vec<PdpState>,
+    pub i2c: Vec<I2cState>,
+    pub spi: Vec<SpiState>,
+    pub serial: Vec<SerialState>,
+
+    digital_pwm_rate: f64,
+    /// The period of each glitch filter, in FPGA cycles.
+    filter_periods: [i64; NUM_DIGITAL_FILTERS],
+    analog_sample_rate: f64,
+
+    pub control_word: u32,
+    alliance: HAL_AllianceStationID,
+    pub joysticks: [JoystickState; NUM_JOYSTICKS],
+
+    pub accelerometer: AccelerometerState,
+
+    pub vin_voltage: f64,
+    pub user_voltage_5v: f64,
+    pub browned_out: bool,
+}
+
+impl SimState {
+    fn new() -> SimState {
+        SimState {
+            start: time::Instant::now(),
+
+            digital: Resources::new(HandleKind::Digital, NUM_DIGITAL_CHANNELS),
+            pwm: Resources::new(HandleKind::Pwm, NUM_PWM_CHANNELS),
+            analog_inputs: Resources::new(HandleKind::AnalogInput, NUM_ANALOG_INPUTS),
+            analog_triggers: Resources::new(HandleKind::AnalogTrigger, NUM_ANALOG_TRIGGERS),
+            analog_outputs: Resources::new(HandleKind::AnalogOutput, NUM_ANALOG_OUTPUTS),
+            analog_gyros: Resources::new(HandleKind::AnalogGyro, NUM_ACCUMULATORS),
+            encoders: Resources::new(HandleKind::Encoder, NUM_ENCODERS),
+            counters: Resources::new(HandleKind::Counter, NUM_COUNTERS),
+            digital_pwm: Resources::new(HandleKind::DigitalPwm, NUM_DIGITAL_PWM),
+            interrupts: Resources::new(HandleKind::Interrupt, NUM_INTERRUPTS),
+            notifiers: Resources::new(HandleKind::Notifier, NUM_NOTIFIERS),
+            notifier_thread: false,
+            relays: Resources::new(HandleKind::Relay, NUM_RELAY_HEADERS * 2),
+            solenoids: Resources::new(HandleKind::Solenoid,
+                                      NUM_PCM_MODULES * NUM_SOLENOID_CHANNELS),
+            pcm: (0..NUM_PCM_MODULES).map(|_| PcmState::default()).collect(),
+            pdp: (0..NUM_PDP_MODULES).map(|_| PdpState::default()).collect(),
+            i2c: (0..NUM_I2C_PORTS).map(|_| I2cState::default()).collect(),
+            spi: (0..NUM_SPI_PORTS).map(|_| SpiState::default()).collect(),
+            serial: (0..NUM_SERIAL_PORTS).map(|_| SerialState::default()).collect(),
+
+            digital_pwm_rate: 0.0,
+            filter_periods: [0; NUM_DIGITAL_FILTERS],
+            analog_sample_rate: 50000.0,
+
+            control_word: 0,
+            alliance: HAL_AllianceStationID::HAL_AllianceStationID_kRed1,
+            joysticks: [JoystickState::default(); NUM_JOYSTICKS],
+
+            accelerometer: AccelerometerState::default(),
+
+            vin_voltage: 12.0,
+            user_voltage_5v: 5.0,
+            browned_out: false,
+        }
+    }
+
+    fn time_us(&self) -> u64 {
+        let elapsed = self.start.elapsed();
+        elapsed.as_secs() * 1_000_000 + elapsed.subsec_nanos() as u64 / 1000
+    }
+
+    fn pcm(&mut self, module: i32) -> Result<&mut PcmState, i32> {
+        if module < 0 || module as usize >= NUM_PCM_MODULES {
+            Err(PARAMETER_OUT_OF_RANGE as i32)
+        } else {
+            Ok(&mut self.pcm[module as usize])
+        }
+    }
+
+    fn compressor(&mut self, handle: HAL_CompressorHandle) -> Result<&mut PcmState, i32> {
+        if handle >> 24 != HandleKind::Compressor as i32 {
+            Err(HAL_HANDLE_ERROR as i32)
+        } else {
+            self.pcm(handle & 0xffffff)
+        }
+    }
+
+    fn serial(&mut self, port: HAL_SerialPort) -> Result<&mut SerialState, i32> {
+        let serial = &mut self.serial[port as usize];
+        if serial.open { Ok(serial) } else { Err(HAL_HANDLE_ERROR as i32) }
+    }
+
+    fn pdp(&mut self, module: i32) -> Result<&mut PdpState, i32> {
+        if module < 0 || module as usize >= NUM_PDP_MODULES {
+            Err(PARAMETER_OUT_OF_RANGE as i32)
+        } else {
+            Ok(&mut self.pdp[module as usize])
+        }
+    }
+
+    /// Check that a handle refers to a source that can be routed to a counter, encoder or
+    /// interrupt.
+    fn check_digital_source(&self, handle: HAL_Handle) -> Result<(), i32> {
+        if handle >> 24 == HandleKind::AnalogTrigger as i32 {
+            self.analog_triggers.index(handle).map(|_| ())
+        } else {
+            self.digital.index(handle).map(|_| ())
+        }
+    }
+
+    /// Get an encoder, first resetting it if its index source is at the level that holds it in
+    /// reset. The edge triggered index modes are not simulated.
+    fn encoder(&mut self, handle: HAL_EncoderHandle) -> Result<&mut EncoderState, i32> {
+        if let Some((source, trigger_type, indexing_type)) = self.encoders.get(handle)?.index {
+            let level = if source >> 24 == HandleKind::AnalogTrigger as i32 {
+                self.analog_trigger_output(source, trigger_type)?
+            } else {
+                self.digital.get(source)?.value
+            };
+            let reset = match indexing_type {
+                HAL_EncoderIndexingType::HAL_kResetWhileHigh => level,
+                HAL_EncoderIndexingType::HAL_kResetWhileLow => !level,
+                _ => false,
+            };
+            if reset {
+                self.encoders.get(handle)?.raw = 0;
+            }
+        }
+        self.encoders.get(handle)
+    }
+
+    /// Read an output of an analog trigger. The trigger is only evaluated when it is read, so the
+    /// trigger state will miss excursions of the input between reads.
+    fn analog_trigger_output(&mut self,
+                             handle: HAL_AnalogTriggerHandle,
+                             trigger_type: HAL_AnalogTriggerType)
+                             -> Result<bool, i32> {
+        let index = self.analog_triggers.index(handle)?;
+        let value = self.analog_inputs.get(self.analog_triggers.devices[index].input)?.value();
+        let trigger = &mut self.analog_triggers.devices[index];
+        if value < trigger.lower {
+            trigger.state = false;
+        } else if value > trigger.upper {
+            trigger.state = true;
+        }
+
+        match trigger_type {
+            HAL_AnalogTriggerType::HAL_Trigger_kInWindow => {
+                Ok(value >= trigger.lower && value <= trigger.upper)
+            }
+            HAL_AnalogTriggerType::HAL_Trigger_kState => Ok(trigger.state),
+            _ => Err(ANALOG_TRIGGER_PULSE_OUTPUT_ERROR as i32),
+        }
+    }
+
+    fn accumulator(&mut self, handle: HAL_AnalogInputHandle) -> Result<&mut AccumulatorState, i32> {
+        let index = self.analog_inputs.index(handle)?;
+        if index >= NUM_ACCUMULATORS {
+            Err(HAL_INVALID_ACCUMULATOR_CHANNEL as i32)
+        } else {
+            Ok(&mut self.analog_inputs.devices[index].accumulator)
+        }
+    }
+}
+
+static STATE: Mutex<Option<SimState>> = Mutex::new(None);
+
+/// Held while a notifier callback runs, so that cleaning up a notifier can wait for its callback
+/// to finish. Always locked before `STATE`.
+static NOTIFIER_CALLBACK: Mutex<()> = Mutex::new(());
+
+/// Notified whenever a notifier alarm changes.
+static NOTIFIER_ALARM: Condvar = Condvar::new();
+
+thread_local! {
+    /// Set on the thread that runs notifier callbacks, which holds `NOTIFIER_CALLBACK` while each
+    /// callback runs.
+    static ON_NOTIFIER_THREAD: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Held while an interrupt handler runs, so that cleaning up an interrupt can wait for its handler
+/// to finish. Always locked before `STATE`.
+static INTERRUPT_HANDLER: Mutex<()> = Mutex::new(());
+
+/// The interrupt mask bit for an edge. Like the FPGA, each interrupt reports a rising edge in bit
+/// `index` and a falling edge in bit `8 + index`.
+fn interrupt_mask(index: usize, rising: bool) -> i64 {
+    if rising { 1 << index } else { 1 << (8 + index) }
+}
+
+/// Lock the simulation state, creating it if this is the first access.
+pub fn state() -> SimStateGuard {
+    let mut guard = STATE.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if guard.is_none() {
+        *guard = Some(SimState::new());
+    }
+    SimStateGuard(guard)
+}
+
+/// Replace the simulation state with a fresh one.
+pub fn reset_state() {
+    *STATE.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(SimState::new());
+}
+
+/// Drive a digital input, triggering any interrupts on that input. Handlers are called on the
+/// current thread, after the state has been unlocked.
+pub fn set_digital_input(channel: usize, value: bool) {
+    let handlers = {
+        let mut sim = state();
+        let previous = ::std::mem::replace(&mut sim.digital.devices[channel].value, value);
+        if previous == value || !sim.digital.allocated[channel] {
+            return;
+        }
+
+        let source = sim.digital.handle(channel);
+        let now = sim.time_us();
+        let interrupts = &mut sim.interrupts;
+        let mut handlers = Vec::new();
+        for index in 0..NUM_INTERRUPTS {
+            let handle = interrupts.handle(index);
+            let interrupt = &mut interrupts.devices[index];
+            if !interrupts.allocated[index] || interrupt.source != Some(source) ||
+               !(if value { interrupt.rising } else { interrupt.falling }) {
+                continue;
+            }
+
+            if value {
+                interrupt.rising_timestamp_us = now;
+            } else {
+                interrupt.falling_timestamp_us = now;
+            }
+            let mask = interrupt_mask(index, value);
+            match interrupt.handler {
+                Some(handler) if !interrupt.watcher => {
+                    if interrupt.enabled {
+                        handlers.push((handle, handler, interrupt.param, mask));
+                    }
+                }
+                _ => interrupt.pending |= mask,
+            }
+        }
+        handlers
+    };
+
+    for (handle, handler, param, mask) in handlers {
+        let _handler = INTERRUPT_HANDLER.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        // The interrupt may have been cleaned up since the state was unlocked.
+        let current = state().interrupts.get(handle).map(|interrupt| interrupt.param) == Ok(param);
+        if current {
+            unsafe { handler(mask as u32, param as *mut c_void) };
+        }
+    }
+}
+
+pub struct SimStateGuard(MutexGuard<'static, Option<SimState>>);
+
+impl ::std::ops::Deref for SimStateGuard {
+    type Target = SimState;
+    fn deref(&self) -> &SimState {
+        self.0.as_ref().unwrap()
+    }
+}
+
+impl ::std::ops::DerefMut for SimStateGuard {
+    fn deref_mut(&mut self) -> &mut SimState {
+        self.0.as_mut().unwrap()
+    }
+}
+
+/// Run `f` against the simulation state, writing any error code into `status`. On failure the
+/// default value of the return type is returned, as the C HAL does.
+unsafe fn sim_call<R, F>(status: *mut i32, f: F) -> R
+    where R: Default,
+          F: FnOnce(&mut SimState) -> Result<R, i32>
+{
+    match f(&mut state()) {
+        Ok(result) => result,
+        Err(code) => {
+            *status = code;
+            R::default()
+        }
+    }
+}
+
+/// Run notifier callbacks as their alarms expire. As in the HAL, a single thread runs every
+/// callback.
+fn run_notifiers() {
+    ON_NOTIFIER_THREAD.with(|on_notifier_thread| on_notifier_thread.set(true));
+    loop {
+        let callback = NOTIFIER_CALLBACK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut sim = state();
+        let now = sim.time_us();
+        let next = {
+            let notifiers = &sim.notifiers;
+            (0..NUM_NOTIFIERS)
+                .filter(|&index| notifiers.allocated[index])
+                .filter_map(|index| notifiers.devices[index].trigger_time.map(|time| (time, index)))
+                .min()
+        };
+
+        match next {
+            Some((time, index)) if time <= now => {
+                let handle = sim.notifiers.handle(index);
+                let notifier = &mut sim.notifiers.devices[index];
+                notifier.trigger_time = None;
+                let process = notifier.process;
+                // The callback is free to use the HAL, so it must run without the state locked.
+                drop(sim);
+                if let Some(process) = process {
+                    process(now, handle);
+                }
+                drop(callback);
+            }
+            next => {
+                drop(callback);
+                let timeout = next.map_or(time::Duration::from_secs(1),
+                                          |(time, _)| time::Duration::from_micros(time - now));
+                let _ = NOTIFIER_ALARM.wait_timeout(sim.0, timeout);
+            }
+        }
+    }
+}
+
+fn check_index(index: i32, count: usize) -> HAL_Bool {
+    (index >= 0 && (index as usize) < count) as HAL_Bool
+}
+
+/// The simulated HAL.
+pub struct Sim;
+
+#[allow(non_snake_case)]
+impl HalBackend for Sim {
+    // Accelerometer
+    unsafe fn HAL_SetAccelerometerActive(active: HAL_Bool) {
+        state().accelerometer.active = active != 0;
+    }
+
+    unsafe fn HAL_SetAccelerometerRange(range: HAL_AccelerometerRange) {
+        state().accelerometer.range = range;
+    }
+
+    unsafe fn HAL_GetAccelerometerX() -> f64 {
+        let sim = state();
+        sim.accelerometer.read(sim.accelerometer.x)
+    }
+
+    unsafe fn HAL_GetAccelerometerY() -> f64 {
+        let sim = state();
+        sim.accelerometer.read(sim.accelerometer.y)
+    }
+
+    unsafe fn HAL_GetAccelerometerZ() -> f64 {
+        let sim = state();
+        sim.accelerometer.read(sim.accelerometer.z)
+    }
+
+    // Analog accumulator
+    unsafe fn HAL_IsAccumulatorChannel(handle: HAL_AnalogInputHandle,
+                                       status: *mut i32)
+                                       -> HAL_Bool {
+        sim_call(status, |sim| {
+            Ok((sim.analog_inputs.index(handle)? < NUM_ACCUMULATORS) as HAL_Bool)
+        })
+    }
+
+    unsafe fn HAL_InitAccumulator(handle: HAL_AnalogInputHandle, status: *mut i32) {
+        sim_call(status, |sim| {
+            let accumulator = sim.accumulator(handle)?;
+            *accumulator = AccumulatorState::default();
+            accumulator.initialized = true;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_ResetAccumulator(handle: HAL_AnalogInputHandle, status: *mut i32) {
+        sim_call(status, |sim| {
+            let accumulator = sim.accumulator(handle)?;
+            accumulator.value = 0;
+            accumulator.count = 0;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_SetAccumulatorCenter(handle: HAL_AnalogInputHandle,
+                                       center: i32,
+                                       status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.accumulator(handle)?.center = center;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_SetAccumulatorDeadband(handle: HAL_AnalogInputHandle,
+                                         deadband: i32,
+                                         status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.accumulator(handle)?.deadband = deadband;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_GetAccumulatorValue(handle: HAL_AnalogInputHandle, status: *mut i32) -> i64 {
+        sim_call(status, |sim| Ok(sim.accumulator(handle)?.value))
+    }
+
+    unsafe fn HAL_GetAccumulatorCount(handle: HAL_AnalogInputHandle, status: *mut i32) -> i64 {
+        sim_call(status, |sim| Ok(sim.accumulator(handle)?.count))
+    }
+
+    unsafe fn HAL_GetAccumulatorOutput(handle: HAL_AnalogInputHandle,
+                                       value: *mut i64,
+                                       count: *mut i64,
+                                       status: *mut i32) {
+        if value.is_null() || count.is_null() {
+            *status = NULL_PARAMETER as i32;
+            return;
+        }
+        let (v, c) = sim_call(status, |sim| {
+            let accumulator = sim.accumulator(handle)?;
+            Ok((accumulator.value, accumulator.count))
+        });
+        *value = v;
+        *count = c;
+    }
+
+    // Analog gyro
+    unsafe fn HAL_InitializeAnalogGyro(handle: HAL_AnalogInputHandle,
+                                       status: *mut i32)
+                                       -> HAL_GyroHandle {
+        sim_call(status, |sim| {
+            sim.accumulator(handle)?;
+            let gyro = sim.analog_gyros.allocate(sim.analog_inputs.index(handle)?)?;
+            *sim.analog_gyros.get(gyro)? = AnalogGyroState::default();
+            Ok(gyro)
+        })
+    }
+
+    unsafe fn HAL_SetupAnalogGyro(handle: HAL_GyroHandle, status: *mut i32) {
+        sim_call(status, |sim| {
+            let index = sim.analog_gyros.index(handle)?;
+            let input = sim.analog_inputs.handle(index);
+            sim.accumulator(input)?.initialized = true;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_FreeAnalogGyro(handle: HAL_GyroHandle) {
+        let _ = state().analog_gyros.free(handle);
+    }
+
+    unsafe fn HAL_SetAnalogGyroParameters(handle: HAL_GyroHandle,
+                                          _volts_per_degree_per_second: f64,
+                                          offset: f64,
+                                          center: i32,
+                                          status: *mut i32) {
+        sim_call(status, |sim| {
+            let gyro = sim.analog_gyros.get(handle)?;
+            gyro.offset = offset;
+            gyro.center = center;
+            Ok(())
+        })
+    }
+
+    // The sensitivity has no effect in the sim, since the rate is set directly.
+    unsafe fn HAL_SetAnalogGyroVoltsPerDegreePerSecond(handle: HAL_GyroHandle,
+                                                       _volts_per_degree_per_second: f64,
+                                                       status: *mut i32) {
+        sim_call(status, |sim| sim.analog_gyros.index(handle).map(|_| ()))
+    }
+
+    unsafe fn HAL_ResetAnalogGyro(handle: HAL_GyroHandle, status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.analog_gyros.get(handle)?.angle = 0.0;
+            Ok(())
+        })
+    }
+
+    // A simulated gyro has no drift, so calibrating just centers it on the current input and
+    // resets the angle, without the HAL's five second wait.
+    unsafe fn HAL_CalibrateAnalogGyro(handle: HAL_GyroHandle, status: *mut i32) {
+        sim_call(status, |sim| {
+            let index = sim.analog_gyros.index(handle)?;
+            let center = sim.analog_inputs.devices[index].value();
+            let gyro = &mut sim.analog_gyros.devices[index];
+            gyro.center = center;
+            gyro.offset = 0.0;
+            gyro.angle = 0.0;
+            Ok(())
+        })
+    }
+
+    // The deadband has no effect in the sim, since the rate is set directly.
+    unsafe fn HAL_SetAnalogGyroDeadband(handle: HAL_GyroHandle, _volts: f64, status: *mut i32) {
+        sim_call(status, |sim| sim.analog_gyros.index(handle).map(|_| ()))
+    }
+
+    unsafe fn HAL_GetAnalogGyroAngle(handle: HAL_GyroHandle, status: *mut i32) -> f64 {
+        sim_call(status, |sim| Ok(sim.analog_gyros.get(handle)?.angle))
+    }
+
+    unsafe fn HAL_GetAnalogGyroRate(handle: HAL_GyroHandle, status: *mut i32) -> f64 {
+        sim_call(status, |sim| Ok(sim.analog_gyros.get(handle)?.rate))
+    }
+
+    unsafe fn HAL_GetAnalogGyroOffset(handle: HAL_GyroHandle, status: *mut i32) -> f64 {
+        sim_call(status, |sim| Ok(sim.analog_gyros.get(handle)?.offset))
+    }
+
+    unsafe fn HAL_GetAnalogGyroCenter(handle: HAL_GyroHandle, status: *mut i32) -> i32 {
+        sim_call(status, |sim| Ok(sim.analog_gyros.get(handle)?.center))
+    }
+
+    // Analog input
+    unsafe fn HAL_InitializeAnalogInputPort(port_handle: HAL_PortHandle,
+                                            status: *mut i32)
+                                            -> HAL_AnalogInputHandle {
+        sim_call(status, |sim| {
+            let handle = sim.analog_inputs.allocate(port_index(port_handle)?)?;
+            let input = sim.analog_inputs.get(handle)?;
+            input.average_bits = 7;
+            input.oversample_bits = 0;
+            input.accumulator = AccumulatorState::default();
+            Ok(handle)
+        })
+    }
+
+    unsafe fn HAL_FreeAnalogInputPort(handle: HAL_AnalogInputHandle) {
+        let _ = state().analog_inputs.free(handle);
+    }
+
+    unsafe fn HAL_CheckAnalogInputChannel(channel: i32) -> HAL_Bool {
+        check_index(channel, NUM_ANALOG_INPUTS)
+    }
+
+    unsafe fn HAL_SetAnalogSampleRate(samples_per_second: f64, status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.analog_sample_rate = samples_per_second;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_GetAnalogSampleRate(status: *mut i32) -> f64 {
+        sim_call(status, |sim| Ok(sim.analog_sample_rate))
+    }
+
+    unsafe fn HAL_SetAnalogAverageBits(handle: HAL_AnalogInputHandle,
+                                       bits: i32,
+                                       status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.analog_inputs.get(handle)?.average_bits = bits;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_GetAnalogAverageBits(handle: HAL_AnalogInputHandle, status: *mut i32) -> i32 {
+        sim_call(status, |sim| Ok(sim.analog_inputs.get(handle)?.average_bits))
+    }
+
+    unsafe fn HAL_SetAnalogOversampleBits(handle: HAL_AnalogInputHandle,
+                                          bits: i32,
+                                          status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.analog_inputs.get(handle)?.oversample_bits = bits;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_GetAnalogOversampleBits(handle: HAL_AnalogInputHandle, status: *mut i32) -> i32 {
+        sim_call(status, |sim| Ok(sim.analog_inputs.get(handle)?.oversample_bits))
+    }
+
+    unsafe fn HAL_GetAnalogValue(handle: HAL_AnalogInputHandle, status: *mut i32) -> i32 {
+        sim_call(status, |sim| Ok(sim.analog_inputs.get(handle)?.value()))
+    }
+
+    unsafe fn HAL_GetAnalogAverageValue(handle: HAL_AnalogInputHandle, status: *mut i32) -> i32 {
+        sim_call(status, |sim| {
+            let input = sim.analog_inputs.get(handle)?;
+            Ok(input.value() << input.oversample_bits)
+        })
+    }
+
+    unsafe fn HAL_GetAnalogVoltage(handle: HAL_AnalogInputHandle, status: *mut i32) -> f64 {
+        sim_call(status, |sim| Ok(sim.analog_inputs.get(handle)?.voltage))
+    }
+
+    unsafe fn HAL_GetAnalogAverageVoltage(handle: HAL_AnalogInputHandle, status: *mut i32) -> f64 {
+        sim_call(status, |sim| Ok(sim.analog_inputs.get(handle)?.voltage))
+    }
+
+    unsafe fn HAL_GetAnalogLSBWeight(handle: HAL_AnalogInputHandle, status: *mut i32) -> i32 {
+        sim_call(status, |sim| sim.analog_inputs.get(handle).map(|_| ANALOG_LSB_WEIGHT))
+    }
+
+    unsafe fn HAL_GetAnalogOffset(handle: HAL_AnalogInputHandle, status: *mut i32) -> i32 {
+        sim_call(status, |sim| sim.analog_inputs.get(handle).map(|_| ANALOG_OFFSET))
+    }
+
+    unsafe fn HAL_GetNumAnalogInputs() -> i32 {
+        NUM_ANALOG_INPUTS as i32
+    }
+
+    // Analog output
+    unsafe fn HAL_InitializeAnalogOutputPort(port_handle: HAL_PortHandle,
+                                             status: *mut i32)
+                                             -> HAL_AnalogOutputHandle {
+        sim_call(status, |sim| {
+            let handle = sim.analog_outputs.allocate(port_index(port_handle)?)?;
+            sim.analog_outputs.get(handle)?.voltage = 0.0;
+            Ok(handle)
+        })
+    }
+
+    unsafe fn HAL_FreeAnalogOutputPort(handle: HAL_AnalogOutputHandle) {
+        let _ = state().analog_outputs.free(handle);
+    }
+
+    // As on the roboRIO, the output is limited to 0-5V.
+    unsafe fn HAL_SetAnalogOutput(handle: HAL_AnalogOutputHandle, voltage: f64, status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.analog_outputs.get(handle)?.voltage = voltage.clamp(0.0, 5.0);
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_GetAnalogOutput(handle: HAL_AnalogOutputHandle, status: *mut i32) -> f64 {
+        sim_call(status, |sim| Ok(sim.analog_outputs.get(handle)?.voltage))
+    }
+
+    unsafe fn HAL_CheckAnalogOutputChannel(channel: i32) -> HAL_Bool {
+        check_index(channel, NUM_ANALOG_OUTPUTS)
+    }
+
+    // Analog trigger
+    unsafe fn HAL_InitializeAnalogTrigger(port_handle: HAL_AnalogInputHandle,
+                                          index: *mut i32,
+                                          status: *mut i32)
+                                          -> HAL_AnalogTriggerHandle {
+        sim_call(status, |sim| {
+            sim.analog_inputs.index(port_handle)?;
+            let handle = sim.analog_triggers.allocate_any()?;
+            *sim.analog_triggers.get(handle)? = AnalogTriggerState {
+                input: port_handle,
+                ..AnalogTriggerState::default()
+            };
+            *index = sim.analog_triggers.index(handle)? as i32;
+            Ok(handle)
+        })
+    }
+
+    unsafe fn HAL_CleanAnalogTrigger(handle: HAL_AnalogTriggerHandle, status: *mut i32) {
+        sim_call(status, |sim| sim.analog_triggers.free(handle))
+    }
+
+    unsafe fn HAL_SetAnalogTriggerLimitsRaw(handle: HAL_AnalogTriggerHandle,
+                                            lower: i32,
+                                            upper: i32,
+                                            status: *mut i32) {
+        sim_call(status, |sim| {
+            let trigger = sim.analog_triggers.get(handle)?;
+            if lower > upper {
+                return Err(ANALOG_TRIGGER_LIMIT_ORDER_ERROR as i32);
+            }
+            trigger.lower = lower;
+            trigger.upper = upper;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_SetAnalogTriggerLimitsVoltage(handle: HAL_AnalogTriggerHandle,
+                                                lower: f64,
+                                                upper: f64,
+                                                status: *mut i32) {
+        Sim::HAL_SetAnalogTriggerLimitsRaw(handle,
+                                           volts_to_value(lower),
+                                           volts_to_value(upper),
+                                           status)
+    }
+
+    unsafe fn HAL_SetAnalogTriggerAveraged(handle: HAL_AnalogTriggerHandle,
+                                           use_averaged_value: HAL_Bool,
+                                           status: *mut i32) {
+        sim_call(status, |sim| {
+            let trigger = sim.analog_triggers.get(handle)?;
+            if trigger.filtered && use_averaged_value != 0 {
+                return Err(INCOMPATIBLE_STATE as i32);
+            }
+            trigger.averaged = use_averaged_value != 0;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_SetAnalogTriggerFiltered(handle: HAL_AnalogTriggerHandle,
+                                           use_filtered_value: HAL_Bool,
+                                           status: *mut i32) {
+        sim_call(status, |sim| {
+            let trigger = sim.analog_triggers.get(handle)?;
+            if trigger.averaged && use_filtered_value != 0 {
+                return Err(INCOMPATIBLE_STATE as i32);
+            }
+            trigger.filtered = use_filtered_value != 0;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_GetAnalogTriggerInWindow(handle: HAL_AnalogTriggerHandle,
+                                           status: *mut i32)
+                                           -> HAL_Bool {
+        Sim::HAL_GetAnalogTriggerOutput(handle,
+                                        HAL_AnalogTriggerType::HAL_Trigger_kInWindow,
+                                        status)
+    }
+
+    unsafe fn HAL_GetAnalogTriggerTriggerState(handle: HAL_AnalogTriggerHandle,
+                                               status: *mut i32)
+                                               -> HAL_Bool {
+        Sim::HAL_GetAnalogTriggerOutput(handle, HAL_AnalogTriggerType::HAL_Trigger_kState, status)
+    }
+
+    unsafe fn HAL_GetAnalogTriggerOutput(handle: HAL_AnalogTriggerHandle,
+                                         trigger_type: HAL_AnalogTriggerType,
+                                         status: *mut i32)
+                                         -> HAL_Bool {
+        sim_call(status, |sim| Ok(sim.analog_trigger_output(handle, trigger_type)? as HAL_Bool))
+    }
+
+    // Compressor
+    unsafe fn HAL_InitializeCompressor(module: i32, status: *mut i32) -> HAL_CompressorHandle {
+        sim_call(status, |sim| {
+            sim.pcm(module)?;
+            Ok(((HandleKind::Compressor as i32) << 24) | module)
+        })
+    }
+
+    unsafe fn HAL_CheckCompressorModule(module: i32) -> HAL_Bool {
+        check_index(module, NUM_PCM_MODULES)
+    }
+
+    unsafe fn HAL_GetCompressor(handle: HAL_CompressorHandle, status: *mut i32) -> HAL_Bool {
+        sim_call(status, |sim| {
+            // The PCM only runs the compressor while the robot is enabled.
+            let enabled = sim.control_word & 1 != 0;
+            let pcm = sim.compressor(handle)?;
+            Ok((enabled && pcm.closed_loop && !pcm.pressure_switch) as HAL_Bool)
+        })
+    }
+
+    unsafe fn HAL_SetCompressorClosedLoopControl(handle: HAL_CompressorHandle,
+                                                 value: HAL_Bool,
+                                                 status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.compressor(handle)?.closed_loop = value != 0;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_GetCompressorClosedLoopControl(handle: HAL_CompressorHandle,
+                                                 status: *mut i32)
+                                                 -> HAL_Bool {
+        sim_call(status, |sim| Ok(sim.compressor(handle)?.closed_loop as HAL_Bool))
+    }
+
+    unsafe fn HAL_GetCompressorPressureSwitch(handle: HAL_CompressorHandle,
+                                              status: *mut i32)
+                                              -> HAL_Bool {
+        sim_call(status, |sim| Ok(sim.compressor(handle)?.pressure_switch as HAL_Bool))
+    }
+
+    unsafe fn HAL_GetCompressorCurrent(handle: HAL_CompressorHandle, status: *mut i32) -> f64 {
+        sim_call(status, |sim| Ok(sim.compressor(handle)?.compressor_current))
+    }
+
+    unsafe fn HAL_GetCompressorCurrentTooHighFault(handle: HAL_CompressorHandle,
+                                                   status: *mut i32)
+                                                   -> HAL_Bool {
+        sim_call(status, |sim| Ok(sim.compressor(handle)?.current_too_high_fault as HAL_Bool))
+    }
+
+    unsafe fn HAL_GetCompressorCurrentTooHighStickyFault(handle: HAL_CompressorHandle,
+                                                         status: *mut i32)
+                                                         -> HAL_Bool {
+        sim_call(status, |sim| {
+            Ok(sim.compressor(handle)?.current_too_high_sticky_fault as HAL_Bool)
+        })
+    }
+
+    unsafe fn HAL_GetCompressorShortedStickyFault(handle: HAL_CompressorHandle,
+                                                  status: *mut i32)
+                                                  -> HAL_Bool {
+        sim_call(status, |sim| Ok(sim.compressor(handle)?.shorted_sticky_fault as HAL_Bool))
+    }
+
+    unsafe fn HAL_GetCompressorShortedFault(handle: HAL_CompressorHandle,
+                                            status: *mut i32)
+                                            -> HAL_Bool {
+        sim_call(status, |sim| Ok(sim.compressor(handle)?.shorted_fault as HAL_Bool))
+    }
+
+    unsafe fn HAL_GetCompressorNotConnectedStickyFault(handle: HAL_CompressorHandle,
+                                                       status: *mut i32)
+                                                       -> HAL_Bool {
+        sim_call(status, |sim| Ok(sim.compressor(handle)?.not_connected_sticky_fault as HAL_Bool))
+    }
+
+    unsafe fn HAL_GetCompressorNotConnectedFault(handle: HAL_CompressorHandle,
+                                                 status: *mut i32)
+                                                 -> HAL_Bool {
+        sim_call(status, |sim| Ok(sim.compressor(handle)?.not_connected_fault as HAL_Bool))
+    }
+
+    // Counter
+    unsafe fn HAL_InitializeCounter(mode: HAL_Counter_Mode,
+                                    index: *mut i32,
+                                    status: *mut i32)
+                                    -> HAL_CounterHandle {
+        sim_call(status, |sim| {
+            let handle = sim.counters.allocate_any()?;
+            *sim.counters.get(handle)? = CounterState { mode, ..CounterState::default() };
+            *index = sim.counters.index(handle)? as i32;
+            Ok(handle)
+        })
+    }
+
+    unsafe fn HAL_FreeCounter(handle: HAL_CounterHandle, status: *mut i32) {
+        sim_call(status, |sim| sim.counters.free(handle))
+    }
+
+    unsafe fn HAL_SetCounterUpSource(handle: HAL_CounterHandle,
+                                     digital_source_handle: HAL_Handle,
+                                     _analog_trigger_type: HAL_AnalogTriggerType,
+                                     status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.check_digital_source(digital_source_handle)?;
+            let counter = sim.counters.get(handle)?;
+            counter.up.source = Some(digital_source_handle);
+            // As in the HAL, counting modes default to counting rising edges.
+            if counter.counts_down() {
+                counter.up.rising = true;
+                counter.up.falling = false;
+            }
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_SetCounterUpSourceEdge(handle: HAL_CounterHandle,
+                                         rising_edge: HAL_Bool,
+                                         falling_edge: HAL_Bool,
+                                         status: *mut i32) {
+        sim_call(status, |sim| {
+            let counter = sim.counters.get(handle)?;
+            counter.up.rising = rising_edge != 0;
+            counter.up.falling = falling_edge != 0;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_ClearCounterUpSource(handle: HAL_CounterHandle, status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.counters.get(handle)?.up = CounterSource::default();
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_SetCounterDownSource(handle: HAL_CounterHandle,
+                                       digital_source_handle: HAL_Handle,
+                                       _analog_trigger_type: HAL_AnalogTriggerType,
+                                       status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.check_digital_source(digital_source_handle)?;
+            let counter = sim.counters.get(handle)?;
+            if !counter.counts_down() {
+                return Err(PARAMETER_OUT_OF_RANGE as i32);
+            }
+            counter.down = CounterSource {
+                source: Some(digital_source_handle),
+                rising: true,
+                falling: false,
+            };
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_SetCounterDownSourceEdge(handle: HAL_CounterHandle,
+                                           rising_edge: HAL_Bool,
+                                           falling_edge: HAL_Bool,
+                                           status: *mut i32) {
+        sim_call(status, |sim| {
+            let counter = sim.counters.get(handle)?;
+            counter.down.rising = rising_edge != 0;
+            counter.down.falling = falling_edge != 0;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_ClearCounterDownSource(handle: HAL_CounterHandle, status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.counters.get(handle)?.down = CounterSource::default();
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_SetCounterUpDownMode(handle: HAL_CounterHandle, status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.counters.get(handle)?.mode = HAL_Counter_Mode::HAL_Counter_kTwoPulse;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_SetCounterExternalDirectionMode(handle: HAL_CounterHandle, status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.counters.get(handle)?.mode = HAL_Counter_Mode::HAL_Counter_kExternalDirection;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_SetCounterSemiPeriodMode(handle: HAL_CounterHandle,
+                                           high_semi_period: HAL_Bool,
+                                           status: *mut i32) {
+        sim_call(status, |sim| {
+            let counter = sim.counters.get(handle)?;
+            counter.mode = HAL_Counter_Mode::HAL_Counter_kSemiperiod;
+            counter.up.rising = high_semi_period != 0;
+            counter.up.falling = high_semi_period == 0;
+            Ok(())
+        })
+    }
+
+    // The threshold has no effect in the sim, since the direction is set directly.
+    unsafe fn HAL_SetCounterPulseLengthMode(handle: HAL_CounterHandle,
+                                            _threshold: f64,
+                                            status: *mut i32) {
+        sim_call(status, |sim| {
+            let counter = sim.counters.get(handle)?;
+            counter.mode = HAL_Counter_Mode::HAL_Counter_kPulseLength;
+            counter.up.rising = true;
+            counter.up.falling = true;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_GetCounterSamplesToAverage(handle: HAL_CounterHandle, status: *mut i32) -> i32 {
+        sim_call(status, |sim| Ok(sim.counters.get(handle)?.samples_to_average))
+    }
+
+    unsafe fn HAL_SetCounterSamplesToAverage(handle: HAL_CounterHandle,
+                                             samples_to_average: i32,
+                                             status: *mut i32) {
+        sim_call(status, |sim| {
+            let counter = sim.counters.get(handle)?;
+            if !(1..=127).contains(&samples_to_average) {
+                return Err(PARAMETER_OUT_OF_RANGE as i32);
+            }
+            counter.samples_to_average = samples_to_average;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_ResetCounter(handle: HAL_CounterHandle, status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.counters.get(handle)?.count = 0;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_GetCounter(handle: HAL_CounterHandle, status: *mut i32) -> i32 {
+        sim_call(status, |sim| Ok(sim.counters.get(handle)?.count))
+    }
+
+    unsafe fn HAL_GetCounterPeriod(handle: HAL_CounterHandle, status: *mut i32) -> f64 {
+        sim_call(status, |sim| {
+            let counter = sim.counters.get(handle)?;
+            Ok(if counter.is_stopped() { f64::INFINITY } else { counter.period })
+        })
+    }
+
+    unsafe fn HAL_SetCounterMaxPeriod(handle: HAL_CounterHandle,
+                                      max_period: f64,
+                                      status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.counters.get(handle)?.max_period = max_period;
+            Ok(())
+        })
+    }
+
+    // The period of a simulated counter is set directly, so there is no timer to keep updating.
+    unsafe fn HAL_SetCounterUpdateWhenEmpty(handle: HAL_CounterHandle,
+                                            _enabled: HAL_Bool,
+                                            status: *mut i32) {
+        sim_call(status, |sim| sim.counters.index(handle).map(|_| ()))
+    }
+
+    unsafe fn HAL_GetCounterStopped(handle: HAL_CounterHandle, status: *mut i32) -> HAL_Bool {
+        sim_call(status, |sim| Ok(sim.counters.get(handle)?.is_stopped() as HAL_Bool))
+    }
+
+    unsafe fn HAL_GetCounterDirection(handle: HAL_CounterHandle, status: *mut i32) -> HAL_Bool {
+        sim_call(status, |sim| Ok(sim.counters.get(handle)?.direction as HAL_Bool))
+    }
+
+    // As in the HAL, reversing only applies in external direction mode, where it changes which
+    // edges of the direction source are sampled.
+    unsafe fn HAL_SetCounterReverseDirection(handle: HAL_CounterHandle,
+                                             reverse_direction: HAL_Bool,
+                                             status: *mut i32) {
+        sim_call(status, |sim| {
+            let counter = sim.counters.get(handle)?;
+            if let HAL_Counter_Mode::HAL_Counter_kExternalDirection = counter.mode {
+                counter.down.rising = reverse_direction != 0;
+                counter.down.falling = true;
+            }
+            Ok(())
+        })
+    }
+
+    // Digital I/O
+    unsafe fn HAL_InitializeDIOPort(port_handle: HAL_PortHandle,
+                                    input: HAL_Bool,
+                                    status: *mut i32)
+                                    -> HAL_DigitalHandle {
+        sim_call(status, |sim| {
+            let handle = sim.digital.allocate(port_index(port_handle)?)?;
+            let dio = sim.digital.get(handle)?;
+            dio.is_input = input != 0;
+            // Inputs are pulled up, so an unconnected input reads high.
+            dio.value = dio.is_input;
+            dio.pulse_end_us = 0;
+            dio.filter = 0;
+            Ok(handle)
+        })
+    }
+
+    unsafe fn HAL_CheckDIOChannel(channel: i32) -> HAL_Bool {
+        check_index(channel, NUM_DIGITAL_CHANNELS)
+    }
+
+    unsafe fn HAL_FreeDIOPort(handle: HAL_DigitalHandle) {
+        let _ = state().digital.free(handle);
+    }
+
+    unsafe fn HAL_AllocateDigitalPWM(status: *mut i32) -> HAL_DigitalPWMHandle {
+        sim_call(status, |sim| {
+            let handle = sim.digital_pwm.allocate_any()?;
+            *sim.digital_pwm.get(handle)? = DigitalPwmState::default();
+            Ok(handle)
+        })
+    }
+
+    unsafe fn HAL_FreeDigitalPWM(pwm_generator: HAL_DigitalPWMHandle, status: *mut i32) {
+        sim_call(status, |sim| sim.digital_pwm.free(pwm_generator))
+    }
+
+    unsafe fn HAL_SetDigitalPWMRate(rate: f64, status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.digital_pwm_rate = rate;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_SetDigitalPWMDutyCycle(pwm_generator: HAL_DigitalPWMHandle,
+                                         duty_cycle: f64,
+                                         status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.digital_pwm.get(pwm_generator)?.duty_cycle = duty_cycle.clamp(0.0, 1.0);
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_SetDigitalPWMOutputChannel(pwm_generator: HAL_DigitalPWMHandle,
+                                             channel: i32,
+                                             status: *mut i32) {
+        sim_call(status, |sim| {
+            let output = sim.digital_pwm.get(pwm_generator)?;
+            output.output_channel = if channel >= 0 && (channel as usize) < NUM_DIGITAL_CHANNELS {
+                Some(channel as usize)
+            } else {
+                None
+            };
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_SetDIO(handle: HAL_DigitalHandle, value: HAL_Bool, status: *mut i32) {
+        sim_call(status, |sim| {
+            let dio = sim.digital.get(handle)?;
+            if !dio.is_input {
+                dio.value = value != 0;
+            }
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_GetDIO(handle: HAL_DigitalHandle, status: *mut i32) -> HAL_Bool {
+        sim_call(status, |sim| {
+            let now = sim.time_us();
+            let dio = sim.digital.get(handle)?;
+            Ok((dio.value || now < dio.pulse_end_us) as HAL_Bool)
+        })
+    }
+
+    unsafe fn HAL_Pulse(handle: HAL_DigitalHandle, pulse_length: f64, status: *mut i32) {
+        sim_call(status, |sim| {
+            let now = sim.time_us();
+            sim.digital.get(handle)?.pulse_end_us = now + (pulse_length * 1e6) as u64;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_IsPulsing(handle: HAL_DigitalHandle, status: *mut i32) -> HAL_Bool {
+        sim_call(status, |sim| {
+            let now = sim.time_us();
+            Ok((now < sim.digital.get(handle)?.pulse_end_us) as HAL_Bool)
+        })
+    }
+
+    unsafe fn HAL_SetFilterSelect(handle: HAL_DigitalHandle, filter_index: i32, status: *mut i32) {
+        sim_call(status, |sim| {
+            if !(0..=NUM_DIGITAL_FILTERS as i32).contains(&filter_index) {
+                return Err(PARAMETER_OUT_OF_RANGE as i32);
+            }
+            sim.digital.get(handle)?.filter = filter_index;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_GetFilterSelect(handle: HAL_DigitalHandle, status: *mut i32) -> i32 {
+        sim_call(status, |sim| Ok(sim.digital.get(handle)?.filter))
+    }
+
+    unsafe fn HAL_SetFilterPeriod(filter_index: i32, value: i64, status: *mut i32) {
+        sim_call(status, |sim| {
+            if check_index(filter_index, NUM_DIGITAL_FILTERS) == 0 {
+                return Err(PARAMETER_OUT_OF_RANGE as i32);
+            }
+            sim.filter_periods[filter_index as usize] = value;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_GetFilterPeriod(filter_index: i32, status: *mut i32) -> i64 {
+        sim_call(status, |sim| {
+            if check_index(filter_index, NUM_DIGITAL_FILTERS) == 0 {
+                return Err(PARAMETER_OUT_OF_RANGE as i32);
+            }
+            Ok(sim.filter_periods[filter_index as usize])
+        })
+    }
+
+    unsafe fn HAL_GetNumDigitalChannels() -> i32 {
+        NUM_DIGITAL_CHANNELS as i32
+    }
+
+    // Driver station
+    unsafe fn HAL_SendError(is_error: HAL_Bool,
+                            error_code: i32,
+                            _is_lv_code: HAL_Bool,
+                            details: *const c_char,
+                            location: *const c_char,
+                            _call_stack: *const c_char,
+                            print_msg: HAL_Bool)
+                            -> i32 {
+        if print_msg != 0 {
+            let details = ffi::CStr::from_ptr(details).to_string_lossy();
+            let location = ffi::CStr::from_ptr(location).to_string_lossy();
+            eprintln!("{} {}: {} {}",
+                      if is_error != 0 { "Error" } else { "Warning" },
+                      error_code,
+                      details,
+                      location);
+        }
+        0
+    }
+
+    unsafe fn HAL_GetControlWord(control_word: *mut HAL_ControlWord) -> i32 {
+        // The control word is a single 32-bit bitfield.
+        *(control_word as *mut u32) = state().control_word;
+        0
+    }
+
+    unsafe fn HAL_GetAllianceStation(_status: *mut i32) -> HAL_AllianceStationID {
+        state().alliance
+    }
+
+    unsafe fn HAL_GetJoystickAxes(joystick_num: i32, axes: *mut HAL_JoystickAxes) -> i32 {
+        *axes = joystick(joystick_num).axes;
+        0
+    }
+
+    unsafe fn HAL_GetJoystickPOVs(joystick_num: i32, povs: *mut HAL_JoystickPOVs) -> i32 {
+        *povs = joystick(joystick_num).povs;
+        0
+    }
+
+    unsafe fn HAL_GetJoystickButtons(joystick_num: i32, buttons: *mut HAL_JoystickButtons) -> i32 {
+        *buttons = joystick(joystick_num).buttons;
+        0
+    }
+
+    unsafe fn HAL_GetJoystickDescriptor(joystick_num: i32,
+                                        desc: *mut HAL_JoystickDescriptor)
+                                        -> i32 {
+        *desc = joystick(joystick_num).descriptor;
+        0
+    }
+
+    unsafe fn HAL_SetJoystickOutputs(joystick_num: i32,
+                                     outputs: i64,
+                                     left_rumble: i32,
+                                     right_rumble: i32)
+                                     -> i32 {
+        if check_index(joystick_num, NUM_JOYSTICKS) == 0 {
+            return PARAMETER_OUT_OF_RANGE as i32;
+        }
+        let stick = &mut state().joysticks[joystick_num as usize];
+        stick.outputs = outputs;
+        stick.left_rumble = left_rumble;
+        stick.right_rumble = right_rumble;
+        0
+    }
+
+    unsafe fn HAL_WaitForDSData() {
+        thread::sleep(time::Duration::from_millis(DS_PACKET_PERIOD_MS));
+    }
+
+    // There is no driver station to show the program's state to.
+    unsafe fn HAL_ObserveUserProgramStarting() {}
+
+    unsafe fn HAL_ObserveUserProgramDisabled() {}
+
+    unsafe fn HAL_ObserveUserProgramAutonomous() {}
+
+    unsafe fn HAL_ObserveUserProgramTeleop() {}
+
+    unsafe fn HAL_ObserveUserProgramTest() {}
+
+    // Encoder
+    unsafe fn HAL_InitializeEncoder(digital_source_handle_a: HAL_Handle,
+                                    _analog_trigger_type_a: HAL_AnalogTriggerType,
+                                    digital_source_handle_b: HAL_Handle,
+                                    _analog_trigger_type_b: HAL_AnalogTriggerType,
+                                    reverse_direction: HAL_Bool,
+                                    encoding_type: HAL_EncoderEncodingType,
+                                    status: *mut i32)
+                                    -> HAL_EncoderHandle {
+        sim_call(status, |sim| {
+            sim.check_digital_source(digital_source_handle_a)?;
+            sim.check_digital_source(digital_source_handle_b)?;
+            let handle = sim.encoders.allocate_any()?;
+            *sim.encoders.get(handle)? = EncoderState {
+                reverse_direction: reverse_direction != 0,
+                encoding: encoding_type,
+                ..EncoderState::default()
+            };
+            Ok(handle)
+        })
+    }
+
+    unsafe fn HAL_FreeEncoder(handle: HAL_EncoderHandle, status: *mut i32) {
+        sim_call(status, |sim| sim.encoders.free(handle))
+    }
+
+    unsafe fn HAL_GetEncoder(handle: HAL_EncoderHandle, status: *mut i32) -> i32 {
+        sim_call(status, |sim| {
+            let encoder = sim.encoder(handle)?;
+            Ok(encoder.directed(encoder.raw / encoder.scale()))
+        })
+    }
+
+    unsafe fn HAL_GetEncoderRaw(handle: HAL_EncoderHandle, status: *mut i32) -> i32 {
+        sim_call(status, |sim| {
+            let encoder = sim.encoder(handle)?;
+            Ok(encoder.directed(encoder.raw))
+        })
+    }
+
+    unsafe fn HAL_GetEncoderEncodingScale(handle: HAL_EncoderHandle, status: *mut i32) -> i32 {
+        sim_call(status, |sim| Ok(sim.encoders.get(handle)?.scale()))
+    }
+
+    unsafe fn HAL_ResetEncoder(handle: HAL_EncoderHandle, status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.encoders.get(handle)?.raw = 0;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_GetEncoderPeriod(handle: HAL_EncoderHandle, status: *mut i32) -> f64 {
+        sim_call(status, |sim| Ok(sim.encoders.get(handle)?.period()))
+    }
+
+    unsafe fn HAL_SetEncoderMaxPeriod(handle: HAL_EncoderHandle,
+                                      max_period: f64,
+                                      status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.encoders.get(handle)?.max_period = max_period;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_GetEncoderStopped(handle: HAL_EncoderHandle, status: *mut i32) -> HAL_Bool {
+        sim_call(status, |sim| Ok(sim.encoders.get(handle)?.stopped() as HAL_Bool))
+    }
+
+    unsafe fn HAL_GetEncoderDirection(handle: HAL_EncoderHandle, status: *mut i32) -> HAL_Bool {
+        sim_call(status, |sim| {
+            let encoder = sim.encoders.get(handle)?;
+            Ok(((encoder.rate >= 0.0) != encoder.reverse_direction) as HAL_Bool)
+        })
+    }
+
+    unsafe fn HAL_GetEncoderDistance(handle: HAL_EncoderHandle, status: *mut i32) -> f64 {
+        sim_call(status, |sim| {
+            let encoder = sim.encoder(handle)?;
+            let distance = encoder.raw as f64 / encoder.scale() as f64 * encoder.distance_per_pulse;
+            Ok(encoder.directed(distance))
+        })
+    }
+
+    unsafe fn HAL_GetEncoderRate(handle: HAL_EncoderHandle, status: *mut i32) -> f64 {
+        sim_call(status, |sim| {
+            let encoder = sim.encoders.get(handle)?;
+            if encoder.stopped() {
+                Ok(0.0)
+            } else {
+                Ok(encoder.directed(encoder.rate * encoder.distance_per_pulse))
+            }
+        })
+    }
+
+    // As in the HAL, the minimum rate is in distance per second, and sets the max period.
+    unsafe fn HAL_SetEncoderMinRate(handle: HAL_EncoderHandle, min_rate: f64, status: *mut i32) {
+        sim_call(status, |sim| {
+            if min_rate == 0.0 {
+                return Err(PARAMETER_OUT_OF_RANGE as i32);
+            }
+            let encoder = sim.encoders.get(handle)?;
+            encoder.max_period = encoder.distance_per_pulse / min_rate;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_SetEncoderDistancePerPulse(handle: HAL_EncoderHandle,
+                                             distance_per_pulse: f64,
+                                             status: *mut i32) {
+        sim_call(status, |sim| {
+            if distance_per_pulse == 0.0 {
+                return Err(PARAMETER_OUT_OF_RANGE as i32);
+            }
+            sim.encoders.get(handle)?.distance_per_pulse = distance_per_pulse;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_SetEncoderReverseDirection(handle: HAL_EncoderHandle,
+                                             reverse_direction: HAL_Bool,
+                                             status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.encoders.get(handle)?.reverse_direction = reverse_direction != 0;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_SetEncoderSamplesToAverage(handle: HAL_EncoderHandle,
+                                             samples_to_average: i32,
+                                             status: *mut i32) {
+        sim_call(status, |sim| {
+            if !(1..=127).contains(&samples_to_average) {
+                return Err(PARAMETER_OUT_OF_RANGE as i32);
+            }
+            sim.encoders.get(handle)?.samples_to_average = samples_to_average;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_GetEncoderSamplesToAverage(handle: HAL_EncoderHandle, status: *mut i32) -> i32 {
+        sim_call(status, |sim| Ok(sim.encoders.get(handle)?.samples_to_average))
+    }
+
+    unsafe fn HAL_SetEncoderIndexSource(handle: HAL_EncoderHandle,
+                                        digital_source_handle: HAL_Handle,
+                                        analog_trigger_type: HAL_AnalogTriggerType,
+                                        indexing_type: HAL_EncoderIndexingType,
+                                        status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.check_digital_source(digital_source_handle)?;
+            sim.encoders.get(handle)?.index =
+                Some((digital_source_handle, analog_trigger_type, indexing_type));
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_GetEncoderFPGAIndex(handle: HAL_EncoderHandle, status: *mut i32) -> i32 {
+        sim_call(status, |sim| Ok(sim.encoders.index(handle)? as i32))
+    }
+
+    unsafe fn HAL_GetEncoderDecodingScaleFactor(handle: HAL_EncoderHandle,
+                                                status: *mut i32)
+                                                -> f64 {
+        sim_call(status, |sim| Ok(1.0 / sim.encoders.get(handle)?.scale() as f64))
+    }
+
+    unsafe fn HAL_GetEncoderDistancePerPulse(handle: HAL_EncoderHandle, status: *mut i32) -> f64 {
+        sim_call(status, |sim| Ok(sim.encoders.get(handle)?.distance_per_pulse))
+    }
+
+    // I2C
+    unsafe fn HAL_InitializeI2C(port: i32, status: *mut i32) {
+        sim_call(status, |sim| {
+            if check_index(port, NUM_I2C_PORTS) == 0 {
+                return Err(RESOURCE_OUT_OF_RANGE as i32);
+            }
+            sim.i2c[port as usize].open = true;
+            Ok(())
+        })
+    }
+
+    // Transfers to an address with no device are aborted, as if it had not acknowledged.
+    unsafe fn HAL_TransactionI2C(port: i32,
+                                 device_address: i32,
+                                 data_to_send: *mut u8,
+                                 send_size: i32,
+                                 data_received: *mut u8,
+                                 receive_size: i32)
+                                 -> i32 {
+        let mut sim = state();
+        match i2c_device(&mut sim, port, device_address) {
+            Some(device) => {
+                device.write(byte_slice(data_to_send, send_size));
+                device.read(byte_slice_mut(data_received, receive_size));
+                0
+            }
+            None => -1,
+        }
+    }
+
+    unsafe fn HAL_WriteI2C(port: i32,
+                           device_address: i32,
+                           data_to_send: *mut u8,
+                           send_size: i32)
+                           -> i32 {
+        let mut sim = state();
+        match i2c_device(&mut sim, port, device_address) {
+            Some(device) => {
+                device.write(byte_slice(data_to_send, send_size));
+                send_size.max(0)
+            }
+            None => -1,
+        }
+    }
+
+    unsafe fn HAL_ReadI2C(port: i32, device_address: i32, buffer: *mut u8, count: i32) -> i32 {
+        let mut sim = state();
+        match i2c_device(&mut sim, port, device_address) {
+            Some(device) => {
+                device.read(byte_slice_mut(buffer, count));
+                count.max(0)
+            }
+            None => -1,
+        }
+    }
+
+    unsafe fn HAL_CloseI2C(port: i32) {
+        if check_index(port, NUM_I2C_PORTS) != 0 {
+            state().i2c[port as usize].open = false;
+        }
+    }
+
+    // Interrupts
+    unsafe fn HAL_InitializeInterrupts(watcher: HAL_Bool, status: *mut i32) -> HAL_InterruptHandle {
+        sim_call(status, |sim| {
+            let handle = sim.interrupts.allocate_any()?;
+            *sim.interrupts.get(handle)? = InterruptState {
+                watcher: watcher != 0,
+                ..InterruptState::default()
+            };
+            Ok(handle)
+        })
+    }
+
+    // This blocks while the interrupt's handler is running, so it must not be called from the
+    // handler itself.
+    unsafe fn HAL_CleanInterrupts(handle: HAL_InterruptHandle, status: *mut i32) {
+        let _handler = INTERRUPT_HANDLER.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        sim_call(status, |sim| sim.interrupts.free(handle))
+    }
+
+    // Edges are only produced by `set_digital_input`, so the state is unlocked between polls.
+    unsafe fn HAL_WaitForInterrupt(handle: HAL_InterruptHandle,
+                                   timeout: f64,
+                                   ignore_previous: HAL_Bool,
+                                   status: *mut i32)
+                                   -> i64 {
+        let timeout = time::Duration::from_millis((timeout.max(0.0) * 1000.0) as u64);
+        let deadline = time::Instant::now() + timeout;
+        let mut first = true;
+        loop {
+            let result = sim_call(status, |sim| {
+                let interrupt = sim.interrupts.get(handle)?;
+                if first && ignore_previous != 0 {
+                    interrupt.pending = 0;
+                }
+                Ok(::std::mem::replace(&mut interrupt.pending, 0))
+            });
+            if result != 0 || *status != 0 || time::Instant::now() >= deadline {
+                return result;
+            }
+            first = false;
+            thread::sleep(time::Duration::from_millis(1));
+        }
+    }
+
+    unsafe fn HAL_EnableInterrupts(handle: HAL_InterruptHandle, status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.interrupts.get(handle)?.enabled = true;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_DisableInterrupts(handle: HAL_InterruptHandle, status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.interrupts.get(handle)?.enabled = false;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_ReadInterruptRisingTimestamp(handle: HAL_InterruptHandle,
+                                               status: *mut i32)
+                                               -> f64 {
+        sim_call(status, |sim| Ok(sim.interrupts.get(handle)?.rising_timestamp_us as f64 * 1e-6))
+    }
+
+    unsafe fn HAL_ReadInterruptFallingTimestamp(handle: HAL_InterruptHandle,
+                                                status: *mut i32)
+                                                -> f64 {
+        sim_call(status, |sim| Ok(sim.interrupts.get(handle)?.falling_timestamp_us as f64 * 1e-6))
+    }
+
+    unsafe fn HAL_RequestInterrupts(handle: HAL_InterruptHandle,
+                                    digital_source_handle: HAL_Handle,
+                                    _analog_trigger_type: HAL_AnalogTriggerType,
+                                    status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.check_digital_source(digital_source_handle)?;
+            sim.interrupts.get(handle)?.source = Some(digital_source_handle);
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_AttachInterruptHandler(handle: HAL_InterruptHandle,
+                                         handler: HAL_InterruptHandlerFunction,
+                                         param: *mut c_void,
+                                         status: *mut i32) {
+        sim_call(status, |sim| {
+            let interrupt = sim.interrupts.get(handle)?;
+            interrupt.handler = handler;
+            interrupt.param = param as usize;
+            Ok(())
+        })
+    }
+
+    // Handlers are always called from the thread that drives the input, so this is the same as
+    // attaching an ordinary handler.
+    unsafe fn HAL_AttachInterruptHandlerThreaded(handle: HAL_InterruptHandle,
+                                                 handler: HAL_InterruptHandlerFunction,
+                                                 param: *mut c_void,
+                                                 status: *mut i32) {
+        Sim::HAL_AttachInterruptHandler(handle, handler, param, status)
+    }
+
+    unsafe fn HAL_SetInterruptUpSourceEdge(handle: HAL_InterruptHandle,
+                                           rising_edge: HAL_Bool,
+                                           falling_edge: HAL_Bool,
+                                           status: *mut i32) {
+        sim_call(status, |sim| {
+            let interrupt = sim.interrupts.get(handle)?;
+            interrupt.rising = rising_edge != 0;
+            interrupt.falling = falling_edge != 0;
+            Ok(())
+        })
+    }
+
+    // Notifier
+    unsafe fn HAL_InitializeNotifier(process: HAL_NotifierProcessFunction,
+                                     param: *mut c_void,
+                                     status: *mut i32)
+                                     -> HAL_NotifierHandle {
+        sim_call(status, |sim| {
+            let handle = sim.notifiers.allocate_any()?;
+            *sim.notifiers.get(handle)? = NotifierState {
+                process,
+                param: param as usize,
+                trigger_time: None,
+            };
+            if !sim.notifier_thread {
+                thread::spawn(run_notifiers);
+                sim.notifier_thread = true;
+            }
+            Ok(handle)
+        })
+    }
+
+    // This waits for a running callback to finish. Callbacks may clean up other notifiers, like the
+    // HAL's recursive lock allows: the callback thread already holds the lock, and no other
+    // callback can be running.
+    unsafe fn HAL_CleanNotifier(handle: HAL_NotifierHandle, status: *mut i32) {
+        let _callback = if ON_NOTIFIER_THREAD.with(Cell::get) {
+            None
+        } else {
+            Some(NOTIFIER_CALLBACK.lock().unwrap_or_else(|poisoned| poisoned.into_inner()))
+        };
+        sim_call(status, |sim| sim.notifiers.free(handle));
+        NOTIFIER_ALARM.notify_all();
+    }
+
+    unsafe fn HAL_GetNotifierParam(handle: HAL_NotifierHandle, status: *mut i32) -> *mut c_void {
+        let param: usize = sim_call(status, |sim| Ok(sim.notifiers.get(handle)?.param));
+        param as *mut c_void
+    }
+
+    unsafe fn HAL_UpdateNotifierAlarm(handle: HAL_NotifierHandle,
+                                      trigger_time: u64,
+                                      status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.notifiers.get(handle)?.trigger_time = Some(trigger_time);
+            Ok(())
+        });
+        NOTIFIER_ALARM.notify_all();
+    }
+
+    unsafe fn HAL_StopNotifierAlarm(handle: HAL_NotifierHandle, status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.notifiers.get(handle)?.trigger_time = None;
+            Ok(())
+        });
+        NOTIFIER_ALARM.notify_all();
+    }
+
+    // PDP
+    unsafe fn HAL_InitializePDP(module: i32, status: *mut i32) {
+        sim_call(status, |sim| sim.pdp(module).map(|_| ()))
+    }
+
+    unsafe fn HAL_CheckPDPModule(module: i32) -> HAL_Bool {
+        check_index(module, NUM_PDP_MODULES)
+    }
+
+    unsafe fn HAL_GetPDPTemperature(module: i32, status: *mut i32) -> f64 {
+        sim_call(status, |sim| Ok(sim.pdp(module)?.temperature))
+    }
+
+    unsafe fn HAL_GetPDPVoltage(module: i32, status: *mut i32) -> f64 {
+        sim_call(status, |sim| Ok(sim.pdp(module)?.voltage))
+    }
+
+    unsafe fn HAL_GetPDPChannelCurrent(module: i32, channel: i32, status: *mut i32) -> f64 {
+        sim_call(status, |sim| {
+            if check_index(channel, NUM_PDP_CHANNELS) == 0 {
+                return Err(PARAMETER_OUT_OF_RANGE as i32);
+            }
+            Ok(sim.pdp(module)?.currents[channel as usize])
+        })
+    }
+
+    unsafe fn HAL_GetPDPTotalCurrent(module: i32, status: *mut i32) -> f64 {
+        sim_call(status, |sim| Ok(sim.pdp(module)?.currents.iter().sum()))
+    }
+
+    unsafe fn HAL_GetPDPTotalPower(module: i32, status: *mut i32) -> f64 {
+        sim_call(status, |sim| {
+            let pdp = sim.pdp(module)?;
+            Ok(pdp.voltage * pdp.currents.iter().sum::<f64>())
+        })
+    }
+
+    unsafe fn HAL_GetPDPTotalEnergy(module: i32, status: *mut i32) -> f64 {
+        sim_call(status, |sim| Ok(sim.pdp(module)?.total_energy))
+    }
+
+    unsafe fn HAL_ResetPDPTotalEnergy(module: i32, status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.pdp(module)?.total_energy = 0.0;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_ClearPDPStickyFaults(module: i32, status: *mut i32) {
+        sim_call(status, |sim| sim.pdp(module).map(|_| ()))
+    }
+
+    // PWM
+    unsafe fn HAL_InitializePWMPort(port_handle: HAL_PortHandle,
+                                    status: *mut i32)
+                                    -> HAL_DigitalHandle {
+        sim_call(status, |sim| {
+            let handle = sim.pwm.allocate(port_index(port_handle)?)?;
+            *sim.pwm.get(handle)? = PwmState::default();
+            Ok(handle)
+        })
+    }
+
+    unsafe fn HAL_FreePWMPort(handle: HAL_DigitalHandle, status: *mut i32) {
+        sim_call(status, |sim| sim.pwm.free(handle))
+    }
+
+    unsafe fn HAL_CheckPWMChannel(channel: i32) -> HAL_Bool {
+        check_index(channel, NUM_PWM_CHANNELS)
+    }
+
+    unsafe fn HAL_SetPWMConfig(handle: HAL_DigitalHandle,
+                               max_pwm: f64,
+                               deadband_max_pwm: f64,
+                               center_pwm: f64,
+                               deadband_min_pwm: f64,
+                               min_pwm: f64,
+                               status: *mut i32) {
+        let to_raw = |ms: f64| ((ms - PWM_CENTER_MS) / PWM_STEP_MS) as i32 + PWM_STEPS_DOWN - 1;
+        Sim::HAL_SetPWMConfigRaw(handle,
+                                 to_raw(max_pwm),
+                                 to_raw(deadband_max_pwm),
+                                 to_raw(center_pwm),
+                                 to_raw(deadband_min_pwm),
+                                 to_raw(min_pwm),
+                                 status)
+    }
+
+    unsafe fn HAL_SetPWMConfigRaw(handle: HAL_DigitalHandle,
+                                  max_pwm: i32,
+                                  deadband_max_pwm: i32,
+                                  center_pwm: i32,
+                                  deadband_min_pwm: i32,
+                                  min_pwm: i32,
+                                  status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.pwm.get(handle)?.config = Some(PwmConfig {
+                max: max_pwm,
+                deadband_max: deadband_max_pwm,
+                center: center_pwm,
+                deadband_min: deadband_min_pwm,
+                min: min_pwm,
+            });
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_SetPWMEliminateDeadband(handle: HAL_DigitalHandle,
+                                          eliminate_deadband: HAL_Bool,
+                                          status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.pwm.get(handle)?.eliminate_deadband = eliminate_deadband != 0;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_SetPWMRaw(handle: HAL_DigitalHandle, value: i32, status: *mut i32) {
+        sim_call(status, |sim| {
+            let pwm = sim.pwm.get(handle)?;
+            pwm.raw = value;
+            pwm.zero_latch = false;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_SetPWMSpeed(handle: HAL_DigitalHandle, speed: f64, status: *mut i32) {
+        sim_call(status, |sim| {
+            let pwm = sim.pwm.get(handle)?;
+            let config = pwm.config()?;
+            let speed = if speed.is_finite() { speed.clamp(-1.0, 1.0) } else { 0.0 };
+
+            let min_positive = pwm.min_positive(&config);
+            let max_negative = pwm.max_negative(&config);
+            pwm.raw = if speed == 0.0 {
+                config.center
+            } else if speed > 0.0 {
+                (speed * (config.max - min_positive) as f64 + min_positive as f64 + 0.5) as i32
+            } else {
+                (speed * (max_negative - config.min) as f64 + max_negative as f64 + 0.5) as i32
+            };
+            pwm.zero_latch = false;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_SetPWMPosition(handle: HAL_DigitalHandle, position: f64, status: *mut i32) {
+        sim_call(status, |sim| {
+            let pwm = sim.pwm.get(handle)?;
+            let config = pwm.config()?;
+            let position = position.clamp(0.0, 1.0);
+            pwm.raw = (position * (config.max - config.min) as f64) as i32 + config.min;
+            pwm.zero_latch = false;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_SetPWMDisabled(handle: HAL_DigitalHandle, status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.pwm.get(handle)?.raw = 0;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_GetPWMRaw(handle: HAL_DigitalHandle, status: *mut i32) -> i32 {
+        sim_call(status, |sim| Ok(sim.pwm.get(handle)?.raw))
+    }
+
+    unsafe fn HAL_GetPWMSpeed(handle: HAL_DigitalHandle, status: *mut i32) -> f64 {
+        sim_call(status, |sim| sim.pwm.get(handle)?.speed())
+    }
+
+    unsafe fn HAL_LatchPWMZero(handle: HAL_DigitalHandle, status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.pwm.get(handle)?.zero_latch = true;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_SetPWMPeriodScale(handle: HAL_DigitalHandle,
+                                    squelch_mask: i32,
+                                    status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.pwm.get(handle)?.period_scale = squelch_mask;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_GetNumPWMChannels() -> i32 {
+        NUM_PWM_CHANNELS as i32
+    }
+
+    // Ports
+    unsafe fn HAL_GetNumRelayHeaders() -> i32 {
+        NUM_RELAY_HEADERS as i32
+    }
+
+    unsafe fn HAL_GetNumPCMModules() -> i32 {
+        NUM_PCM_MODULES as i32
+    }
+
+    unsafe fn HAL_GetNumSolenoidChannels() -> i32 {
+        NUM_SOLENOID_CHANNELS as i32
+    }
+
+    // Power
+    unsafe fn HAL_GetVinVoltage(status: *mut i32) -> f64 {
+        sim_call(status, |sim| Ok(sim.vin_voltage))
+    }
+
+    unsafe fn HAL_GetUserVoltage5V(status: *mut i32) -> f64 {
+        sim_call(status, |sim| Ok(sim.user_voltage_5v))
+    }
+
+    // Relay
+    unsafe fn HAL_InitializeRelayPort(port_handle: HAL_PortHandle,
+                                      fwd: HAL_Bool,
+                                      status: *mut i32)
+                                      -> HAL_RelayHandle {
+        sim_call(status, |sim| {
+            let channel = port_index(port_handle)?;
+            if channel >= NUM_RELAY_HEADERS {
+                return Err(RESOURCE_OUT_OF_RANGE as i32);
+            }
+            let index = if fwd != 0 { channel } else { channel + NUM_RELAY_HEADERS };
+            let handle = sim.relays.allocate(index)?;
+            sim.relays.get(handle)?.on = false;
+            Ok(handle)
+        })
+    }
+
+    unsafe fn HAL_FreeRelayPort(handle: HAL_RelayHandle) {
+        let mut sim = state();
+        if let Ok(relay) = sim.relays.get(handle) {
+            relay.on = false;
+        }
+        let _ = sim.relays.free(handle);
+    }
+
+    unsafe fn HAL_CheckRelayChannel(channel: i32) -> HAL_Bool {
+        check_index(channel, NUM_RELAY_HEADERS)
+    }
+
+    unsafe fn HAL_SetRelay(handle: HAL_RelayHandle, on: HAL_Bool, status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.relays.get(handle)?.on = on != 0;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_GetRelay(handle: HAL_RelayHandle, status: *mut i32) -> HAL_Bool {
+        sim_call(status, |sim| Ok(sim.relays.get(handle)?.on as HAL_Bool))
+    }
+
+    // Serial
+    unsafe fn HAL_InitializeSerialPort(port: HAL_SerialPort, status: *mut i32) {
+        sim_call(status, |sim| {
+            let serial = &mut sim.serial[port as usize];
+            if serial.open {
+                return Err(RESOURCE_IS_ALLOCATED as i32);
+            }
+            *serial = SerialState::default();
+            serial.open = true;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_SetSerialBaudRate(port: HAL_SerialPort, baud: i32, status: *mut i32) {
+        sim_call(status, |sim| {
+            if baud <= 0 {
+                return Err(PARAMETER_OUT_OF_RANGE as i32);
+            }
+            sim.serial(port)?.baud_rate = baud;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_SetSerialDataBits(port: HAL_SerialPort, bits: i32, status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.serial(port)?;
+            if (5..=8).contains(&bits) { Ok(()) } else { Err(PARAMETER_OUT_OF_RANGE as i32) }
+        })
+    }
+
+    unsafe fn HAL_SetSerialParity(port: HAL_SerialPort, parity: i32, status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.serial(port)?;
+            if (0..=4).contains(&parity) { Ok(()) } else { Err(PARAMETER_OUT_OF_RANGE as i32) }
+        })
+    }
+
+    unsafe fn HAL_SetSerialStopBits(port: HAL_SerialPort, stop_bits: i32, status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.serial(port)?;
+            match stop_bits {
+                10 | 15 | 20 => Ok(()),
+                _ => Err(PARAMETER_OUT_OF_RANGE as i32),
+            }
+        })
+    }
+
+    unsafe fn HAL_SetSerialWriteMode(port: HAL_SerialPort, mode: i32, status: *mut i32) {
+        sim_call(status, |sim| {
+            let serial = sim.serial(port)?;
+            if mode != 1 && mode != 2 {
+                return Err(PARAMETER_OUT_OF_RANGE as i32);
+            }
+            serial.write_mode = mode;
+            serial.flush();
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_SetSerialFlowControl(port: HAL_SerialPort, flow: i32, status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.serial(port)?;
+            match flow {
+                0 | 1 | 2 | 4 => Ok(()),
+                _ => Err(PARAMETER_OUT_OF_RANGE as i32),
+            }
+        })
+    }
+
+    unsafe fn HAL_SetSerialTimeout(port: HAL_SerialPort, timeout: f64, status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.serial(port)?.timeout = timeout.max(0.0);
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_EnableSerialTermination(port: HAL_SerialPort,
+                                          terminator: c_char,
+                                          status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.serial(port)?.termination = Some(terminator as u8);
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_DisableSerialTermination(port: HAL_SerialPort, status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.serial(port)?.termination = None;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_SetSerialReadBufferSize(port: HAL_SerialPort, size: i32, status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.serial(port)?;
+            if size > 0 { Ok(()) } else { Err(PARAMETER_OUT_OF_RANGE as i32) }
+        })
+    }
+
+    unsafe fn HAL_SetSerialWriteBufferSize(port: HAL_SerialPort, size: i32, status: *mut i32) {
+        sim_call(status, |sim| {
+            if size <= 0 {
+                return Err(PARAMETER_OUT_OF_RANGE as i32);
+            }
+            sim.serial(port)?.write_buffer_size = size as usize;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_GetSerialBytesReceived(port: HAL_SerialPort, status: *mut i32) -> i32 {
+        sim_call(status, |sim| Ok(sim.serial(port)?.received.len() as i32))
+    }
+
+    // As on the roboRIO, a read returns once `count` bytes or the terminator have arrived, or the
+    // timeout expires. The state is unlocked between polls so that data can arrive.
+    unsafe fn HAL_ReadSerial(port: HAL_SerialPort,
+                             buffer: *mut c_char,
+                             count: i32,
+                             status: *mut i32)
+                             -> i32 {
+        let count = count.max(0) as usize;
+        let mut read = Vec::with_capacity(count);
+        let mut deadline = None;
+        loop {
+            let done = sim_call(status, |sim| {
+                let serial = sim.serial(port)?;
+                while read.len() < count {
+                    match serial.received.pop_front() {
+                        Some(byte) => {
+                            read.push(byte);
+                            if serial.termination == Some(byte) {
+                                return Ok(true);
+                            }
+                        }
+                        None => break,
+                    }
+                }
+                let timeout = time::Duration::from_millis((serial.timeout * 1000.0) as u64);
+                let deadline = *deadline.get_or_insert_with(|| time::Instant::now() + timeout);
+                Ok(read.len() == count || time::Instant::now() >= deadline)
+            });
+            if done || *status != 0 {
+                break;
+            }
+            thread::sleep(time::Duration::from_millis(1));
+        }
+        ::std::ptr::copy_nonoverlapping(read.as_ptr(), buffer as *mut u8, read.len());
+        read.len() as i32
+    }
+
+    unsafe fn HAL_WriteSerial(port: HAL_SerialPort,
+                              buffer: *const c_char,
+                              count: i32,
+                              status: *mut i32)
+                              -> i32 {
+        sim_call(status, |sim| {
+            let serial = sim.serial(port)?;
+            let count = count.max(0) as usize;
+            serial.pending.extend_from_slice(::std::slice::from_raw_parts(buffer as *const u8,
+                                                                          count));
+            if serial.write_mode == 1 || serial.pending.len() >= serial.write_buffer_size {
+                serial.flush();
+            }
+            Ok(count as i32)
+        })
+    }
+
+    unsafe fn HAL_FlushSerial(port: HAL_SerialPort, status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.serial(port)?.flush();
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_ClearSerial(port: HAL_SerialPort, status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.serial(port)?.received.clear();
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_CloseSerial(port: HAL_SerialPort, status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.serial(port)?.open = false;
+            Ok(())
+        })
+    }
+
+    // SPI
+    unsafe fn HAL_InitializeSPI(port: i32, status: *mut i32) {
+        sim_call(status, |sim| {
+            if check_index(port, NUM_SPI_PORTS) == 0 {
+                return Err(PARAMETER_OUT_OF_RANGE as i32);
+            }
+            // As in the HAL, opening a port that is already open does nothing. Responses queued
+            // before the port is opened are kept, so that a device can reply as it is set up.
+            let spi = &mut sim.spi[port as usize];
+            if !spi.open {
+                *spi = SpiState {
+                    open: true,
+                    responses: ::std::mem::take(&mut spi.responses),
+                    ..SpiState::default()
+                };
+            }
+            Ok(())
+        })
+    }
+
+    // The simulated device replies with the bytes queued through `sim::spi`.
+    unsafe fn HAL_TransactionSPI(port: i32,
+                                 data_to_send: *mut u8,
+                                 data_received: *mut u8,
+                                 size: i32)
+                                 -> i32 {
+        if Sim::HAL_WriteSPI(port, data_to_send, size) < 0 {
+            return -1;
+        }
+        Sim::HAL_ReadSPI(port, data_received, size)
+    }
+
+    unsafe fn HAL_WriteSPI(port: i32, data_to_send: *mut u8, send_size: i32) -> i32 {
+        let mut sim = state();
+        match spi(&mut sim, port) {
+            Some(spi) => {
+                let count = send_size.max(0) as usize;
+                spi.sent.extend_from_slice(::std::slice::from_raw_parts(data_to_send, count));
+                count as i32
+            }
+            None => -1,
+        }
+    }
+
+    unsafe fn HAL_ReadSPI(port: i32, buffer: *mut u8, count: i32) -> i32 {
+        let mut sim = state();
+        match spi(&mut sim, port) {
+            Some(spi) => {
+                let received = spi.receive(count.max(0) as usize);
+                ::std::ptr::copy_nonoverlapping(received.as_ptr(), buffer, received.len());
+                received.len() as i32
+            }
+            None => -1,
+        }
+    }
+
+    unsafe fn HAL_CloseSPI(port: i32) {
+        if let Some(spi) = spi(&mut state(), port) {
+            spi.open = false;
+        }
+    }
+
+    unsafe fn HAL_SetSPISpeed(port: i32, speed: i32) {
+        if let Some(spi) = spi(&mut state(), port) {
+            spi.speed = speed;
+        }
+    }
+
+    unsafe fn HAL_SetSPIOpts(port: i32,
+                             msb_first: HAL_Bool,
+                             sample_on_trailing: HAL_Bool,
+                             clk_idle_high: HAL_Bool) {
+        if let Some(spi) = spi(&mut state(), port) {
+            spi.msb_first = msb_first != 0;
+            spi.sample_on_trailing = sample_on_trailing != 0;
+            spi.clk_idle_high = clk_idle_high != 0;
+        }
+    }
+
+    unsafe fn HAL_SetSPIChipSelectActiveHigh(port: i32, status: *mut i32) {
+        sim_call(status, |sim| {
+            spi(sim, port).ok_or(RESOURCE_OUT_OF_RANGE as i32)?.chip_select_active_high = true;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_SetSPIChipSelectActiveLow(port: i32, status: *mut i32) {
+        sim_call(status, |sim| {
+            spi(sim, port).ok_or(RESOURCE_OUT_OF_RANGE as i32)?.chip_select_active_high = false;
+            Ok(())
+        })
+    }
+
+    // The accumulator doesn't talk to the simulated device, so only the port is checked.
+    unsafe fn HAL_InitSPIAccumulator(port: i32,
+                                     _period: i32,
+                                     _cmd: i32,
+                                     _xfer_size: i32,
+                                     _valid_mask: i32,
+                                     _valid_value: i32,
+                                     _data_shift: i32,
+                                     _data_size: i32,
+                                     _is_signed: HAL_Bool,
+                                     _big_endian: HAL_Bool,
+                                     status: *mut i32) {
+        sim_call(status, |sim| {
+            let spi = spi(sim, port).ok_or(RESOURCE_OUT_OF_RANGE as i32)?;
+            spi.accumulator = Some(SpiAccumulatorState::default());
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_FreeSPIAccumulator(port: i32, status: *mut i32) {
+        sim_call(status, |sim| {
+            spi_accumulator(sim, port)?;
+            sim.spi[port as usize].accumulator = None;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_ResetSPIAccumulator(port: i32, status: *mut i32) {
+        sim_call(status, |sim| {
+            let accumulator = spi_accumulator(sim, port)?;
+            accumulator.value = 0;
+            accumulator.count = 0;
+            accumulator.last_value = 0;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_SetSPIAccumulatorCenter(port: i32, center: i32, status: *mut i32) {
+        sim_call(status, |sim| {
+            spi_accumulator(sim, port)?.center = center;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_SetSPIAccumulatorDeadband(port: i32, deadband: i32, status: *mut i32) {
+        sim_call(status, |sim| {
+            spi_accumulator(sim, port)?.deadband = deadband;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_GetSPIAccumulatorLastValue(port: i32, status: *mut i32) -> i32 {
+        sim_call(status, |sim| Ok(spi_accumulator(sim, port)?.last_value))
+    }
+
+    unsafe fn HAL_GetSPIAccumulatorValue(port: i32, status: *mut i32) -> i64 {
+        sim_call(status, |sim| Ok(spi_accumulator(sim, port)?.value))
+    }
+
+    unsafe fn HAL_GetSPIAccumulatorCount(port: i32, status: *mut i32) -> i64 {
+        sim_call(status, |sim| Ok(spi_accumulator(sim, port)?.count))
+    }
+
+    unsafe fn HAL_GetSPIAccumulatorAverage(port: i32, status: *mut i32) -> f64 {
+        sim_call(status, |sim| {
+            let accumulator = spi_accumulator(sim, port)?;
+            if accumulator.count == 0 {
+                Ok(0.0)
+            } else {
+                Ok(accumulator.value as f64 / accumulator.count as f64)
+            }
+        })
+    }
+
+    unsafe fn HAL_GetSPIAccumulatorOutput(port: i32,
+                                          value: *mut i64,
+                                          count: *mut i64,
+                                          status: *mut i32) {
+        if value.is_null() || count.is_null() {
+            *status = NULL_PARAMETER as i32;
+            return;
+        }
+        let (v, c) = sim_call(status, |sim| {
+            let accumulator = spi_accumulator(sim, port)?;
+            Ok((accumulator.value, accumulator.count))
+        });
+        *value = v;
+        *count = c;
+    }
+
+    // Solenoid
+    unsafe fn HAL_InitializeSolenoidPort(port_handle: HAL_PortHandle,
+                                         status: *mut i32)
+                                         -> HAL_SolenoidHandle {
+        sim_call(status, |sim| {
+            let (module, channel) = port_module_channel(port_handle)?;
+            if module >= NUM_PCM_MODULES || channel >= NUM_SOLENOID_CHANNELS {
+                return Err(RESOURCE_OUT_OF_RANGE as i32);
+            }
+            let handle = sim.solenoids.allocate(module * NUM_SOLENOID_CHANNELS + channel)?;
+            sim.solenoids.get(handle)?.value = false;
+            Ok(handle)
+        })
+    }
+
+    unsafe fn HAL_FreeSolenoidPort(handle: HAL_SolenoidHandle) {
+        let _ = state().solenoids.free(handle);
+    }
+
+    unsafe fn HAL_CheckSolenoidModule(module: i32) -> HAL_Bool {
+        check_index(module, NUM_PCM_MODULES)
+    }
+
+    unsafe fn HAL_CheckSolenoidChannel(channel: i32) -> HAL_Bool {
+        check_index(channel, NUM_SOLENOID_CHANNELS)
+    }
+
+    unsafe fn HAL_GetSolenoid(handle: HAL_SolenoidHandle, status: *mut i32) -> HAL_Bool {
+        sim_call(status, |sim| Ok(sim.solenoids.get(handle)?.value as HAL_Bool))
+    }
+
+    unsafe fn HAL_GetAllSolenoids(module: i32, status: *mut i32) -> i32 {
+        sim_call(status, |sim| {
+            sim.pcm(module)?;
+            let first = module as usize * NUM_SOLENOID_CHANNELS;
+            let solenoids = &sim.solenoids.devices[first..first + NUM_SOLENOID_CHANNELS];
+            Ok(solenoids.iter()
+                .enumerate()
+                .fold(0, |all, (channel, solenoid)| all | (solenoid.value as i32) << channel))
+        })
+    }
+
+    unsafe fn HAL_SetSolenoid(handle: HAL_SolenoidHandle, value: HAL_Bool, status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.solenoids.get(handle)?.value = value != 0;
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_SetAllSolenoids(module: i32, state: i32, status: *mut i32) {
+        sim_call(status, |sim| {
+            sim.pcm(module)?;
+            let first = module as usize * NUM_SOLENOID_CHANNELS;
+            let solenoids = &mut sim.solenoids.devices[first..first + NUM_SOLENOID_CHANNELS];
+            for (channel, solenoid) in solenoids.iter_mut().enumerate() {
+                solenoid.value = state & (1 << channel) != 0;
+            }
+            Ok(())
+        })
+    }
+
+    unsafe fn HAL_GetPCMSolenoidBlackList(module: i32, status: *mut i32) -> i32 {
+        sim_call(status, |sim| Ok(sim.pcm(module)?.solenoid_blacklist))
+    }
+
+    unsafe fn HAL_GetPCMSolenoidVoltageStickyFault(module: i32, status: *mut i32) -> HAL_Bool {
+        sim_call(status, |sim| Ok(sim.pcm(module)?.solenoid_voltage_sticky_fault as HAL_Bool))
+    }
+
+    unsafe fn HAL_GetPCMSolenoidVoltageFault(module: i32, status: *mut i32) -> HAL_Bool {
+        sim_call(status, |sim| Ok(sim.pcm(module)?.solenoid_voltage_fault as HAL_Bool))
+    }
+
+    unsafe fn HAL_ClearAllPCMStickyFaults(module: i32, status: *mut i32) {
+        sim_call(status, |sim| {
+            // Shorted channels stay blacklisted until sticky faults are cleared.
+            let pcm = sim.pcm(module)?;
+            pcm.solenoid_voltage_sticky_fault = false;
+            pcm.solenoid_blacklist = 0;
+            pcm.current_too_high_sticky_fault = false;
+            pcm.shorted_sticky_fault = false;
+            pcm.not_connected_sticky_fault = false;
+            Ok(())
+        })
+    }
+
+    // HAL
+    unsafe fn HAL_GetErrorMessage(code: i32) -> *const c_char {
+        error_message(code).as_ptr() as *const c_char
+    }
+
+    unsafe fn HAL_GetFPGAVersion(_status: *mut i32) -> i32 {
+        2017
+    }
+
+    unsafe fn HAL_GetFPGARevision(_status: *mut i32) -> i64 {
+        0
+    }
+
+    unsafe fn HAL_GetFPGAButton(_status: *mut i32) -> HAL_Bool {
+        0
+    }
+
+    unsafe fn HAL_GetSystemActive(status: *mut i32) -> HAL_Bool {
+        sim_call(status, |sim| Ok((sim.control_word & 1 != 0 && !sim.browned_out) as HAL_Bool))
+    }
+
+    unsafe fn HAL_GetBrownedOut(status: *mut i32) -> HAL_Bool {
+        sim_call(status, |sim| Ok(sim.browned_out as HAL_Bool))
+    }
+
+    unsafe fn HAL_GetPort(channel: i32) -> HAL_PortHandle {
+        Sim::HAL_GetPortWithModule(0, channel)
+    }
+
+    unsafe fn HAL_GetPortWithModule(module: i32, channel: i32) -> HAL_PortHandle {
+        if !(0..255).contains(&channel) || !(0..255).contains(&module) {
+            HAL_kInvalidHandle as HAL_PortHandle
+        } else {
+            ((HandleKind::Port as i32) << 24) | (module << 8) | channel
+        }
+    }
+
+    unsafe fn HAL_GetFPGATime(_status: *mut i32) -> u64 {
+        state().time_us()
+    }
+
+    unsafe fn HAL_GetSystemClockTicksPerMicrosecond() -> i32 {
+        SYSTEM_CLOCK_TICKS_PER_MICROSECOND
+    }
+
+    unsafe fn HAL_Initialize(_mode: i32) -> i32 {
+        state();
+        1
+    }
+
+    unsafe fn HAL_Report(_resource: i32,
+                         _instance_number: i32,
+                         _context: i32,
+                         _feature: *const c_char)
+                         -> i64 {
+        0
+    }
+}
+
+/// Get the channel number from a port handle.
+fn port_index(port_handle: HAL_PortHandle) -> Result<usize, i32> {
+    port_module_channel(port_handle).map(|(_, channel)| channel)
+}
+
+/// Get the module and channel numbers from a port handle.
+fn port_module_channel(port_handle: HAL_PortHandle) -> Result<(usize, usize), i32> {
+    if port_handle >> 24 != HandleKind::Port as i32 {
+        Err(HAL_HANDLE_ERROR as i32)
+    } else {
+        Ok((((port_handle >> 8) & 0xff) as usize, (port_handle & 0xff) as usize))
+    }
+}
+
+/// Get the state of a joystick, or an empty joystick if the port does not exist.
+fn joystick(joystick_num: i32) -> JoystickState {
+    if check_index(joystick_num, NUM_JOYSTICKS) != 0 {
+        state().joysticks[joystick_num as usize]
+    } else {
+        JoystickState::default()
+    }
+}
+
+/// Get the device at an address on an open I2C port.
+fn i2c_device(sim: &mut SimState, port: i32, address: i32) -> Option<&mut I2cDeviceState> {
+    if check_index(port, NUM_I2C_PORTS) != 0 && sim.i2c[port as usize].open {
+        sim.i2c[port as usize].devices.get_mut(&address)
+    } else {
+        None
+    }
+}
+
+/// View a buffer passed to the HAL, which may be null if it is empty.
+unsafe fn byte_slice<'a>(data: *const u8, size: i32) -> &'a [u8] {
+    if data.is_null() || size <= 0 {
+        &[]
+    } else {
+        ::std::slice::from_raw_parts(data, size as usize)
+    }
+}
+
+/// View a buffer passed to the HAL for writing, which may be null if it is empty.
+unsafe fn byte_slice_mut<'a>(data: *mut u8, size: i32) -> &'a mut [u8] {
+    if data.is_null() || size <= 0 {
+        &mut []
+    } else {
+        ::std::slice::from_raw_parts_mut(data, size as usize)
+    }
+}
+
+/// Get an open SPI port.
+fn spi(sim: &mut SimState, port: i32) -> Option<&mut SpiState> {
+    if check_index(port, NUM_SPI_PORTS) != 0 && sim.spi[port as usize].open {
+        Some(&mut sim.spi[port as usize])
+    } else {
+        None
+    }
+}
+
+/// Get the accumulator of an open SPI port.
+fn spi_accumulator(sim: &mut SimState, port: i32) -> Result<&mut SpiAccumulatorState, i32> {
+    spi(sim, port).and_then(|spi| spi.accumulator.as_mut()).ok_or(NULL_PARAMETER as i32)
+}
+
+/// The message for a HAL status code, as a nul-terminated string.
+fn error_message(code: i32) -> &'static [u8] {
+    match code {
+        0 => b"\0",
+        c if c == SAMPLE_RATE_TOO_HIGH as i32 => b"HAL: Analog module sample rate is too high\0",
+        c if c == VOLTAGE_OUT_OF_RANGE as i32 => {
+            b"HAL: Voltage to convert to raw value is out of range [0; 5]\0"
+        }
+        c if c == LOOP_TIMING_ERROR as i32 => {
+            b"HAL: Digital module loop timing is not the expected value\0"
+        }
+        c if c == SPI_WRITE_NO_MOSI as i32 => {
+            b"HAL: Cannot write to SPI port with no MOSI output\0"
+        }
+        c if c == SPI_READ_NO_MISO as i32 => b"HAL: Cannot read from SPI port with no MISO input\0",
+        c if c == SPI_READ_NO_DATA as i32 => b"HAL: No data available to read from SPI\0",
+        c if c == INCOMPATIBLE_STATE as i32 => {
+            b"HAL: Incompatible State: The operation cannot be completed\0"
+        }
+        c if c == NO_AVAILABLE_RESOURCES as i32 => b"HAL: No available resources to allocate\0",
+        c if c == NULL_PARAMETER as i32 => b"HAL: A pointer parameter to a method is NULL\0",
+        c if c == ANALOG_TRIGGER_LIMIT_ORDER_ERROR as i32 => {
+            b"HAL: AnalogTrigger limits error.  Lower limit > Upper Limit\0"
+        }
+        c if c == ANALOG_TRIGGER_PULSE_OUTPUT_ERROR as i32 => {
+            b"HAL: Attempted to read AnalogTrigger pulse output.\0"
+        }
+        c if c == PARAMETER_OUT_OF_RANGE as i32 => b"HAL: A parameter is out of range.\0",
+        c if c == RESOURCE_IS_ALLOCATED as i32 => b"HAL: Resource already allocated\0",
+        c if c == RESOURCE_OUT_OF_RANGE as i32 => {
+            b"HAL: The requested resource is out of range.\0"
+        }
+        c if c == HAL_INVALID_ACCUMULATOR_CHANNEL as i32 => {
+            b"HAL: The requested input is not an accumulator channel\0"
+        }
+        c if c == HAL_COUNTER_NOT_SUPPORTED as i32 => {
+            b"HAL: Counter mode not supported for encoder method\0"
+        }
+        c if c == HAL_PWM_SCALE_ERROR as i32 => {
+            b"HAL: The PWM Scale Factors need to be set before using them.\0"
+        }
+        c if c == HAL_HANDLE_ERROR as i32 => b"HAL: A handle parameter was passed incorrectly\0",
+        _ => b"Unknown error status\0",
+    }
+}
@@ -1,9 +1,10 @@
-use wpilib::wpilib_hal::*;
 use wpilib::usage::*;
 use wpilib::hal_call::*;
+use std::{error, fmt};
 
 /// Describes one of two possible locations on the roborio for i2c devices
-#[derive(Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[allow(clippy::upper_case_acronyms)]
 pub enum I2cPort {
     /// Enum option for i2c device plugged into the onboard i2c connectors on the roborio
     OnBoard = 0,
@@ -12,19 +13,57 @@ pub enum I2cPort {
 }
 
 /// Describe various errored or otherwise special cases that may result from an i2c transaction
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum I2cError {
     // TODO find is there is any way to get more i2c transaction information out of HAl than just
     // success or failure
 
     /// Indicates a general error in an i2c transaction
     IOError,
-    /// Indicates when no string, or an invalid string was received from the i2c transaction
+    /// Indicates that the bytes received were not valid UTF-8
     InvalidReceiveString,
-    /// Indicates a situation where the connection was specifically aborted
+    /// Indicates a situation where the connection was specifically aborted, such as when no
+    /// device acknowledged the address
     TransferAbort,
 }
 
+impl fmt::Display for I2cError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            I2cError::IOError => write!(f, "I2C transfer failed"),
+            I2cError::InvalidReceiveString => write!(f, "I2C device sent invalid UTF-8"),
+            I2cError::TransferAbort => write!(f, "I2C transfer aborted"),
+        }
+    }
+}
+
+impl error::Error for I2cError {}
+
+/// Turn the status returned by the HAL into a result.
+fn transfer_result(status: i32) -> Result<(), I2cError> {
+    match status {
+        -1 => Err(I2cError::TransferAbort),
+        x if x >= 0 => Ok(()),
+        _ => Err(I2cError::IOError),
+    }
+}
+
 /// Struct for sending and receiving data over i2c
+///
+/// Most devices are a bank of registers: writing a register address sets the device's pointer,
+/// and reads then return consecutive registers from there. The register helpers work this way.
+///
+/// # Usage
+/// ```
+/// # use wpilib::*;
+/// # sim::i2c(I2cPort::OnBoard).add_device(0x1d);
+/// # sim::i2c(I2cPort::OnBoard).set_registers(0x1d, 0x00, &[0xe5]);
+/// let mut device = I2cInterface::new(I2cPort::OnBoard, 0x1d).unwrap();
+/// // Check the device ID before configuring it.
+/// assert!(device.verify_sensor(0x00, &[0xe5]).unwrap());
+/// device.write_register(0x2d, 0x08).unwrap();
+/// # assert_eq!(sim::i2c(I2cPort::OnBoard).registers(0x1d, 0x2d, 1), [0x08]);
+/// ```
 pub struct I2cInterface {
     port: I2cPort,
     device_address: i32,
@@ -33,98 +72,109 @@ pub struct I2cInterface {
 impl I2cInterface {
     /// Create a new I2cInterface here
     pub fn new(p: I2cPort, addr: i32) -> HalResult<I2cInterface> {
-        let mut init_status = 0;
-        unsafe {
-            HAL_InitializeI2C(p as i32, &mut init_status as *mut i32);
-            report_usage(ResourceType::I2C, p as i32);
-        }
-        match init_status {
-            0 => Ok(I2cInterface { port: p, device_address: addr }),
-            _ => Err(HalError(0)),
-        }
+        hal_call!(HAL_InitializeI2C(p as i32))?;
+        report_usage(ResourceType::I2C, p as i32);
+        Ok(I2cInterface { port: p, device_address: addr })
+    }
+
+    /// Get the port the device is on
+    pub fn get_port(&self) -> I2cPort {
+        self.port
+    }
+
+    /// Get the address of the device
+    pub fn get_device_address(&self) -> i32 {
+        self.device_address
     }
 
     /// Perform a simultaneous read from and write to an i2c device
     pub fn transaction(&mut self, sent: &[u8], received: &mut [u8]) -> Result<(), I2cError> {
-        let status = unsafe {
-            HAL_TransactionI2C(self.port as i32, self.device_address, sent.as_ptr() as *mut u8, sent.len() as i32,
-                                                                      received.as_mut_ptr(), received.len() as i32)
-        };
-        match status {
-            -1 => Err(I2cError::TransferAbort),
-            x if x >= 0 => Ok(()),
-            _ => Err(I2cError::IOError),
-        }
+        transfer_result(unsafe {
+            Hal::HAL_TransactionI2C(self.port as i32,
+                                    self.device_address,
+                                    sent.as_ptr() as *mut u8,
+                                    sent.len() as i32,
+                                    received.as_mut_ptr(),
+                                    received.len() as i32)
+        })
+    }
+
+    /// Send only the device's address, to check whether it is present
+    pub fn address_only(&mut self) -> Result<(), I2cError> {
+        self.transaction(&[], &mut [])
     }
 
     /// Read received message to inputed byte slice
     pub fn read(&self, received: &mut [u8]) -> Result<(), I2cError> {
-        let status = unsafe {
-            HAL_ReadI2C(self.port as i32, self.device_address, received.as_mut_ptr(), received.len() as i32)
-        };
-        match status {
-            -1 => Err(I2cError::TransferAbort),
-            x if x >= 0 => Ok(()),
-            _ => Err(I2cError::IOError),
-        }
+        transfer_result(unsafe {
+            Hal::HAL_ReadI2C(self.port as i32,
+                             self.device_address,
+                             received.as_mut_ptr(),
+                             received.len() as i32)
+        })
+    }
+
+    /// Read `count` bytes from the device
+    pub fn read_vec(&self, count: usize) -> Result<Vec<u8>, I2cError> {
+        let mut received = vec![0; count];
+        self.read(&mut received)?;
+        Ok(received)
     }
 
     /// Write byte slice to i2c device
     pub fn write(&mut self, sent: &[u8]) -> Result<(), I2cError> {
-        let status = unsafe {
-            HAL_WriteI2C(self.port as i32, self.device_address, sent.as_ptr() as *mut u8, sent.len() as i32)
-        };
-        match status {
-            -1 => Err(I2cError::TransferAbort),
-            x if x >= 0 => Ok(()),
-            _ => Err(I2cError::IOError),
-        }
+        transfer_result(unsafe {
+            Hal::HAL_WriteI2C(self.port as i32,
+                              self.device_address,
+                              sent.as_ptr() as *mut u8,
+                              sent.len() as i32)
+        })
     }
 
-    /// Perform string transaction with connected i2c device
-    pub fn string_transaction(&mut self, sent_string: &str, received_string: &mut str) -> Result<(), I2cError> {
-        let status = unsafe {
-            HAL_TransactionI2C(self.port as i32, self.device_address, sent_string.as_bytes().as_ptr() as *mut u8, sent_string.len() as i32,
-                                                                      received_string.as_bytes().as_ptr() as *mut u8, received_string.len() as i32)
-        };
-        match status {
-            -1 => Err(I2cError::TransferAbort),
-            x if x >= 0 => Ok(()),
-            _ => Err(I2cError::IOError),
-        }
+    /// Write a single byte to a register
+    pub fn write_register(&mut self, register: u8, value: u8) -> Result<(), I2cError> {
+        self.write(&[register, value])
     }
 
-    /// Read received message to str
-    pub fn read_string(&self, received_string: &mut str) -> Result<(), I2cError> {
-        let status = unsafe {
-            HAL_ReadI2C(self.port as i32, self.device_address, received_string.as_bytes().as_ptr() as *mut u8, received_string.len() as i32)
-        };
-        match status {
-            -1 => Err(I2cError::TransferAbort),
-            x if x >= 0 => match received_string.len() == x as usize {
-                true => Ok(()),
-                false => Err(I2cError::InvalidReceiveString),
-            },
-            _ => Err(I2cError::IOError),
-        }
+    /// Fill `received` from consecutive registers, starting at `register`
+    pub fn read_register(&mut self, register: u8, received: &mut [u8]) -> Result<(), I2cError> {
+        self.transaction(&[register], received)
+    }
+
+    /// Read `count` consecutive registers in one transfer, starting at `register`
+    pub fn read_registers(&mut self, register: u8, count: usize) -> Result<Vec<u8>, I2cError> {
+        let mut received = vec![0; count];
+        self.read_register(register, &mut received)?;
+        Ok(received)
+    }
+
+    /// Check that consecutive registers, starting at `register`, hold the `expected` bytes, such
+    /// as a device ID. Returns false if they differ, and an error if they can't be read.
+    pub fn verify_sensor(&mut self, register: u8, expected: &[u8]) -> Result<bool, I2cError> {
+        Ok(self.read_registers(register, expected.len())? == expected)
+    }
+
+    /// Send a string and read `count` bytes back as a string
+    pub fn string_transaction(&mut self, sent: &str, count: usize) -> Result<String, I2cError> {
+        let mut received = vec![0; count];
+        self.transaction(sent.as_bytes(), &mut received)?;
+        String::from_utf8(received).map_err(|_| I2cError::InvalidReceiveString)
+    }
+
+    /// Read `count` bytes from the device as a string
+    pub fn read_string(&self, count: usize) -> Result<String, I2cError> {
+        String::from_utf8(self.read_vec(count)?).map_err(|_| I2cError::InvalidReceiveString)
     }
 
     /// Write str to i2c device
     pub fn write_string(&mut self, sent_string: &str) -> Result<(), I2cError> {
-        let status = unsafe {
-            HAL_WriteI2C(self.port as i32, self.device_address, sent_string.as_bytes().as_ptr() as *mut u8, sent_string.len() as i32)
-        };
-        match status {
-            -1 => Err(I2cError::TransferAbort),
-            x if x >= 0 => Ok(()),
-            _ => Err(I2cError::IOError),
-        }
+        self.write(sent_string.as_bytes())
     }
 }
 
 impl Drop for I2cInterface {
     /// Close I2C connection cleanly
     fn drop(&mut self) {
-        unsafe { HAL_CloseI2C(self.port as i32); }
+        unsafe { Hal::HAL_CloseI2C(self.port as i32); }
     }
 }
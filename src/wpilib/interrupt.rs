@@ -1,4 +1,5 @@
 use wpilib::DigitalInput;
+use wpilib::digital_source::DigitalSource;
 use wpilib::wpilib_hal::*;
 use wpilib::hal_call::*;
 use std::sync::Arc;
@@ -7,6 +8,7 @@ use std::mem;
 
 
 /// The result of an interrupt wait
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum WaitResult {
     /// Waiting on the interrupt timed out.
     Timeout = 0x0,
@@ -18,24 +20,84 @@ pub enum WaitResult {
     Both = 0x101,
 }
 
+impl WaitResult {
+    /// Decode a mask from the HAL, which reports a rising edge in bit `index` and a falling edge
+    /// in bit `8 + index` for the interrupt with that index.
+    fn from_mask(mask: i64) -> WaitResult {
+        match (mask & 0xff != 0, mask & 0xff00 != 0) {
+            (false, false) => WaitResult::Timeout,
+            (true, false) => WaitResult::RisingEdge,
+            (false, true) => WaitResult::FallingEdge,
+            (true, true) => WaitResult::Both,
+        }
+    }
+}
+
+type Handler = Box<dyn FnMut(WaitResult) + Send>;
+
+/// Called by the HAL on its handler thread, with a pointer to the boxed handler as `param`.
+unsafe extern "C" fn call_handler(mask: u32, param: *mut raw::c_void) {
+    let handler = &mut *(param as *mut Handler);
+    handler(WaitResult::from_mask(mask as i64));
+}
+
 /// The interface to a hardware interrupt. An interrupt allows the user to wait on a specific
 /// hardware event - for example, for a DIO pin to go high - before executing any instructions. In
-/// addition, it supports using C-style callback functions for asynchronous interrupts.
+/// addition, it supports running a closure on a separate thread whenever the interrupt triggers.
 ///
-/// An interrupt can be created based on an existing digital input or by creating a new one.
+/// An interrupt can be created based on an existing digital source, such as a digital input or an
+/// analog trigger output, or by creating a new digital input. By default, it triggers on rising
+/// edges only.
+///
+/// # Usage
+/// ```
+/// # use wpilib::*;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::sync::Arc;
+///
+/// let presses = Arc::new(AtomicUsize::new(0));
+/// let mut button = Interrupt::from_channel(0).unwrap();
+/// button.set_up_source_edge(false, true).unwrap();
+/// let counter = presses.clone();
+/// button.register_handler(move |_| {
+///     counter.fetch_add(1, Ordering::SeqCst);
+/// }).unwrap();
+/// button.enable().unwrap();
+///
+/// # sim::dio(0).set_value(false);
+/// # assert_eq!(presses.load(Ordering::SeqCst), 1);
+/// // The FPGA timestamps each edge, in seconds.
+/// let pressed_at = button.read_falling_timestamp().unwrap();
+/// # assert!(pressed_at > 0.0);
+/// ```
 pub struct Interrupt {
-    input: Arc<DigitalInput>,
+    source: Box<dyn DigitalSource + Send>,
     interrupt: HAL_InterruptHandle,
+    rising: bool,
+    falling: bool,
+    // Boxed twice so that the HAL can be given a thin pointer to it.
+    handler: Option<Box<Handler>>,
 }
 
 impl Interrupt {
     /// Create an interrupt from an existing digital input, returning an error if initialization
     /// fails.
     pub fn from_input(input: Arc<DigitalInput>) -> HalResult<Interrupt> {
+        Interrupt::from_source(input)
+    }
+
+    /// Create an interrupt from an existing digital source, returning an error if initialization
+    /// fails.
+    pub fn from_source<S>(source: S) -> HalResult<Interrupt>
+        where S: DigitalSource + Send + 'static
+    {
         let handle = Interrupt::allocate_interrupt(true)?;
         Ok(Interrupt {
-            input: input,
+            source: Box::new(source),
             interrupt: handle,
+            rising: true,
+            falling: false,
+            handler: None,
         })
     }
 
@@ -50,47 +112,83 @@ impl Interrupt {
         hal_call!(HAL_InitializeInterrupts(watcher as i32))
     }
 
-    /// Set when to trigger this interrupt.
-    fn setup_source_edge(&mut self, rising: bool, falling: bool) -> HalResult<()> {
-        hal_call!(HAL_SetInterruptUpSourceEdge(self.interrupt, rising as i32, falling as i32))
+    /// Set which edges of the source trigger this interrupt.
+    pub fn set_up_source_edge(&mut self, rising: bool, falling: bool) -> HalResult<()> {
+        hal_call!(HAL_SetInterruptUpSourceEdge(self.interrupt, rising as i32, falling as i32))?;
+        self.rising = rising;
+        self.falling = falling;
+        Ok(())
+    }
+
+    /// Route the source to the interrupt, and apply the configured edges.
+    fn request_interrupts(&mut self) -> HalResult<()> {
+        hal_call!(HAL_RequestInterrupts(self.interrupt,
+                                        self.source.get_handle_for_routing(),
+                                        self.source.get_analog_trigger_type_for_routing()))?;
+        let (rising, falling) = (self.rising, self.falling);
+        self.set_up_source_edge(rising, falling)
     }
 
     /// Register a synchronous interrupt. This allows the user to wait on this interrupt.
     pub fn register_sync_interrupt(&mut self) -> HalResult<()> {
-        hal_call!(HAL_RequestInterrupts(self.interrupt,
-                                        self.input.get_handle(),
-                                        mem::transmute(0)))?;
-        self.setup_source_edge(true, false)
+        self.request_interrupts()
     }
 
-    /// Register an asynchronous callback to be called when this interrupt is triggered.
-    pub fn register_async_interrupt(&mut self,
-                                    handler: unsafe extern "C" fn(u32, *mut raw::c_void),
-                                    param: *mut raw::c_void)
-                                    -> HalResult<()> {
-        hal_call!(HAL_RequestInterrupts(self.interrupt,
-                                        self.input.get_handle(),
-                                        mem::transmute(0)))?;
-        self.setup_source_edge(true, false)?;
-        hal_call!(HAL_AttachInterruptHandler(self.interrupt, Some(handler), param))
+    /// Register a closure to be called on a separate thread each time this interrupt triggers,
+    /// replacing any previous handler. The handler does not run until `enable` is called.
+    ///
+    /// The handler must not drop this interrupt, since dropping it waits for the handler to
+    /// finish.
+    pub fn register_handler<F>(&mut self, handler: F) -> HalResult<()>
+        where F: FnMut(WaitResult) + Send + 'static
+    {
+        // Handlers need an interrupt that is not a watcher, so replace the current one. Cleaning
+        // the old one also guarantees that any previous handler has stopped before it is dropped.
+        // The new one is allocated first, so that on failure this still owns a valid interrupt.
+        let interrupt = Interrupt::allocate_interrupt(false)?;
+        let old = mem::replace(&mut self.interrupt, interrupt);
+        hal_call!(HAL_CleanInterrupts(old))?;
+        self.request_interrupts()?;
+
+        let mut handler: Box<Handler> = Box::new(Box::new(handler));
+        let param = &mut *handler as *mut Handler as *mut raw::c_void;
+        hal_call!(HAL_AttachInterruptHandlerThreaded(self.interrupt, Some(call_handler), param))?;
+        self.handler = Some(handler);
+        Ok(())
     }
 
-    /// Wait until this interrupt is triggered, blocking the current thread. Calling this while
-    /// trying to read the input from another thread will break.
-    pub unsafe fn wait(&mut self, timeout: f64, ignore_previous: bool) -> HalResult<WaitResult> {
-        #[allow(unused_unsafe)]
-        match hal_call!(HAL_WaitForInterrupt(self.interrupt, timeout, ignore_previous as i32))? {
-            0x0 => Ok(WaitResult::Timeout),
-            0x1 => Ok(WaitResult::RisingEdge),
-            0x100 => Ok(WaitResult::FallingEdge),
-            0x101 => Ok(WaitResult::Both),
-            _ => Err(HalError(0)),
-        }
+    /// Start calling the registered handler when the interrupt triggers.
+    pub fn enable(&mut self) -> HalResult<()> {
+        hal_call!(HAL_EnableInterrupts(self.interrupt))
+    }
+
+    /// Stop calling the registered handler. Edges while disabled are ignored.
+    pub fn disable(&mut self) -> HalResult<()> {
+        hal_call!(HAL_DisableInterrupts(self.interrupt))
+    }
+
+    /// Wait up to `timeout` seconds for this interrupt to trigger, blocking the current thread.
+    /// If `ignore_previous` is false, an edge since the last wait returns immediately.
+    pub fn wait(&mut self, timeout: f64, ignore_previous: bool) -> HalResult<WaitResult> {
+        Ok(WaitResult::from_mask(hal_call!(HAL_WaitForInterrupt(self.interrupt,
+                                                                timeout,
+                                                                ignore_previous as i32))?))
+    }
+
+    /// Get the time of the last rising edge, in seconds since the FPGA started.
+    pub fn read_rising_timestamp(&self) -> HalResult<f64> {
+        hal_call!(HAL_ReadInterruptRisingTimestamp(self.interrupt))
+    }
+
+    /// Get the time of the last falling edge, in seconds since the FPGA started.
+    pub fn read_falling_timestamp(&self) -> HalResult<f64> {
+        hal_call!(HAL_ReadInterruptFallingTimestamp(self.interrupt))
     }
 }
 
 impl Drop for Interrupt {
     fn drop(&mut self) {
+        // The handler is dropped after this, once the HAL can no longer call it.
         let _ = hal_call!(HAL_CleanInterrupts(self.interrupt));
     }
 }
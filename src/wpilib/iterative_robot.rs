@@ -0,0 +1,181 @@
+use wpilib::hal_call::*;
+use wpilib::driverstation::{DriverStation, RobotState};
+use wpilib::robot::initialize_hal;
+
+use std::{thread, time};
+
+/// What paces the periodic functions of an `IterativeRobot`.
+#[derive(Debug, Copy, Clone)]
+pub enum LoopTiming {
+    /// Run once for every new driver station packet, about every 20ms.
+    DriverStation,
+    /// Run at a fixed period, whether or not new driver station data has arrived.
+    Period(time::Duration),
+}
+
+/// A robot that is driven by a loop which switches between modes for you. Each mode has an init
+/// function, called when the robot enters that mode, and a periodic function, called on every
+/// iteration of the loop while the robot is in that mode. `robot_periodic` is called on every
+/// iteration regardless of mode. Every function does nothing unless it is overridden.
+///
+/// # Usage
+///
+/// ```no_run
+/// use wpilib::*;
+/// use std::time::Duration;
+///
+/// struct TestRobot {
+///     drive: PwmSpeedController,
+///     stick: Joystick,
+/// }
+///
+/// impl IterativeRobot for TestRobot {
+///     fn new() -> TestRobot {
+///         TestRobot {
+///             drive: PwmSpeedController::victor_sp(0, false).unwrap(),
+///             stick: Joystick::new(0),
+///         }
+///     }
+///
+///     // Run every 10ms instead of waiting for driver station packets.
+///     fn loop_timing() -> LoopTiming {
+///         LoopTiming::Period(Duration::from_millis(10))
+///     }
+///
+///     fn teleop_periodic(&mut self) {
+///         self.drive.set(self.stick.get_raw_axis(1).unwrap_or(0.0) as f64);
+///     }
+///
+///     fn disabled_init(&mut self) {
+///         self.drive.disable();
+///     }
+/// }
+///
+/// fn main() {
+///     TestRobot::main();
+/// }
+/// ```
+pub trait IterativeRobot: Sized {
+    /// Create an instance of the robot class.
+    fn new() -> Self;
+
+    /// How the loop is paced. Defaults to waiting for driver station packets.
+    fn loop_timing() -> LoopTiming {
+        LoopTiming::DriverStation
+    }
+
+    /// Called once, before the loop starts.
+    fn robot_init(&mut self) {}
+
+    /// Called on every iteration of the loop, after the periodic function for the current mode.
+    fn robot_periodic(&mut self) {}
+
+    /// Called when the robot is disabled.
+    fn disabled_init(&mut self) {}
+
+    /// Called periodically while the robot is disabled.
+    fn disabled_periodic(&mut self) {}
+
+    /// Called when the robot enters autonomous mode.
+    fn autonomous_init(&mut self) {}
+
+    /// Called periodically during autonomous mode.
+    fn autonomous_periodic(&mut self) {}
+
+    /// Called when the robot enters teleop mode.
+    fn teleop_init(&mut self) {}
+
+    /// Called periodically during teleop mode.
+    fn teleop_periodic(&mut self) {}
+
+    /// Called when the robot enters test mode.
+    fn test_init(&mut self) {}
+
+    /// Called periodically during test mode.
+    fn test_periodic(&mut self) {}
+
+    /// Run the main loop. This never returns.
+    fn start_competition(mut self) {
+        let ds = DriverStation::instance();
+
+        self.robot_init();
+        unsafe {
+            Hal::HAL_ObserveUserProgramStarting();
+        }
+
+        let timing = Self::loop_timing();
+        let mut next_loop = time::Instant::now();
+        let mut last_state = None;
+        loop {
+            match timing {
+                LoopTiming::DriverStation => ds.wait_for_data(),
+                LoopTiming::Period(period) => {
+                    next_loop += period;
+                    let now = time::Instant::now();
+                    if next_loop > now {
+                        thread::sleep(next_loop - now);
+                    } else {
+                        // Skip the iterations we have fallen behind on instead of running them
+                        // back to back.
+                        next_loop = now;
+                    }
+                }
+            }
+
+            let state = match ds.get_state() {
+                RobotState::EStop => RobotState::Disabled,
+                state => state,
+            };
+            let entered = last_state != Some(state);
+            last_state = Some(state);
+
+            match state {
+                RobotState::Autonomous => {
+                    if entered {
+                        self.autonomous_init();
+                    }
+                    unsafe {
+                        Hal::HAL_ObserveUserProgramAutonomous();
+                    }
+                    self.autonomous_periodic();
+                }
+                RobotState::Teleop => {
+                    if entered {
+                        self.teleop_init();
+                    }
+                    unsafe {
+                        Hal::HAL_ObserveUserProgramTeleop();
+                    }
+                    self.teleop_periodic();
+                }
+                RobotState::Test => {
+                    if entered {
+                        self.test_init();
+                    }
+                    unsafe {
+                        Hal::HAL_ObserveUserProgramTest();
+                    }
+                    self.test_periodic();
+                }
+                _ => {
+                    if entered {
+                        self.disabled_init();
+                    }
+                    unsafe {
+                        Hal::HAL_ObserveUserProgramDisabled();
+                    }
+                    self.disabled_periodic();
+                }
+            }
+
+            self.robot_periodic();
+        }
+    }
+
+    /// Run the robot statically.
+    fn main() {
+        initialize_hal();
+        let robot = Self::new();
+        robot.start_competition();
+    }
+}
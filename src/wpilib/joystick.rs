@@ -1,4 +1,4 @@
-use wpilib::wpilib_hal::*;
+use wpilib::hal_call::*;
 use wpilib::driverstation::*;
 
 const RUMBLE_BASE: i32 = 65535;
@@ -87,21 +87,21 @@ impl JoystickBase for Joystick {
     fn set_output(&mut self, output_number: i32, value: bool) {
         let o = output_number - 1i32;
         self.outputs = (self.outputs & (!(1i32 << o)) as i64) | ((value as i64) << o);
-        unsafe { HAL_SetJoystickOutputs(self.port as i32, self.outputs, self.left_rumble, self.right_rumble); }
+        unsafe { Hal::HAL_SetJoystickOutputs(self.port as i32, self.outputs, self.left_rumble, self.right_rumble); }
     }
 
     fn set_outputs(&mut self, value: i64) {
         self.outputs = value;
-        unsafe { HAL_SetJoystickOutputs(self.port as i32, self.outputs, self.left_rumble, self.right_rumble); }
+        unsafe { Hal::HAL_SetJoystickOutputs(self.port as i32, self.outputs, self.left_rumble, self.right_rumble); }
     }
 
     fn set_rumble(&mut self, side: JoystickSide, mut value: f32) {
-        value = if value > 1f32 { 1f32 } else if value < 0f32 { 0f32 } else { value };
+        value = value.clamp(0f32, 1f32);
         match side {
             JoystickSide::LeftHand => self.left_rumble = (value * RUMBLE_BASE as f32) as i32,
             JoystickSide::RightHand => self.right_rumble = (value * RUMBLE_BASE as f32) as i32,
         }
-        unsafe { HAL_SetJoystickOutputs(self.port as i32, self.outputs, self.left_rumble, self.right_rumble) };
+        unsafe { Hal::HAL_SetJoystickOutputs(self.port as i32, self.outputs, self.left_rumble, self.right_rumble) };
     }
 }
 
@@ -120,9 +120,9 @@ impl XBoxController {
         XBoxController { port: p, ds: DriverStation::instance(), outputs: 0i64, left_rumble: 0i32, right_rumble: 0i32 }
     }
 
-    /// simply wrappers for get_raw_[axis/button] with hardcoded values for the standard xbox 360 controller
+    // simply wrappers for get_raw_[axis/button] with hardcoded values for the standard xbox 360 controller
 
-    /// buttons
+    // buttons
 
     /// helper function for getting a button
     pub fn get_a_button(&mut self) -> Result<bool, JoystickError> {
@@ -170,7 +170,7 @@ impl XBoxController {
         }
     }
 
-    /// axes
+    // axes
 
     /// helper function for getting an x axis
     pub fn get_x(&mut self, side: JoystickSide) -> Result<f32, JoystickError> {
@@ -200,15 +200,15 @@ impl XBoxController {
     /// print a warning if the value returned by get_pov is not a valid pov value for the xbox 360 controller
     pub fn get_dpad(&mut self) -> Result<DPad, JoystickError> {
         match self.get_pov(1) {
-            Ok(val) if val == 0 => Ok(DPad::Up),
-            Ok(val) if val == 1 => Ok(DPad::UpRight),
-            Ok(val) if val == 2 => Ok(DPad::Right),
-            Ok(val) if val == 3 => Ok(DPad::DownRight),
-            Ok(val) if val == 4 => Ok(DPad::Down),
-            Ok(val) if val == 5 => Ok(DPad::DownLeft),
-            Ok(val) if val == 6 => Ok(DPad::Left),
-            Ok(val) if val == 7 => Ok(DPad::UpLeft),
-            Ok(val) if val == -01 => Ok(DPad::Neutral),
+            Ok(0) => Ok(DPad::Up),
+            Ok(1) => Ok(DPad::UpRight),
+            Ok(2) => Ok(DPad::Right),
+            Ok(3) => Ok(DPad::DownRight),
+            Ok(4) => Ok(DPad::Down),
+            Ok(5) => Ok(DPad::DownLeft),
+            Ok(6) => Ok(DPad::Left),
+            Ok(7) => Ok(DPad::UpLeft),
+            Ok(-1) => Ok(DPad::Neutral),
             Ok(_) => {
                 let mut message: String = String::from("Received invalid POV value on XBox controller at port ");
                 message += self.port.to_string().as_str();
@@ -236,20 +236,20 @@ impl JoystickBase for XBoxController {
     fn set_output(&mut self, output_number: i32, value: bool) {
         let o = output_number - 1i32;
         self.outputs = (self.outputs & (!(1i32 << o)) as i64) | ((value as i64) << o);
-        unsafe { HAL_SetJoystickOutputs(self.port as i32, self.outputs, self.left_rumble, self.right_rumble); }
+        unsafe { Hal::HAL_SetJoystickOutputs(self.port as i32, self.outputs, self.left_rumble, self.right_rumble); }
     }
 
     fn set_outputs(&mut self, value: i64) {
         self.outputs = value;
-        unsafe { HAL_SetJoystickOutputs(self.port as i32, self.outputs, self.left_rumble, self.right_rumble); }
+        unsafe { Hal::HAL_SetJoystickOutputs(self.port as i32, self.outputs, self.left_rumble, self.right_rumble); }
     }
 
     fn set_rumble(&mut self, side: JoystickSide, mut value: f32) {
-        value = if value > 1f32 { 1f32 } else if value < 0f32 { 0f32 } else { value };
+        value = value.clamp(0f32, 1f32);
         match side {
             JoystickSide::LeftHand => self.left_rumble = (value * RUMBLE_BASE as f32) as i32,
             JoystickSide::RightHand => self.right_rumble = (value * RUMBLE_BASE as f32) as i32,
         }
-        unsafe { HAL_SetJoystickOutputs(self.port as i32, self.outputs, self.left_rumble, self.right_rumble) };
+        unsafe { Hal::HAL_SetJoystickOutputs(self.port as i32, self.outputs, self.left_rumble, self.right_rumble) };
     }
 }
@@ -2,11 +2,24 @@
 mod robot;
 pub use self::robot::*;
 
+mod iterative_robot;
+pub use self::iterative_robot::*;
+
+/// Command-based programming
+pub mod command;
+
 #[macro_use]
 mod hal_call;
 
 mod wpilib_hal;
 
+mod hal_backend;
+
+mod sync;
+
+#[cfg(not(feature = "athena"))]
+mod hal_sim;
+
 mod driverstation;
 pub use self::driverstation::DriverStation;
 
@@ -25,26 +38,93 @@ pub mod sensor;
 mod digital_input;
 pub use self::digital_input::DigitalInput;
 
+mod digital_source;
+pub use self::digital_source::DigitalSource;
+
 mod digital_output;
 pub use self::digital_output::DigitalOutput;
 
+mod counter;
+pub use self::counter::{Counter, CounterMode};
+
 mod encoder;
-pub use self::encoder::Encoder;
+pub use self::encoder::{Encoder, EncodingType, IndexingType};
+
+mod duty_cycle_encoder;
+pub use self::duty_cycle_encoder::DutyCycleEncoder;
+
+mod ultrasonic;
+pub use self::ultrasonic::Ultrasonic;
+
+mod digital_glitch_filter;
+pub use self::digital_glitch_filter::DigitalGlitchFilter;
 
 mod pdp;
 pub use self::pdp::PowerDistributionPanel;
 
+mod relay;
+pub use self::relay::{Relay, RelayDirection, RelayValue};
+
+mod compressor;
+pub use self::compressor::{Compressor, CompressorFaults};
+
+mod solenoid;
+pub use self::solenoid::{DoubleSolenoid, DoubleSolenoidValue, Solenoid};
+
 /// Functions for information about the robot's state that are not contained anywhere else.
 pub mod robot_state;
 
 mod analog_input;
 pub use self::analog_input::AnalogInput;
 
+mod accelerometer;
+pub use self::accelerometer::{Acceleration, Accelerometer, AccelerometerRange};
+
+mod built_in_accelerometer;
+pub use self::built_in_accelerometer::BuiltInAccelerometer;
+
+mod adxl345;
+pub use self::adxl345::{ADXL345I2c, ADXL345Range, ADXL345Spi};
+
+mod adxl362;
+pub use self::adxl362::ADXL362;
+
+mod gyro;
+pub use self::gyro::Gyro;
+
+mod analog_gyro;
+pub use self::analog_gyro::AnalogGyro;
+
+mod adxrs450_gyro;
+pub use self::adxrs450_gyro::ADXRS450Gyro;
+
+mod navx;
+pub use self::navx::NavX;
+
+mod analog_potentiometer;
+pub use self::analog_potentiometer::AnalogPotentiometer;
+
+mod analog_ultrasonic;
+pub use self::analog_ultrasonic::AnalogUltrasonic;
+
+mod analog_output;
+pub use self::analog_output::AnalogOutput;
+
+mod analog_trigger;
+pub use self::analog_trigger::{AnalogTrigger, AnalogTriggerOutput, AnalogTriggerType};
+
 mod spi;
-pub use self::spi::SpiInterface;
+pub use self::spi::{BitOrder, SpiAccumulatorConfig, SpiError, SpiInterface, SpiMode, SpiPort};
+
+mod serial_port;
+pub use self::serial_port::{FlowControl, Parity, SerialPort, SerialPortBuilder, SerialPortId,
+                            StopBits, WriteBufferMode};
 
 mod interrupt;
-pub use self::interrupt::Interrupt;
+pub use self::interrupt::{Interrupt, WaitResult};
+
+mod notifier;
+pub use self::notifier::Notifier;
 
 mod pwm;
 pub use self::pwm::Pwm;
@@ -61,6 +141,12 @@ pub use self::pwm_speed_controller::*;
 mod i2c;
 pub use self::i2c::*;
 
+mod bus;
+pub use self::bus::DeviceError;
+
 /// defines all joystick related traits and structs
 pub mod joystick;
 pub use self::joystick::*;
+
+#[cfg(not(feature = "athena"))]
+pub mod sim;
@@ -0,0 +1,879 @@
+use wpilib::accelerometer::Acceleration;
+use wpilib::bus::DeviceError;
+use wpilib::gyro::Gyro;
+use wpilib::i2c::{I2cInterface, I2cPort};
+use wpilib::serial_port::{SerialPort, SerialPortId};
+use wpilib::spi::{SpiInterface, SpiMode, SpiPort};
+use wpilib::sync::lock;
+use std::io::{Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// The navX register map.
+const REG_UPDATE_RATE_HZ: u8 = 0x04;
+/// The first register read on each update.
+const REG_OP_STATUS: u8 = 0x08;
+const REG_CAL_STATUS: u8 = 0x09;
+const REG_SENSOR_STATUS_L: u8 = 0x10;
+const REG_YAW: u8 = 0x16;
+const REG_ROLL: u8 = 0x18;
+const REG_PITCH: u8 = 0x1a;
+const REG_HEADING: u8 = 0x1c;
+const REG_FUSED_HEADING: u8 = 0x1e;
+const REG_ALTITUDE: u8 = 0x20;
+const REG_LINEAR_ACC_X: u8 = 0x24;
+const REG_LINEAR_ACC_Y: u8 = 0x26;
+const REG_LINEAR_ACC_Z: u8 = 0x28;
+const REG_MPU_TEMP_C: u8 = 0x32;
+const REG_HW_INTEGRATION_CTL: u8 = 0x56;
+const REG_VEL_X: u8 = 0x58;
+const REG_VEL_Y: u8 = 0x5c;
+const REG_VEL_Z: u8 = 0x60;
+const REG_DISP_X: u8 = 0x64;
+const REG_DISP_Y: u8 = 0x68;
+const REG_DISP_Z: u8 = 0x6c;
+/// The register after the last one read on each update.
+const REG_END: u8 = 0x70;
+
+/// Set in the address of a register write.
+const REG_WRITE: u8 = 0x80;
+
+const OP_STATUS_NORMAL: u8 = 0x04;
+const CAL_STATUS_IMU_CAL_STATE_MASK: u8 = 0x03;
+const CAL_STATUS_IMU_CAL_COMPLETE: u8 = 0x02;
+const CAL_STATUS_MAG_CAL_COMPLETE: u8 = 0x04;
+const SENSOR_STATUS_MOVING: u8 = 0x01;
+const SENSOR_STATUS_MAG_DISTURBANCE: u8 = 0x04;
+
+/// Reset the velocity and displacement on every axis.
+const INTEGRATION_CTL_RESET_DISPLACEMENT: u8 = 0x3f;
+
+const SPI_CLOCK_RATE: i32 = 500_000;
+const I2C_ADDRESS: i32 = 0x32;
+/// The longest read the I2C bus allows.
+const I2C_MAX_READ: usize = 127;
+const SERIAL_BAUD_RATE: i32 = 57_600;
+
+// The serial protocol. Every message starts with `!`, and ends with a checksum, as two hex
+// digits, and CR LF.
+const PACKET_START: u8 = b'!';
+/// Follows the start of a binary message, which has its length next.
+const BINARY_PACKET_INDICATOR: u8 = b'#';
+const MSGID_STREAM_CMD: u8 = b'S';
+const MSGID_AHRSPOS_UPDATE: u8 = b'p';
+const MSGID_INTEGRATION_CONTROL_CMD: u8 = b'I';
+const AHRSPOS_UPDATE_LENGTH: usize = 66;
+
+/// How long to wait for data before the navX is considered disconnected.
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(1);
+/// How long `calibrate` waits for the navX to finish calibrating itself.
+const CALIBRATION_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// One update from the navX, in its own units.
+#[derive(Debug, Default, Copy, Clone)]
+struct Sample {
+    op_status: u8,
+    cal_status: u8,
+    sensor_status: u8,
+    yaw: f64,
+    pitch: f64,
+    roll: f64,
+    compass_heading: f64,
+    fused_heading: f64,
+    altitude: f64,
+    linear_acceleration: Acceleration,
+    velocity: [f64; 3],
+    displacement: [f64; 3],
+    temperature: f64,
+}
+
+fn decode_u16(data: &[u8]) -> u16 {
+    u16::from_le_bytes([data[0], data[1]])
+}
+
+fn decode_i16(data: &[u8]) -> i16 {
+    i16::from_le_bytes([data[0], data[1]])
+}
+
+fn decode_signed_hundredths(data: &[u8]) -> f64 {
+    decode_i16(data) as f64 / 100.0
+}
+
+fn decode_unsigned_hundredths(data: &[u8]) -> f64 {
+    decode_u16(data) as f64 / 100.0
+}
+
+fn decode_signed_thousandths(data: &[u8]) -> f64 {
+    decode_i16(data) as f64 / 1000.0
+}
+
+/// Decode a signed 16.16 fixed point number.
+fn decode_1616(data: &[u8]) -> f64 {
+    i32::from_le_bytes([data[0], data[1], data[2], data[3]]) as f64 / 65536.0
+}
+
+impl Sample {
+    /// Decode the registers from `REG_OP_STATUS` to `REG_END`.
+    fn from_registers(data: &[u8]) -> Sample {
+        let at = |register: u8| &data[(register - REG_OP_STATUS) as usize..];
+        Sample {
+            op_status: at(REG_OP_STATUS)[0],
+            cal_status: at(REG_CAL_STATUS)[0],
+            sensor_status: at(REG_SENSOR_STATUS_L)[0],
+            yaw: decode_signed_hundredths(at(REG_YAW)),
+            pitch: decode_signed_hundredths(at(REG_PITCH)),
+            roll: decode_signed_hundredths(at(REG_ROLL)),
+            compass_heading: decode_unsigned_hundredths(at(REG_HEADING)),
+            fused_heading: decode_unsigned_hundredths(at(REG_FUSED_HEADING)),
+            altitude: decode_1616(at(REG_ALTITUDE)),
+            linear_acceleration: Acceleration {
+                x: decode_signed_thousandths(at(REG_LINEAR_ACC_X)),
+                y: decode_signed_thousandths(at(REG_LINEAR_ACC_Y)),
+                z: decode_signed_thousandths(at(REG_LINEAR_ACC_Z)),
+            },
+            velocity: [decode_1616(at(REG_VEL_X)),
+                       decode_1616(at(REG_VEL_Y)),
+                       decode_1616(at(REG_VEL_Z))],
+            displacement: [decode_1616(at(REG_DISP_X)),
+                           decode_1616(at(REG_DISP_Y)),
+                           decode_1616(at(REG_DISP_Z))],
+            temperature: decode_signed_hundredths(at(REG_MPU_TEMP_C)),
+        }
+    }
+
+    /// Decode a complete AHRS position update message.
+    fn from_ahrs_pos_update(packet: &[u8]) -> Sample {
+        Sample {
+            op_status: packet[58],
+            sensor_status: packet[59],
+            cal_status: packet[60],
+            yaw: decode_signed_hundredths(&packet[4..]),
+            roll: decode_signed_hundredths(&packet[6..]),
+            pitch: decode_signed_hundredths(&packet[8..]),
+            compass_heading: decode_unsigned_hundredths(&packet[10..]),
+            altitude: decode_1616(&packet[12..]),
+            fused_heading: decode_unsigned_hundredths(&packet[16..]),
+            linear_acceleration: Acceleration {
+                x: decode_signed_thousandths(&packet[18..]),
+                y: decode_signed_thousandths(&packet[20..]),
+                z: decode_signed_thousandths(&packet[22..]),
+            },
+            velocity: [decode_1616(&packet[24..]),
+                       decode_1616(&packet[28..]),
+                       decode_1616(&packet[32..])],
+            displacement: [decode_1616(&packet[36..]),
+                           decode_1616(&packet[40..]),
+                           decode_1616(&packet[44..])],
+            temperature: decode_signed_hundredths(&packet[56..]),
+        }
+    }
+}
+
+/// The CRC the navX appends to SPI transfers.
+fn crc7(data: &[u8]) -> u8 {
+    let mut crc = 0;
+    for byte in data {
+        crc ^= byte;
+        for _ in 0..8 {
+            if crc & 1 != 0 {
+                crc ^= 0x91;
+            }
+            crc >>= 1;
+        }
+    }
+    crc
+}
+
+/// Write a byte as two uppercase hex digits.
+fn hex(byte: u8) -> [u8; 2] {
+    const DIGITS: &[u8] = b"0123456789ABCDEF";
+    [DIGITS[(byte >> 4) as usize], DIGITS[(byte & 0xf) as usize]]
+}
+
+/// Finish a serial message by appending the checksum of its contents and the terminator.
+fn terminate(mut message: Vec<u8>) -> Vec<u8> {
+    let checksum = message.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+    message.extend_from_slice(&hex(checksum));
+    message.extend_from_slice(b"\r\n");
+    message
+}
+
+/// Does a complete serial message have the right checksum?
+fn checksum_valid(message: &[u8]) -> bool {
+    let content = message.len() - 4;
+    let checksum = message[..content].iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+    message[content..content + 2] == hex(checksum)
+}
+
+/// The bus the navX is connected by.
+enum Transport {
+    Spi(SpiInterface),
+    I2c(I2cInterface),
+    Serial {
+        port: SerialPort,
+        /// Bytes received that have not been parsed yet.
+        buffer: Vec<u8>,
+    },
+}
+
+impl Transport {
+    fn write_register(&mut self, register: u8, value: u8) -> Result<(), DeviceError> {
+        match *self {
+            Transport::Spi(ref mut spi) => {
+                let mut command = [register | REG_WRITE, value, 0];
+                command[2] = crc7(&command[..2]);
+                spi.write(&command)?;
+            }
+            Transport::I2c(ref mut i2c) => {
+                i2c.write(&[register | REG_WRITE, value])?;
+            }
+            Transport::Serial { .. } => unreachable!("the serial protocol has no registers"),
+        }
+        Ok(())
+    }
+
+    fn read_registers(&mut self, first: u8, data: &mut [u8]) -> Result<(), DeviceError> {
+        match *self {
+            Transport::Spi(ref mut spi) => {
+                let mut command = [first, data.len() as u8, 0];
+                command[2] = crc7(&command[..2]);
+                spi.write(&command)?;
+                // Give the navX time to fetch the registers.
+                thread::sleep(Duration::from_micros(200));
+
+                let mut received = vec![0; data.len() + 1];
+                spi.initiate_read(&mut received)?;
+                if received[data.len()] != crc7(&received[..data.len()]) {
+                    return Err(DeviceError::Checksum);
+                }
+                data.copy_from_slice(&received[..data.len()]);
+            }
+            Transport::I2c(ref mut i2c) => {
+                for (index, chunk) in data.chunks_mut(I2C_MAX_READ).enumerate() {
+                    let register = first + (index * I2C_MAX_READ) as u8;
+                    i2c.write(&[register, chunk.len() as u8])?;
+                    i2c.read(chunk)?;
+                }
+            }
+            Transport::Serial { .. } => unreachable!("the serial protocol has no registers"),
+        }
+        Ok(())
+    }
+
+    /// Set the update rate. Over serial, this also asks the navX to start streaming updates.
+    fn configure(&mut self, update_rate_hz: u8) -> Result<(), DeviceError> {
+        match *self {
+            Transport::Serial { ref mut port, .. } => {
+                let mut command = vec![PACKET_START, MSGID_STREAM_CMD, MSGID_AHRSPOS_UPDATE];
+                command.extend_from_slice(&hex(update_rate_hz));
+                Ok(port.write_all(&terminate(command))?)
+            }
+            _ => self.write_register(REG_UPDATE_RATE_HZ, update_rate_hz),
+        }
+    }
+
+    /// Send integration control flags, such as `INTEGRATION_CTL_RESET_DISPLACEMENT`.
+    fn send_integration_control(&mut self, action: u8) -> Result<(), DeviceError> {
+        match *self {
+            Transport::Serial { ref mut port, .. } => {
+                let mut command = vec![PACKET_START,
+                                       BINARY_PACKET_INDICATOR,
+                                       11,
+                                       MSGID_INTEGRATION_CONTROL_CMD,
+                                       action];
+                command.extend_from_slice(&0i32.to_le_bytes());
+                Ok(port.write_all(&terminate(command))?)
+            }
+            _ => self.write_register(REG_HW_INTEGRATION_CTL, action),
+        }
+    }
+
+    /// Get the latest update, if there is a new one.
+    fn poll(&mut self) -> Result<Option<Sample>, DeviceError> {
+        match *self {
+            Transport::Serial { ref mut port, ref mut buffer } => {
+                let available = port.get_bytes_received()?.max(0) as usize;
+                if available > 0 {
+                    let start = buffer.len();
+                    buffer.resize(start + available, 0);
+                    let count = port.read(&mut buffer[start..])?;
+                    buffer.truncate(start + count);
+                }
+                Ok(Transport::parse(buffer))
+            }
+            _ => {
+                let mut data = [0; (REG_END - REG_OP_STATUS) as usize];
+                self.read_registers(REG_OP_STATUS, &mut data)?;
+                Ok(Some(Sample::from_registers(&data)))
+            }
+        }
+    }
+
+    /// Parse every complete message in `buffer`, returning the latest update. Anything that is
+    /// not an update, or is corrupt, is skipped.
+    fn parse(buffer: &mut Vec<u8>) -> Option<Sample> {
+        let mut sample = None;
+        loop {
+            match buffer.iter().position(|&byte| byte == PACKET_START) {
+                Some(start) => {
+                    buffer.drain(..start);
+                }
+                None => {
+                    buffer.clear();
+                    return sample;
+                }
+            }
+            if buffer.len() < 3 {
+                return sample;
+            }
+
+            let length = if buffer[1] == BINARY_PACKET_INDICATOR {
+                buffer[2] as usize + 2
+            } else {
+                // Skip text messages, which end at the terminator.
+                match buffer.iter().position(|&byte| byte == b'\n') {
+                    Some(end) => end + 1,
+                    None => return sample,
+                }
+            };
+            if buffer.len() < length {
+                return sample;
+            }
+
+            let message: Vec<u8> = buffer.drain(..length).collect();
+            if length == AHRSPOS_UPDATE_LENGTH && message[3] == MSGID_AHRSPOS_UPDATE &&
+               message.ends_with(b"\r\n") && checksum_valid(&message) {
+                sample = Some(Sample::from_ahrs_pos_update(&message));
+            }
+        }
+    }
+}
+
+/// Wrap an angle in degrees to between -180 and 180.
+fn wrap_degrees(degrees: f64) -> f64 {
+    (degrees + 180.0).rem_euclid(360.0) - 180.0
+}
+
+/// The state shared with the update thread.
+#[derive(Default)]
+struct Shared {
+    sample: Sample,
+    last_update: Option<Instant>,
+    /// The raw yaw at the last zeroing.
+    yaw_offset: f64,
+    /// The zeroed yaw at the last update.
+    yaw: f64,
+    /// The number of full turns clockwise since the last zeroing.
+    turns: i64,
+    /// The rate of rotation, in degrees per second.
+    rate: f64,
+    /// Integration control flags waiting to be sent.
+    pending_control: u8,
+}
+
+impl Shared {
+    fn update(&mut self, sample: Sample, now: Instant) {
+        let yaw = wrap_degrees(sample.yaw - self.yaw_offset);
+        let delta = yaw - self.yaw;
+        if delta < -180.0 {
+            self.turns += 1;
+        } else if delta > 180.0 {
+            self.turns -= 1;
+        }
+        if let Some(last_update) = self.last_update {
+            let elapsed = now.duration_since(last_update).as_secs_f64();
+            if elapsed > 0.0 {
+                self.rate = wrap_degrees(delta) / elapsed;
+            }
+        }
+
+        self.sample = sample;
+        self.yaw = yaw;
+        self.last_update = Some(now);
+    }
+}
+
+/// A Kauai Labs navX-MXP or navX-Micro, an IMU which fuses its gyros, accelerometers and
+/// magnetometer into an orientation. It can be connected to the MXP port by SPI, I2C or the UART,
+/// or plugged in by USB.
+///
+/// A background thread reads the navX at its update rate, so reading the values here does not
+/// touch the bus. Yaw, pitch and roll are in degrees, with yaw increasing clockwise.
+///
+/// The navX calibrates itself for several seconds after powering on, and must be kept still while
+/// it does.
+///
+/// # Usage
+/// ```no_run
+/// # use wpilib::*;
+/// let mut navx = NavX::from_spi(SpiPort::MXP, 50).unwrap();
+/// navx.calibrate().unwrap();
+/// navx.reset().unwrap();
+///
+/// // Drive straight by steering back to a heading of zero.
+/// let correction = -0.03 * navx.get_angle().unwrap();
+/// ```
+pub struct NavX {
+    shared: Arc<Mutex<Shared>>,
+    stop: Arc<AtomicBool>,
+    join: Option<thread::JoinHandle<()>>,
+}
+
+impl NavX {
+    /// Connect to a navX on an SPI port, such as `SpiPort::MXP`, updating `update_rate_hz` times a
+    /// second, from 4 to 200. Other rates return `DeviceError::OutOfRange`.
+    pub fn from_spi(port: SpiPort, update_rate_hz: u8) -> Result<NavX, DeviceError> {
+        let mut spi = SpiInterface::new(port)?;
+        spi.set_clock_rate(SPI_CLOCK_RATE);
+        spi.set_mode(SpiMode::Mode3);
+        NavX::start(Transport::Spi(spi), update_rate_hz)
+    }
+
+    /// Connect to a navX on an I2C port, such as `I2cPort::MXP`, updating `update_rate_hz` times a
+    /// second, from 4 to 200.
+    pub fn from_i2c(port: I2cPort, update_rate_hz: u8) -> Result<NavX, DeviceError> {
+        NavX::start(Transport::I2c(I2cInterface::new(port, I2C_ADDRESS)?), update_rate_hz)
+    }
+
+    /// Connect to a navX on a serial port, such as `SerialPortId::MXP` or a USB port, updating
+    /// `update_rate_hz` times a second, from 4 to 200.
+    pub fn from_serial(port: SerialPortId, update_rate_hz: u8) -> Result<NavX, DeviceError> {
+        let port = SerialPort::builder(port, SERIAL_BAUD_RATE)
+            .timeout(Duration::from_millis(10))
+            .open()?;
+        NavX::start(Transport::Serial {
+                        port,
+                        buffer: Vec::new(),
+                    },
+                    update_rate_hz)
+    }
+
+    fn start(mut transport: Transport, update_rate_hz: u8) -> Result<NavX, DeviceError> {
+        if !(4..=200).contains(&update_rate_hz) {
+            return Err(DeviceError::OutOfRange);
+        }
+
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let period = Duration::from_secs(1) / update_rate_hz as u32;
+
+        let thread_shared = shared.clone();
+        let thread_stop = stop.clone();
+        let join = thread::spawn(move || {
+            let mut configured_at: Option<Instant> = None;
+            while !thread_stop.load(Ordering::SeqCst) {
+                let now = Instant::now();
+
+                // Configure the navX on startup, and again if it stops responding, in case it
+                // was restarted.
+                let connected = lock(&thread_shared)
+                    .last_update
+                    .is_some_and(|last_update| now - last_update < CONNECTION_TIMEOUT);
+                let reconfigure = configured_at
+                    .is_none_or(|configured_at| now - configured_at > CONNECTION_TIMEOUT);
+                if !connected && reconfigure && transport.configure(update_rate_hz).is_ok() {
+                    configured_at = Some(now);
+                }
+
+                let control = ::std::mem::take(&mut lock(&thread_shared).pending_control);
+                if control != 0 && transport.send_integration_control(control).is_err() {
+                    lock(&thread_shared).pending_control |= control;
+                }
+
+                if let Ok(Some(sample)) = transport.poll() {
+                    lock(&thread_shared).update(sample, Instant::now());
+                }
+
+                thread::sleep(period.saturating_sub(now.elapsed()));
+            }
+        });
+
+        Ok(NavX {
+            shared,
+            stop,
+            join: Some(join),
+        })
+    }
+
+    fn shared(&self) -> MutexGuard<'_, Shared> {
+        lock(&self.shared)
+    }
+
+    fn sample(&self) -> Sample {
+        self.shared().sample
+    }
+
+    /// Has the navX sent an update in the last second?
+    pub fn is_connected(&self) -> bool {
+        self.shared()
+            .last_update
+            .is_some_and(|last_update| last_update.elapsed() < CONNECTION_TIMEOUT)
+    }
+
+    /// Is the navX still calibrating its gyros and accelerometers? Orientation is not accurate
+    /// until it finishes.
+    pub fn is_calibrating(&self) -> bool {
+        let sample = self.sample();
+        sample.op_status != OP_STATUS_NORMAL ||
+        sample.cal_status & CAL_STATUS_IMU_CAL_STATE_MASK != CAL_STATUS_IMU_CAL_COMPLETE
+    }
+
+    /// Has the magnetometer been calibrated? The compass and fused headings are only valid if
+    /// it has.
+    pub fn is_magnetometer_calibrated(&self) -> bool {
+        self.sample().cal_status & CAL_STATUS_MAG_CAL_COMPLETE != 0
+    }
+
+    /// Is a magnetic field other than the Earth's, such as from a motor, disturbing the compass?
+    pub fn is_magnetic_disturbance(&self) -> bool {
+        self.sample().sensor_status & SENSOR_STATUS_MAG_DISTURBANCE != 0
+    }
+
+    /// Is the navX accelerating?
+    pub fn is_moving(&self) -> bool {
+        self.sample().sensor_status & SENSOR_STATUS_MOVING != 0
+    }
+
+    /// Get the yaw since the last zeroing, from -180 to 180 degrees. See `get_angle` for a yaw
+    /// that does not wrap around.
+    pub fn get_yaw(&self) -> f64 {
+        self.shared().yaw
+    }
+
+    /// Get the pitch, from -180 to 180 degrees.
+    pub fn get_pitch(&self) -> f64 {
+        self.sample().pitch
+    }
+
+    /// Get the roll, from -180 to 180 degrees.
+    pub fn get_roll(&self) -> f64 {
+        self.sample().roll
+    }
+
+    /// Get the heading from the magnetometer alone, from 0 to 360 degrees clockwise from north.
+    pub fn get_compass_heading(&self) -> f64 {
+        self.sample().compass_heading
+    }
+
+    /// Get the heading from the gyros and magnetometer together, from 0 to 360 degrees clockwise
+    /// from north. This needs a calibrated magnetometer.
+    pub fn get_fused_heading(&self) -> f64 {
+        self.sample().fused_heading
+    }
+
+    /// Get the acceleration with gravity removed, in g, along the axes of the field rather than
+    /// the robot.
+    pub fn get_linear_acceleration(&self) -> Acceleration {
+        self.sample().linear_acceleration
+    }
+
+    /// Get the estimated velocity along the X axis, in meters per second. Estimates from
+    /// integrating acceleration drift quickly, so only use them over short periods.
+    pub fn get_velocity_x(&self) -> f64 {
+        self.sample().velocity[0]
+    }
+
+    /// Get the estimated velocity along the Y axis, in meters per second.
+    pub fn get_velocity_y(&self) -> f64 {
+        self.sample().velocity[1]
+    }
+
+    /// Get the estimated velocity along the Z axis, in meters per second.
+    pub fn get_velocity_z(&self) -> f64 {
+        self.sample().velocity[2]
+    }
+
+    /// Get the estimated displacement along the X axis since the last reset, in meters.
+    pub fn get_displacement_x(&self) -> f64 {
+        self.sample().displacement[0]
+    }
+
+    /// Get the estimated displacement along the Y axis since the last reset, in meters.
+    pub fn get_displacement_y(&self) -> f64 {
+        self.sample().displacement[1]
+    }
+
+    /// Get the estimated displacement along the Z axis since the last reset, in meters.
+    pub fn get_displacement_z(&self) -> f64 {
+        self.sample().displacement[2]
+    }
+
+    /// Get the altitude from the pressure sensor, in meters, on boards that have one.
+    pub fn get_altitude(&self) -> f64 {
+        self.sample().altitude
+    }
+
+    /// Get the temperature of the IMU chip, in degrees Celsius.
+    pub fn get_temperature(&self) -> f64 {
+        self.sample().temperature
+    }
+
+    /// Make the current yaw zero.
+    pub fn zero_yaw(&self) {
+        let mut shared = self.shared();
+        shared.yaw_offset = shared.sample.yaw;
+        shared.yaw = 0.0;
+        shared.turns = 0;
+    }
+
+    /// Reset the velocity and displacement estimates to zero. This takes effect at the next
+    /// update.
+    pub fn reset_displacement(&self) {
+        self.shared().pending_control |= INTEGRATION_CTL_RESET_DISPLACEMENT;
+    }
+}
+
+impl Gyro for NavX {
+    type Error = DeviceError;
+
+    /// Wait for the navX to finish calibrating itself, returning `DeviceError::Timeout` if it has
+    /// not after 20 seconds.
+    fn calibrate(&mut self) -> Result<(), DeviceError> {
+        let start = Instant::now();
+        while !self.is_connected() || self.is_calibrating() {
+            if start.elapsed() > CALIBRATION_TIMEOUT {
+                return Err(DeviceError::Timeout);
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        Ok(())
+    }
+
+    fn reset(&mut self) -> Result<(), DeviceError> {
+        self.zero_yaw();
+        Ok(())
+    }
+
+    fn get_angle(&self) -> Result<f64, DeviceError> {
+        let shared = self.shared();
+        Ok(shared.turns as f64 * 360.0 + shared.yaw)
+    }
+
+    fn get_rate(&self) -> Result<f64, DeviceError> {
+        Ok(self.shared().rate)
+    }
+}
+
+impl Drop for NavX {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(join) = self.join.take() {
+            let _ = join.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build an AHRS position update with the given yaw, pitch and roll, in hundredths of a degree.
+    fn ahrs_pos_update(yaw: i16, pitch: i16, roll: i16) -> Vec<u8> {
+        let mut packet = vec![0; AHRSPOS_UPDATE_LENGTH - 4];
+        packet[..4].copy_from_slice(&[PACKET_START,
+                                      BINARY_PACKET_INDICATOR,
+                                      AHRSPOS_UPDATE_LENGTH as u8 - 2,
+                                      MSGID_AHRSPOS_UPDATE]);
+        packet[4..6].copy_from_slice(&yaw.to_le_bytes());
+        packet[6..8].copy_from_slice(&roll.to_le_bytes());
+        packet[8..10].copy_from_slice(&pitch.to_le_bytes());
+        packet[10..12].copy_from_slice(&27_000u16.to_le_bytes());
+        packet[12..16].copy_from_slice(&(-3 * 65536 / 2i32).to_le_bytes());
+        packet[16..18].copy_from_slice(&9_000u16.to_le_bytes());
+        packet[18..20].copy_from_slice(&(-250i16).to_le_bytes());
+        packet[22..24].copy_from_slice(&1_000i16.to_le_bytes());
+        packet[28..32].copy_from_slice(&(65536 / 4i32).to_le_bytes());
+        packet[44..48].copy_from_slice(&(2 * 65536i32).to_le_bytes());
+        packet[56..58].copy_from_slice(&3_150i16.to_le_bytes());
+        packet[58] = OP_STATUS_NORMAL;
+        packet[59] = SENSOR_STATUS_MOVING;
+        packet[60] = CAL_STATUS_IMU_CAL_COMPLETE;
+        terminate(packet)
+    }
+
+    fn sample_with_yaw(yaw: f64) -> Sample {
+        Sample { yaw, ..Sample::default() }
+    }
+
+    #[test]
+    fn crc7_residue_is_zero() {
+        assert_eq!(crc7(&[]), 0);
+        for message in [&[0x08, 0x68][..], &[0x84, 0x32], &[0xff, 0x00, 0x5a]] {
+            let mut framed = message.to_vec();
+            framed.push(crc7(message));
+            assert_eq!(crc7(&framed), 0, "{:?}", message);
+        }
+        assert_ne!(crc7(&[0x08, 0x68]), crc7(&[0x08, 0x69]));
+    }
+
+    #[test]
+    fn checksum_matches_terminate() {
+        let message = terminate(b"!Sp32".to_vec());
+        // '!' + 'S' + 'p' + '3' + '2' = 0x149, truncated to 0x49.
+        assert_eq!(message, b"!Sp3249\r\n");
+        assert!(checksum_valid(&message));
+
+        let mut corrupt = message.clone();
+        corrupt[3] = b'4';
+        assert!(!checksum_valid(&corrupt));
+    }
+
+    #[test]
+    fn decodes_registers() {
+        let mut data = [0; (REG_END - REG_OP_STATUS) as usize];
+        {
+            let mut set = |register: u8, bytes: &[u8]| {
+                let at = (register - REG_OP_STATUS) as usize;
+                data[at..at + bytes.len()].copy_from_slice(bytes);
+            };
+            set(REG_OP_STATUS, &[OP_STATUS_NORMAL]);
+            set(REG_CAL_STATUS, &[CAL_STATUS_IMU_CAL_COMPLETE | CAL_STATUS_MAG_CAL_COMPLETE]);
+            set(REG_SENSOR_STATUS_L, &[SENSOR_STATUS_MAG_DISTURBANCE]);
+            set(REG_YAW, &(-9_050i16).to_le_bytes());
+            set(REG_ROLL, &150i16.to_le_bytes());
+            set(REG_PITCH, &(-25i16).to_le_bytes());
+            set(REG_HEADING, &35_999u16.to_le_bytes());
+            set(REG_FUSED_HEADING, &18_000u16.to_le_bytes());
+            set(REG_ALTITUDE, &(65536 * 100 + 32768i32).to_le_bytes());
+            set(REG_LINEAR_ACC_X, &500i16.to_le_bytes());
+            set(REG_LINEAR_ACC_Z, &(-1_000i16).to_le_bytes());
+            set(REG_MPU_TEMP_C, &2_575i16.to_le_bytes());
+            set(REG_VEL_Y, &(-65536i32).to_le_bytes());
+            set(REG_DISP_Z, &(65536 / 2i32).to_le_bytes());
+        }
+
+        let sample = Sample::from_registers(&data);
+        assert_eq!(sample.op_status, OP_STATUS_NORMAL);
+        assert_eq!(sample.cal_status, CAL_STATUS_IMU_CAL_COMPLETE | CAL_STATUS_MAG_CAL_COMPLETE);
+        assert_eq!(sample.sensor_status, SENSOR_STATUS_MAG_DISTURBANCE);
+        assert_eq!(sample.yaw, -90.5);
+        assert_eq!(sample.roll, 1.5);
+        assert_eq!(sample.pitch, -0.25);
+        assert_eq!(sample.compass_heading, 359.99);
+        assert_eq!(sample.fused_heading, 180.0);
+        assert_eq!(sample.altitude, 100.5);
+        assert_eq!(sample.linear_acceleration,
+                   Acceleration { x: 0.5, y: 0.0, z: -1.0 });
+        assert_eq!(sample.temperature, 25.75);
+        assert_eq!(sample.velocity, [0.0, -1.0, 0.0]);
+        assert_eq!(sample.displacement, [0.0, 0.0, 0.5]);
+    }
+
+    #[test]
+    fn decodes_ahrs_pos_update() {
+        let sample = Sample::from_ahrs_pos_update(&ahrs_pos_update(-4_500, 1_000, -200));
+        assert_eq!(sample.yaw, -45.0);
+        assert_eq!(sample.pitch, 10.0);
+        assert_eq!(sample.roll, -2.0);
+        assert_eq!(sample.compass_heading, 270.0);
+        assert_eq!(sample.altitude, -1.5);
+        assert_eq!(sample.fused_heading, 90.0);
+        assert_eq!(sample.linear_acceleration,
+                   Acceleration { x: -0.25, y: 0.0, z: 1.0 });
+        assert_eq!(sample.velocity, [0.0, 0.25, 0.0]);
+        assert_eq!(sample.displacement, [0.0, 0.0, 2.0]);
+        assert_eq!(sample.temperature, 31.5);
+        assert_eq!(sample.op_status, OP_STATUS_NORMAL);
+        assert_eq!(sample.sensor_status, SENSOR_STATUS_MOVING);
+        assert_eq!(sample.cal_status, CAL_STATUS_IMU_CAL_COMPLETE);
+    }
+
+    #[test]
+    fn parses_a_complete_update() {
+        let mut buffer = ahrs_pos_update(1_234, 0, 0);
+        assert_eq!(Transport::parse(&mut buffer).map(|sample| sample.yaw), Some(12.34));
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn parses_an_update_split_across_reads() {
+        let packet = ahrs_pos_update(-500, 0, 0);
+        let mut buffer = packet[..30].to_vec();
+        assert!(Transport::parse(&mut buffer).is_none());
+        assert_eq!(buffer, &packet[..30]);
+
+        buffer.extend_from_slice(&packet[30..]);
+        assert_eq!(Transport::parse(&mut buffer).map(|sample| sample.yaw), Some(-5.0));
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn resyncs_after_garbage() {
+        let mut buffer = b"\x00\xff\r\nxyz".to_vec();
+        buffer.extend_from_slice(&ahrs_pos_update(100, 0, 0));
+        assert_eq!(Transport::parse(&mut buffer).map(|sample| sample.yaw), Some(1.0));
+        assert!(buffer.is_empty());
+
+        let mut buffer = b"no start byte here".to_vec();
+        assert!(Transport::parse(&mut buffer).is_none());
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn skips_updates_with_a_bad_checksum() {
+        let mut corrupt = ahrs_pos_update(100, 0, 0);
+        corrupt[5] ^= 0x01;
+        let mut buffer = ahrs_pos_update(200, 0, 0);
+        buffer.extend_from_slice(&corrupt);
+        assert_eq!(Transport::parse(&mut buffer).map(|sample| sample.yaw), Some(2.0));
+        assert!(buffer.is_empty());
+
+        let mut buffer = corrupt;
+        assert!(Transport::parse(&mut buffer).is_none());
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn skips_interleaved_text_messages() {
+        let mut buffer = terminate(b"!s0832".to_vec());
+        buffer.extend_from_slice(&ahrs_pos_update(300, 0, 0));
+        buffer.extend_from_slice(&terminate(b"!c00".to_vec()));
+        buffer.extend_from_slice(&ahrs_pos_update(400, 0, 0));
+        // A text message that has not finished arriving is kept for the next parse.
+        buffer.extend_from_slice(b"!s08");
+        assert_eq!(Transport::parse(&mut buffer).map(|sample| sample.yaw), Some(4.0));
+        assert_eq!(buffer, b"!s08");
+    }
+
+    #[test]
+    fn counts_turns_when_yaw_wraps() {
+        let start = Instant::now();
+        let mut shared = Shared::default();
+        shared.update(sample_with_yaw(170.0), start);
+        assert_eq!(shared.turns, 0);
+
+        // Turning 20 degrees clockwise in 100ms crosses 180.
+        shared.update(sample_with_yaw(-170.0), start + Duration::from_millis(100));
+        assert_eq!(shared.turns, 1);
+        assert_eq!(shared.yaw, -170.0);
+        assert!((shared.rate - 200.0).abs() < 1e-9);
+
+        // Turning back the other way undoes it.
+        shared.update(sample_with_yaw(175.0), start + Duration::from_millis(200));
+        assert_eq!(shared.turns, 0);
+        assert!((shared.rate + 150.0).abs() < 1e-9);
+
+        shared.update(sample_with_yaw(-175.0), start + Duration::from_millis(300));
+        shared.update(sample_with_yaw(-5.0), start + Duration::from_millis(400));
+        shared.update(sample_with_yaw(175.0), start + Duration::from_millis(500));
+        shared.update(sample_with_yaw(-175.0), start + Duration::from_millis(600));
+        assert_eq!(shared.turns, 2);
+    }
+
+    #[test]
+    fn zeroes_yaw_with_an_offset() {
+        let start = Instant::now();
+        let mut shared = Shared::default();
+        shared.update(sample_with_yaw(-170.0), start);
+        shared.yaw_offset = -170.0;
+        shared.yaw = 0.0;
+
+        // A raw yaw of 170 is 20 degrees counterclockwise of the new zero.
+        shared.update(sample_with_yaw(170.0), start + Duration::from_millis(100));
+        assert!((shared.yaw + 20.0).abs() < 1e-9);
+        assert_eq!(shared.turns, 0);
+        assert!((shared.rate + 200.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,129 @@
+use wpilib::wpilib_hal::*;
+use wpilib::hal_call::*;
+use wpilib::fpga;
+use std::sync::Mutex;
+use std::os::raw;
+
+/// When the notifier should next run, and how often it repeats.
+struct Schedule {
+    expiration_us: u64,
+    period_us: Option<u64>,
+}
+
+/// The state shared with the HAL's notifier thread.
+struct NotifierData {
+    handler: Mutex<Box<dyn FnMut() + Send>>,
+    schedule: Mutex<Schedule>,
+}
+
+/// Runs a closure at a time, or periodically, as measured by the FPGA clock. Unlike sleeping in a
+/// loop, the timing of each call does not drift with the time the closure takes to run.
+///
+/// All notifiers share a single thread, so a closure that takes a long time will delay the others.
+/// A closure may create, start, stop and drop other notifiers, but must not drop the notifier that
+/// is running it.
+///
+/// # Usage
+/// ```
+/// # use wpilib::*;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::sync::Arc;
+///
+/// let count = Arc::new(AtomicUsize::new(0));
+/// let counter = count.clone();
+/// let mut notifier = Notifier::new(move || {
+///     counter.fetch_add(1, Ordering::SeqCst);
+/// }).unwrap();
+///
+/// // Run every 5ms.
+/// notifier.start_periodic(5000).unwrap();
+/// # std::thread::sleep(std::time::Duration::from_millis(50));
+/// # assert!(count.load(Ordering::SeqCst) > 0);
+/// ```
+pub struct Notifier {
+    handle: HAL_NotifierHandle,
+    data: Box<NotifierData>,
+}
+
+impl Notifier {
+    /// Create a new notifier that will run `handler`. It does not run until it is started.
+    pub fn new<F>(handler: F) -> HalResult<Notifier>
+        where F: FnMut() + Send + 'static
+    {
+        let data = Box::new(NotifierData {
+            handler: Mutex::new(Box::new(handler)),
+            schedule: Mutex::new(Schedule {
+                expiration_us: 0,
+                period_us: None,
+            }),
+        });
+        let param = &*data as *const NotifierData as *mut raw::c_void;
+        let handle = hal_call!(HAL_InitializeNotifier(Some(notifier_callback), param))?;
+
+        Ok(Notifier {
+            handle,
+            data,
+        })
+    }
+
+    /// Run the handler once, at `time_us` on the FPGA clock. See `fpga::get_time_us`.
+    pub fn start_at(&mut self, time_us: u64) -> HalResult<()> {
+        let mut schedule = self.data.schedule.lock().unwrap();
+        schedule.expiration_us = time_us;
+        schedule.period_us = None;
+        hal_call!(HAL_UpdateNotifierAlarm(self.handle, time_us))
+    }
+
+    /// Run the handler once, `delay_us` microseconds from now.
+    pub fn start_single(&mut self, delay_us: u64) -> HalResult<()> {
+        self.start_at(fpga::get_time_us() + delay_us)
+    }
+
+    /// Run the handler every `period_us` microseconds, starting one period from now. Returns an
+    /// error if the period is zero.
+    pub fn start_periodic(&mut self, period_us: u64) -> HalResult<()> {
+        if period_us == 0 {
+            return Err(HalError(0));
+        }
+        let mut schedule = self.data.schedule.lock().unwrap();
+        schedule.expiration_us = fpga::get_time_us() + period_us;
+        schedule.period_us = Some(period_us);
+        hal_call!(HAL_UpdateNotifierAlarm(self.handle, schedule.expiration_us))
+    }
+
+    /// Stop running the handler. If it is running right now, that call will still finish.
+    pub fn stop(&mut self) -> HalResult<()> {
+        let mut schedule = self.data.schedule.lock().unwrap();
+        schedule.period_us = None;
+        hal_call!(HAL_StopNotifierAlarm(self.handle))
+    }
+}
+
+impl Drop for Notifier {
+    fn drop(&mut self) {
+        let _ = self.stop();
+        // This waits for any running call to finish, so the handler can be freed afterwards. On the
+        // notifier thread nothing else is running, so it returns straight away.
+        let _ = hal_call!(HAL_CleanNotifier(self.handle));
+    }
+}
+
+/// Called by the HAL when a notifier's alarm expires.
+extern "C" fn notifier_callback(_current_time: u64, handle: HAL_NotifierHandle) {
+    let param = match hal_call!(HAL_GetNotifierParam(handle)) {
+        Ok(param) if !param.is_null() => param,
+        _ => return,
+    };
+    let data = unsafe { &*(param as *const NotifierData) };
+
+    {
+        let mut schedule = data.schedule.lock().unwrap();
+        if let Some(period_us) = schedule.period_us {
+            // Schedule from the previous expiration rather than the current time to avoid drift.
+            schedule.expiration_us += period_us;
+            let _ = hal_call!(HAL_UpdateNotifierAlarm(handle, schedule.expiration_us));
+        }
+    }
+
+    (data.handler.lock().unwrap())();
+}
@@ -1,4 +1,3 @@
-use wpilib::wpilib_hal::*;
 use wpilib::hal_call::*;
 use wpilib::usage::*;
 use wpilib::sensor;
@@ -13,10 +12,11 @@ impl PowerDistributionPanel {
     pub fn new(module: i32) -> HalResult<PowerDistributionPanel> {
         hal_call!(HAL_InitializePDP(module))?;
         report_usage(ResourceType::PDP, module);
-        Ok(PowerDistributionPanel { module: module })
+        Ok(PowerDistributionPanel { module })
     }
 
     /// Use the default module (0).
+    #[allow(clippy::should_implement_trait)]
     pub fn default() -> HalResult<PowerDistributionPanel> {
         PowerDistributionPanel::new(0)
     }
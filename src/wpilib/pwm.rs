@@ -13,6 +13,7 @@ pub struct Pwm {
 /// Values by which the PWM cycle can be slowed.
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+#[allow(non_camel_case_types)]
 pub enum PeriodMultiplier {
     k1X = 1,
     k2X = 2,
@@ -27,14 +28,14 @@ impl Pwm {
             return Err(HalError(0));
         }
 
-        let handle = hal_call!(HAL_InitializePWMPort(HAL_GetPort(channel)))?;
+        let handle = hal_call!(HAL_InitializePWMPort(Hal::HAL_GetPort(channel)))?;
         hal_call!(HAL_SetPWMDisabled(handle))?;
 
         report_usage(ResourceType::PWM, channel);
 
         Ok(Pwm {
-            channel: channel,
-            handle: handle,
+            channel,
+            handle,
         })
     }
 
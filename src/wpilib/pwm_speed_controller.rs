@@ -7,9 +7,10 @@ use wpilib::usage::*;
 ///
 /// # Usage
 /// ```
+/// # use wpilib::*;
+/// # fn at_goal() -> bool { false }
 /// let mut victor = PwmSpeedController::victor_sp(0, false).unwrap();
-/// ...
-/// if (at_goal()) {
+/// if at_goal() {
 ///     victor.disable();
 /// } else {
 ///     victor.set(0.5);
@@ -34,8 +35,8 @@ impl PwmSpeedController {
         report_usage(ResourceType::VictorSP, channel);
 
         Ok(PwmSpeedController {
-            pwm: pwm,
-            inverted: inverted,
+            pwm,
+            inverted,
         })
     }
 
@@ -53,8 +54,8 @@ impl PwmSpeedController {
         report_usage(ResourceType::Victor, channel);
 
         Ok(PwmSpeedController {
-            pwm: pwm,
-            inverted: inverted,
+            pwm,
+            inverted,
         })
     }
 
@@ -71,8 +72,8 @@ impl PwmSpeedController {
         report_usage(ResourceType::Talon, channel);
 
         Ok(PwmSpeedController {
-            pwm: pwm,
-            inverted: inverted,
+            pwm,
+            inverted,
         })
     }
 
@@ -89,8 +90,8 @@ impl PwmSpeedController {
         report_usage(ResourceType::TalonSRX, channel);
 
         Ok(PwmSpeedController {
-            pwm: pwm,
-            inverted: inverted,
+            pwm,
+            inverted,
         })
     }
 }
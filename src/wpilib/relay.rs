@@ -0,0 +1,170 @@
+use wpilib::wpilib_hal::*;
+use wpilib::hal_call::*;
+use wpilib::sensor;
+use wpilib::usage::*;
+
+/// The value of a relay.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RelayValue {
+    /// Both outputs are off.
+    Off,
+    /// Both outputs are on. For a relay with a single direction, this turns that output on.
+    On,
+    /// The forward output is on and the reverse output is off.
+    Forward,
+    /// The reverse output is on and the forward output is off.
+    Reverse,
+}
+
+/// Which outputs of a relay are used.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RelayDirection {
+    /// Both outputs are used, for example to drive a motor in either direction.
+    Both,
+    /// Only the forward output is used, leaving the reverse output free for other code.
+    Forward,
+    /// Only the reverse output is used, leaving the forward output free for other code.
+    Reverse,
+}
+
+/// A Spike-style relay on one of the relay headers. Each header has a forward and a reverse
+/// output, which can be used together or separately.
+///
+/// # Usage
+/// ```
+/// # use wpilib::*;
+/// let mut light = Relay::new(0, RelayDirection::Forward).unwrap();
+/// light.set(RelayValue::On).unwrap();
+/// assert_eq!(light.get().unwrap(), RelayValue::On);
+///
+/// // Setting the other direction is an error.
+/// assert!(light.set(RelayValue::Reverse).is_err());
+/// ```
+pub struct Relay {
+    channel: i32,
+    direction: RelayDirection,
+    forward_handle: Option<HAL_RelayHandle>,
+    reverse_handle: Option<HAL_RelayHandle>,
+}
+
+impl Relay {
+    /// Create a new relay on the specified header, using the outputs given by `direction`,
+    /// returning an error if initialization fails. The relay starts off.
+    pub fn new(channel: i32, direction: RelayDirection) -> HalResult<Relay> {
+        if !sensor::check_relay_channel(channel) {
+            return Err(HalError(0));
+        }
+
+        let mut relay = Relay {
+            channel,
+            direction,
+            forward_handle: None,
+            reverse_handle: None,
+        };
+        relay.initialize()?;
+
+        report_usage(ResourceType::Relay, channel);
+        Ok(relay)
+    }
+
+    /// Allocate the ports used by the current direction. On failure, nothing is left allocated.
+    fn initialize(&mut self) -> HalResult<()> {
+        let port = unsafe { Hal::HAL_GetPort(self.channel) };
+        if self.direction != RelayDirection::Reverse {
+            self.forward_handle = Some(hal_call!(HAL_InitializeRelayPort(port, true as i32))?);
+        }
+        if self.direction != RelayDirection::Forward {
+            match hal_call!(HAL_InitializeRelayPort(port, false as i32)) {
+                Ok(handle) => self.reverse_handle = Some(handle),
+                Err(err) => {
+                    self.free();
+                    return Err(err);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Turn off and release any allocated ports.
+    fn free(&mut self) {
+        for handle in self.forward_handle.take().into_iter().chain(self.reverse_handle.take()) {
+            unsafe {
+                let mut status = 0;
+                Hal::HAL_SetRelay(handle, false as i32, &mut status);
+                Hal::HAL_FreeRelayPort(handle);
+            }
+        }
+    }
+
+    /// Set the value of the relay. For a relay with a single direction, `Forward` or `Reverse`
+    /// must match that direction, and is the same as `On`.
+    pub fn set(&mut self, value: RelayValue) -> HalResult<()> {
+        let (forward, reverse) = match (self.direction, value) {
+            (_, RelayValue::Off) => (false, false),
+            (RelayDirection::Both, RelayValue::On) => (true, true),
+            (RelayDirection::Both, RelayValue::Forward) => (true, false),
+            (RelayDirection::Both, RelayValue::Reverse) => (false, true),
+            (RelayDirection::Forward, RelayValue::On) |
+            (RelayDirection::Forward, RelayValue::Forward) => (true, false),
+            (RelayDirection::Reverse, RelayValue::On) |
+            (RelayDirection::Reverse, RelayValue::Reverse) => (false, true),
+            _ => return Err(HalError(0)),
+        };
+
+        if let Some(handle) = self.forward_handle {
+            hal_call!(HAL_SetRelay(handle, forward as i32))?;
+        }
+        if let Some(handle) = self.reverse_handle {
+            hal_call!(HAL_SetRelay(handle, reverse as i32))?;
+        }
+        Ok(())
+    }
+
+    /// Get the value last written to the relay. For a relay with a single direction, this is
+    /// either `On` or `Off`.
+    pub fn get(&self) -> HalResult<RelayValue> {
+        let forward = match self.forward_handle {
+            Some(handle) => hal_call!(HAL_GetRelay(handle))? != 0,
+            None => false,
+        };
+        let reverse = match self.reverse_handle {
+            Some(handle) => hal_call!(HAL_GetRelay(handle))? != 0,
+            None => false,
+        };
+
+        Ok(match (self.direction, forward, reverse) {
+            (RelayDirection::Both, true, true) => RelayValue::On,
+            (RelayDirection::Both, true, false) => RelayValue::Forward,
+            (RelayDirection::Both, false, true) => RelayValue::Reverse,
+            (_, false, false) => RelayValue::Off,
+            _ => RelayValue::On,
+        })
+    }
+
+    /// Change which outputs the relay uses. The relay is turned off, and outputs that are no
+    /// longer used are released.
+    pub fn set_direction(&mut self, direction: RelayDirection) -> HalResult<()> {
+        if direction == self.direction {
+            return Ok(());
+        }
+        self.free();
+        self.direction = direction;
+        self.initialize()
+    }
+
+    /// Get which outputs the relay uses.
+    pub fn get_direction(&self) -> RelayDirection {
+        self.direction
+    }
+
+    /// Get the header number of this relay.
+    pub fn get_channel(&self) -> i32 {
+        self.channel
+    }
+}
+
+impl Drop for Relay {
+    fn drop(&mut self) {
+        self.free();
+    }
+}
@@ -1,10 +1,12 @@
-use wpilib::wpilib_hal::*;
+use wpilib::hal_call::*;
 
 /// The base class from which all robots should be derived.
 ///
 /// # Usage
 ///
-/// ```
+/// ```no_run
+/// use wpilib::Robot;
+///
 /// struct TestRobot {};
 ///
 /// impl Robot for TestRobot {
@@ -31,14 +33,18 @@ pub trait Robot: Sized {
 
     /// Run the robot statically.
     fn main() {
-        // Initialize HAL
-        unsafe {
-            let status = HAL_Initialize(0);
-            if status != 1 {
-                panic!("WPILib HAL failed to initialize!");
-            }
-        }
+        initialize_hal();
         let robot = Self::new();
         robot.run();
     }
 }
+
+/// Initialize the HAL, panicking if it fails. This must be done before anything else uses the HAL.
+pub(crate) fn initialize_hal() {
+    unsafe {
+        let status = Hal::HAL_Initialize(0);
+        if status != 1 {
+            panic!("WPILib HAL failed to initialize!");
+        }
+    }
+}
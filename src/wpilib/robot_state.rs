@@ -1,4 +1,3 @@
-use wpilib::wpilib_hal::*;
 use wpilib::hal_call::*;
 
 /// Is the robot browned out?
@@ -15,3 +14,8 @@ pub fn is_system_active() -> HalResult<bool> {
 pub fn get_battery_voltage() -> HalResult<f64> {
     hal_call!(HAL_GetVinVoltage())
 }
+
+/// Get the voltage of the roboRIO's 5V rail, which powers sensors such as potentiometers.
+pub fn get_user_voltage_5v() -> HalResult<f64> {
+    hal_call!(HAL_GetUserVoltage5V())
+}
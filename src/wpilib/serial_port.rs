@@ -0,0 +1,346 @@
+use wpilib::wpilib_hal::*;
+use wpilib::hal_call::*;
+use wpilib::usage::*;
+use std::io;
+use std::os::raw::c_char;
+use std::time::Duration;
+
+/// One of the serial ports on the roboRIO.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SerialPortId {
+    /// The RS-232 port on the roboRIO itself.
+    OnBoard = 0,
+    /// The UART pins on the MXP port.
+    MXP = 1,
+    /// A USB serial adapter, the first one plugged in.
+    USB1 = 2,
+    /// A USB serial adapter, the second one plugged in.
+    USB2 = 3,
+}
+
+impl SerialPortId {
+    fn into_hal(self) -> HAL_SerialPort {
+        match self {
+            SerialPortId::OnBoard => HAL_SerialPort::HAL_SerialPort_Onboard,
+            SerialPortId::MXP => HAL_SerialPort::HAL_SerialPort_MXP,
+            SerialPortId::USB1 => HAL_SerialPort::HAL_SerialPort_USB1,
+            SerialPortId::USB2 => HAL_SerialPort::HAL_SerialPort_USB2,
+        }
+    }
+}
+
+/// The parity bit sent with each character.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Parity {
+    /// No parity bit.
+    None = 0,
+    /// The parity bit makes the number of set bits odd.
+    Odd = 1,
+    /// The parity bit makes the number of set bits even.
+    Even = 2,
+    /// The parity bit is always set.
+    Mark = 3,
+    /// The parity bit is always clear.
+    Space = 4,
+}
+
+/// The number of stop bits sent after each character.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StopBits {
+    /// One stop bit.
+    One = 10,
+    /// One and a half stop bits.
+    OnePointFive = 15,
+    /// Two stop bits.
+    Two = 20,
+}
+
+/// How the two ends of the line pace each other.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FlowControl {
+    /// No flow control.
+    None = 0,
+    /// Software flow control, with XON and XOFF characters.
+    XonXoff = 1,
+    /// Hardware flow control on the RTS and CTS lines.
+    RtsCts = 2,
+    /// Hardware flow control on the DTR and DSR lines.
+    DtrDsr = 4,
+}
+
+/// When buffered writes are sent out of the port.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WriteBufferMode {
+    /// Send the data as part of every write.
+    FlushOnAccess = 1,
+    /// Hold the data until the buffer fills or `flush` is called.
+    FlushWhenFull = 2,
+}
+
+fn io_error(err: HalError) -> io::Error {
+    io::Error::other(format!("{:?}", err))
+}
+
+/// Line settings for a serial port, used to open it. Unless changed, ports are opened 8N1 with no
+/// flow control, a 5 second timeout and no termination character.
+#[derive(Debug, Clone)]
+pub struct SerialPortBuilder {
+    port: SerialPortId,
+    baud_rate: i32,
+    data_bits: i32,
+    parity: Parity,
+    stop_bits: StopBits,
+    flow_control: FlowControl,
+    write_mode: WriteBufferMode,
+    timeout: Duration,
+    termination: Option<u8>,
+    read_buffer_size: Option<i32>,
+    write_buffer_size: Option<i32>,
+}
+
+impl SerialPortBuilder {
+    /// Set the number of data bits in each character, from 5 to 8.
+    pub fn data_bits(mut self, data_bits: i32) -> Self {
+        self.data_bits = data_bits;
+        self
+    }
+
+    /// Set the parity bit sent with each character.
+    pub fn parity(mut self, parity: Parity) -> Self {
+        self.parity = parity;
+        self
+    }
+
+    /// Set the number of stop bits sent after each character.
+    pub fn stop_bits(mut self, stop_bits: StopBits) -> Self {
+        self.stop_bits = stop_bits;
+        self
+    }
+
+    /// Set the flow control used on the line.
+    pub fn flow_control(mut self, flow_control: FlowControl) -> Self {
+        self.flow_control = flow_control;
+        self
+    }
+
+    /// Set when buffered writes are sent out of the port.
+    pub fn write_mode(mut self, write_mode: WriteBufferMode) -> Self {
+        self.write_mode = write_mode;
+        self
+    }
+
+    /// Set how long a read waits for data before giving up.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// End reads early when `terminator` is received, or not if this is `None`.
+    pub fn termination(mut self, terminator: Option<u8>) -> Self {
+        self.termination = terminator;
+        self
+    }
+
+    /// Set the size of the read buffer, in bytes.
+    pub fn read_buffer_size(mut self, size: i32) -> Self {
+        self.read_buffer_size = Some(size);
+        self
+    }
+
+    /// Set the size of the write buffer, in bytes.
+    pub fn write_buffer_size(mut self, size: i32) -> Self {
+        self.write_buffer_size = Some(size);
+        self
+    }
+
+    /// Open the port with these settings, returning an error if initialization fails.
+    pub fn open(self) -> HalResult<SerialPort> {
+        let port = self.port.into_hal();
+        hal_call!(HAL_InitializeSerialPort(port))?;
+        // Construct the port now, so that it is closed again if any setting fails.
+        let mut serial = SerialPort {
+            port: self.port,
+            termination: None,
+        };
+
+        hal_call!(HAL_SetSerialBaudRate(port, self.baud_rate))?;
+        hal_call!(HAL_SetSerialDataBits(port, self.data_bits))?;
+        hal_call!(HAL_SetSerialParity(port, self.parity as i32))?;
+        hal_call!(HAL_SetSerialStopBits(port, self.stop_bits as i32))?;
+        hal_call!(HAL_SetSerialFlowControl(port, self.flow_control as i32))?;
+        hal_call!(HAL_SetSerialWriteMode(port, self.write_mode as i32))?;
+        if let Some(size) = self.read_buffer_size {
+            hal_call!(HAL_SetSerialReadBufferSize(port, size))?;
+        }
+        if let Some(size) = self.write_buffer_size {
+            hal_call!(HAL_SetSerialWriteBufferSize(port, size))?;
+        }
+        serial.set_timeout(self.timeout)?;
+        serial.set_termination(self.termination)?;
+        serial.clear()?;
+
+        report_usage(ResourceType::SerialPort, self.port as i32);
+        Ok(serial)
+    }
+}
+
+/// A serial port, for talking to coprocessors and sensors over RS-232, the MXP UART or USB.
+///
+/// Reads block until data arrives or the timeout expires, and a read that times out with no data
+/// fails with `ErrorKind::TimedOut`.
+///
+/// # Usage
+/// ```
+/// # use wpilib::*;
+/// use std::io::Write;
+///
+/// let mut vision = SerialPort::builder(SerialPortId::MXP, 115200)
+///     .termination(Some(b'\n'))
+///     .open()
+///     .unwrap();
+/// vision.write_all(b"target?\n").unwrap();
+/// # sim::serial(SerialPortId::MXP).receive(b"12.5,3.0\n");
+///
+/// let mut line = String::new();
+/// vision.read_line(&mut line).unwrap();
+/// assert_eq!(line, "12.5,3.0\n");
+/// ```
+pub struct SerialPort {
+    port: SerialPortId,
+    termination: Option<u8>,
+}
+
+impl SerialPort {
+    /// Start configuring a port at the given baud rate. Call `open` on the result to open it.
+    pub fn builder(port: SerialPortId, baud_rate: i32) -> SerialPortBuilder {
+        SerialPortBuilder {
+            port,
+            baud_rate,
+            data_bits: 8,
+            parity: Parity::None,
+            stop_bits: StopBits::One,
+            flow_control: FlowControl::None,
+            write_mode: WriteBufferMode::FlushOnAccess,
+            timeout: Duration::from_secs(5),
+            termination: None,
+            read_buffer_size: None,
+            write_buffer_size: None,
+        }
+    }
+
+    /// Open a port at the given baud rate with the default line settings, returning an error if
+    /// initialization fails.
+    pub fn new(port: SerialPortId, baud_rate: i32) -> HalResult<SerialPort> {
+        SerialPort::builder(port, baud_rate).open()
+    }
+
+    /// Get which port this is.
+    pub fn get_port(&self) -> SerialPortId {
+        self.port
+    }
+
+    /// Set how long a read waits for data before giving up.
+    pub fn set_timeout(&mut self, timeout: Duration) -> HalResult<()> {
+        let seconds = timeout.as_secs() as f64 + timeout.subsec_nanos() as f64 * 1e-9;
+        hal_call!(HAL_SetSerialTimeout(self.port.into_hal(), seconds))
+    }
+
+    /// End reads early when `terminator` is received, or not if this is `None`.
+    pub fn set_termination(&mut self, terminator: Option<u8>) -> HalResult<()> {
+        match terminator {
+            Some(terminator) => {
+                hal_call!(HAL_EnableSerialTermination(self.port.into_hal(),
+                                                      terminator as c_char))?
+            }
+            None => hal_call!(HAL_DisableSerialTermination(self.port.into_hal()))?,
+        }
+        self.termination = terminator;
+        Ok(())
+    }
+
+    /// Get the number of bytes waiting to be read.
+    pub fn get_bytes_received(&self) -> HalResult<i32> {
+        hal_call!(HAL_GetSerialBytesReceived(self.port.into_hal()))
+    }
+
+    /// Throw away any bytes waiting to be read.
+    pub fn clear(&mut self) -> HalResult<()> {
+        hal_call!(HAL_ClearSerial(self.port.into_hal()))
+    }
+
+    /// Read a line into `buf`, returning the number of bytes read. The line ends at the
+    /// termination character, or at `\n` if termination is off, and the end is kept. If the read
+    /// times out part way through a line, the partial line is returned.
+    pub fn read_line(&mut self, buf: &mut String) -> io::Result<usize> {
+        let terminator = self.termination.unwrap_or(b'\n');
+        let mut line = Vec::new();
+        let mut chunk = [0u8; 64];
+        loop {
+            // With termination on, the HAL stops each read at the terminator, so nothing past
+            // the end of the line is consumed. Otherwise read a byte at a time.
+            let len = if self.termination.is_some() { chunk.len() } else { 1 };
+            let count = match self.read_raw(&mut chunk[..len]) {
+                Ok(0) if line.is_empty() => {
+                    return Err(io::Error::new(io::ErrorKind::TimedOut, "serial read timed out"))
+                }
+                Ok(0) => break,
+                Ok(count) => count,
+                Err(err) => return Err(io_error(err)),
+            };
+            line.extend_from_slice(&chunk[..count]);
+            if chunk[count - 1] == terminator {
+                break;
+            }
+        }
+
+        let line = String::from_utf8(line)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        buf.push_str(&line);
+        Ok(line.len())
+    }
+
+    /// Read up to `buf.len()` bytes, waiting until they all arrive, the terminator is received or
+    /// the timeout expires.
+    fn read_raw(&mut self, buf: &mut [u8]) -> HalResult<usize> {
+        let count = hal_call!(HAL_ReadSerial(self.port.into_hal(),
+                                             buf.as_mut_ptr() as *mut c_char,
+                                             buf.len() as i32))?;
+        Ok(count.max(0) as usize)
+    }
+}
+
+impl io::Read for SerialPort {
+    /// Read the bytes that are waiting, up to `buf.len()`. If none are, wait for at least one to
+    /// arrive.
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let available = self.get_bytes_received().map_err(io_error)?.max(0) as usize;
+        let len = available.clamp(1, buf.len());
+        match self.read_raw(&mut buf[..len]) {
+            Ok(0) => Err(io::Error::new(io::ErrorKind::TimedOut, "serial read timed out")),
+            Ok(count) => Ok(count),
+            Err(err) => Err(io_error(err)),
+        }
+    }
+}
+
+impl io::Write for SerialPort {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let count = hal_call!(HAL_WriteSerial(self.port.into_hal(),
+                                              buf.as_ptr() as *const c_char,
+                                              buf.len() as i32)).map_err(io_error)?;
+        Ok(count.max(0) as usize)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        hal_call!(HAL_FlushSerial(self.port.into_hal())).map_err(io_error)
+    }
+}
+
+impl Drop for SerialPort {
+    fn drop(&mut self) {
+        let _ = hal_call!(HAL_CloseSerial(self.port.into_hal()));
+    }
+}
@@ -0,0 +1,756 @@
+//! Inspect and drive the simulated hardware. This module only exists when the crate is built
+//! without the `athena` feature.
+//!
+//! Each accessor returns a lightweight view of one device in the simulated HAL; reading from it
+//! shows what robot code has commanded, and writing to it changes what robot code will read back.
+//!
+//! ```
+//! use wpilib::*;
+//!
+//! let mut victor = PwmSpeedController::victor_sp(3, false).unwrap();
+//! victor.set(0.5);
+//! assert!((sim::pwm(3).speed() - 0.5).abs() < 0.01);
+//!
+//! sim::analog_in(0).set_voltage(2.5);
+//! let input = AnalogInput::new(0).unwrap();
+//! assert_eq!(input.get_voltage().unwrap(), 2.5);
+//! ```
+
+use wpilib::hal_sim::*;
+use wpilib::i2c::I2cPort;
+use wpilib::serial_port::SerialPortId;
+use wpilib::spi::SpiPort;
+
+// Bits of the driver station control word.
+const ENABLED: u32 = 1 << 0;
+const AUTONOMOUS: u32 = 1 << 1;
+const TEST: u32 = 1 << 2;
+const ESTOP: u32 = 1 << 3;
+const FMS_ATTACHED: u32 = 1 << 4;
+const DS_ATTACHED: u32 = 1 << 5;
+
+/// Put every simulated device back into its power-on state. Any objects still holding HAL
+/// resources should be dropped first, since their handles will no longer be valid.
+pub fn reset() {
+    reset_state();
+}
+
+fn check(index: i32, count: usize, kind: &str) -> usize {
+    assert!(index >= 0 && (index as usize) < count,
+            "no simulated {} {}",
+            kind,
+            index);
+    index as usize
+}
+
+/// A simulated PWM output.
+#[derive(Debug, Copy, Clone)]
+pub struct PwmSim {
+    channel: usize,
+}
+
+/// Get the simulated PWM output on a channel.
+pub fn pwm(channel: i32) -> PwmSim {
+    PwmSim { channel: check(channel, NUM_PWM_CHANNELS, "PWM channel") }
+}
+
+impl PwmSim {
+    /// Has robot code allocated this channel?
+    pub fn is_initialized(&self) -> bool {
+        state().pwm.allocated[self.channel]
+    }
+
+    /// Get the raw value last written to the channel, 0 if disabled.
+    pub fn raw(&self) -> i32 {
+        state().pwm.devices[self.channel].raw
+    }
+
+    /// Get the commanded speed, in [-1, 1]. Returns 0 if the channel has not been configured.
+    pub fn speed(&self) -> f64 {
+        state().pwm.devices[self.channel].speed().unwrap_or(0.0)
+    }
+
+    /// Get the commanded servo position, in [0, 1]. Returns 0 if the channel has not been
+    /// configured.
+    pub fn position(&self) -> f64 {
+        state().pwm.devices[self.channel].position().unwrap_or(0.0)
+    }
+
+    /// Is the output latched to zero?
+    pub fn is_zero_latched(&self) -> bool {
+        state().pwm.devices[self.channel].zero_latch
+    }
+
+    /// Get the period scale mask written to the channel.
+    pub fn period_scale(&self) -> i32 {
+        state().pwm.devices[self.channel].period_scale
+    }
+}
+
+/// A simulated digital I/O channel.
+#[derive(Debug, Copy, Clone)]
+pub struct DioSim {
+    channel: usize,
+}
+
+/// Get the simulated digital I/O on a channel.
+pub fn dio(channel: i32) -> DioSim {
+    DioSim { channel: check(channel, NUM_DIGITAL_CHANNELS, "digital channel") }
+}
+
+impl DioSim {
+    /// Has robot code allocated this channel?
+    pub fn is_initialized(&self) -> bool {
+        state().digital.allocated[self.channel]
+    }
+
+    /// Is the channel configured as an input?
+    pub fn is_input(&self) -> bool {
+        state().digital.devices[self.channel].is_input
+    }
+
+    /// Get the value on the channel, either written by robot code or set from here.
+    pub fn value(&self) -> bool {
+        state().digital.devices[self.channel].value
+    }
+
+    /// Get the glitch filter applied to the channel: 1 to 3 for a filter, or 0 for none.
+    pub fn filter(&self) -> i32 {
+        state().digital.devices[self.channel].filter
+    }
+
+    /// Set the value on the channel, as seen by a digital input. If this changes the value, any
+    /// interrupts on the input are triggered, and their handlers run before this returns.
+    pub fn set_value(&self, value: bool) {
+        set_digital_input(self.channel, value);
+    }
+}
+
+/// A simulated analog input.
+#[derive(Debug, Copy, Clone)]
+pub struct AnalogInSim {
+    channel: usize,
+}
+
+/// Get the simulated analog input on a channel.
+pub fn analog_in(channel: i32) -> AnalogInSim {
+    AnalogInSim { channel: check(channel, NUM_ANALOG_INPUTS, "analog input") }
+}
+
+impl AnalogInSim {
+    /// Has robot code allocated this channel?
+    pub fn is_initialized(&self) -> bool {
+        state().analog_inputs.allocated[self.channel]
+    }
+
+    /// Get the voltage on the input.
+    pub fn voltage(&self) -> f64 {
+        state().analog_inputs.devices[self.channel].voltage
+    }
+
+    /// Set the voltage on the input.
+    pub fn set_voltage(&self, voltage: f64) {
+        state().analog_inputs.devices[self.channel].voltage = voltage;
+    }
+
+    /// Set the accumulated value and sample count. Only channels 0 and 1 have accumulators.
+    pub fn set_accumulator(&self, value: i64, count: i64) {
+        let channel = check(self.channel as i32, NUM_ACCUMULATORS, "accumulator");
+        let accumulator = &mut state().analog_inputs.devices[channel].accumulator;
+        accumulator.value = value;
+        accumulator.count = count;
+    }
+}
+
+/// A simulated analog gyro.
+#[derive(Debug, Copy, Clone)]
+pub struct AnalogGyroSim {
+    channel: usize,
+}
+
+/// Get the simulated analog gyro on an accumulator channel.
+pub fn analog_gyro(channel: i32) -> AnalogGyroSim {
+    AnalogGyroSim { channel: check(channel, NUM_ACCUMULATORS, "accumulator") }
+}
+
+impl AnalogGyroSim {
+    /// Has robot code allocated a gyro on this channel?
+    pub fn is_initialized(&self) -> bool {
+        state().analog_gyros.allocated[self.channel]
+    }
+
+    /// Get the angle, in degrees.
+    pub fn angle(&self) -> f64 {
+        state().analog_gyros.devices[self.channel].angle
+    }
+
+    /// Set the angle, in degrees.
+    pub fn set_angle(&self, angle: f64) {
+        state().analog_gyros.devices[self.channel].angle = angle;
+    }
+
+    /// Set the rate of rotation, in degrees per second.
+    pub fn set_rate(&self, rate: f64) {
+        state().analog_gyros.devices[self.channel].rate = rate;
+    }
+}
+
+/// A simulated MXP analog output.
+#[derive(Debug, Copy, Clone)]
+pub struct AnalogOutSim {
+    channel: usize,
+}
+
+/// Get the simulated analog output on a channel.
+pub fn analog_out(channel: i32) -> AnalogOutSim {
+    AnalogOutSim { channel: check(channel, NUM_ANALOG_OUTPUTS, "analog output") }
+}
+
+impl AnalogOutSim {
+    /// Has robot code allocated this channel?
+    pub fn is_initialized(&self) -> bool {
+        state().analog_outputs.allocated[self.channel]
+    }
+
+    /// Get the voltage last written to the output.
+    pub fn voltage(&self) -> f64 {
+        state().analog_outputs.devices[self.channel].voltage
+    }
+}
+
+/// A simulated quadrature encoder.
+#[derive(Debug, Copy, Clone)]
+pub struct EncoderSim {
+    index: usize,
+}
+
+/// Get the simulated encoder with an FPGA index. Encoders are given the lowest free index when
+/// they are created, so the first encoder is 0.
+pub fn encoder(index: i32) -> EncoderSim {
+    EncoderSim { index: check(index, NUM_ENCODERS, "encoder") }
+}
+
+impl EncoderSim {
+    /// Has robot code allocated this encoder?
+    pub fn is_initialized(&self) -> bool {
+        state().encoders.allocated[self.index]
+    }
+
+    /// Get the count, as seen by an encoder that is not reversed.
+    pub fn count(&self) -> i32 {
+        let encoder = &state().encoders.devices[self.index];
+        encoder.raw / encoder.scale()
+    }
+
+    /// Set the count, as seen by an encoder that is not reversed.
+    pub fn set_count(&self, count: i32) {
+        let encoder = &mut state().encoders.devices[self.index];
+        encoder.raw = count * encoder.scale();
+    }
+
+    /// Get the rate, in counts per second.
+    pub fn rate(&self) -> f64 {
+        state().encoders.devices[self.index].rate
+    }
+
+    /// Set the rate, in counts per second.
+    pub fn set_rate(&self, rate: f64) {
+        state().encoders.devices[self.index].rate = rate;
+    }
+}
+
+/// A simulated counter.
+#[derive(Debug, Copy, Clone)]
+pub struct CounterSim {
+    index: usize,
+}
+
+/// Get the simulated counter with an FPGA index. Counters are given the lowest free index when
+/// they are created, so the first counter is 0.
+pub fn counter(index: i32) -> CounterSim {
+    CounterSim { index: check(index, NUM_COUNTERS, "counter") }
+}
+
+impl CounterSim {
+    /// Has robot code allocated this counter?
+    pub fn is_initialized(&self) -> bool {
+        state().counters.allocated[self.index]
+    }
+
+    /// Get the count.
+    pub fn count(&self) -> i32 {
+        state().counters.devices[self.index].count
+    }
+
+    /// Set the count.
+    pub fn set_count(&self, count: i32) {
+        state().counters.devices[self.index].count = count;
+    }
+
+    /// Set the time between counts, in seconds. The counter reports that it is stopped while this
+    /// is longer than its max period.
+    pub fn set_period(&self, period: f64) {
+        state().counters.devices[self.index].period = period;
+    }
+
+    /// Set the direction of the last count. True means up.
+    pub fn set_direction(&self, up: bool) {
+        state().counters.devices[self.index].direction = up;
+    }
+}
+
+/// A simulated relay header.
+#[derive(Debug, Copy, Clone)]
+pub struct RelaySim {
+    channel: usize,
+}
+
+/// Get the simulated relay on a header.
+pub fn relay(channel: i32) -> RelaySim {
+    RelaySim { channel: check(channel, NUM_RELAY_HEADERS, "relay header") }
+}
+
+impl RelaySim {
+    /// Has robot code allocated either output of this header?
+    pub fn is_initialized(&self) -> bool {
+        let state = state();
+        state.relays.allocated[self.channel] ||
+        state.relays.allocated[self.channel + NUM_RELAY_HEADERS]
+    }
+
+    /// Is the forward output on?
+    pub fn forward(&self) -> bool {
+        state().relays.devices[self.channel].on
+    }
+
+    /// Is the reverse output on?
+    pub fn reverse(&self) -> bool {
+        state().relays.devices[self.channel + NUM_RELAY_HEADERS].on
+    }
+}
+
+/// A simulated solenoid channel.
+#[derive(Debug, Copy, Clone)]
+pub struct SolenoidSim {
+    index: usize,
+}
+
+/// Get the simulated solenoid on a channel of a PCM.
+pub fn solenoid(module: i32, channel: i32) -> SolenoidSim {
+    let module = check(module, NUM_PCM_MODULES, "PCM module");
+    let channel = check(channel, NUM_SOLENOID_CHANNELS, "solenoid channel");
+    SolenoidSim { index: module * NUM_SOLENOID_CHANNELS + channel }
+}
+
+impl SolenoidSim {
+    /// Has robot code allocated this channel?
+    pub fn is_initialized(&self) -> bool {
+        state().solenoids.allocated[self.index]
+    }
+
+    /// Is the solenoid on?
+    pub fn value(&self) -> bool {
+        state().solenoids.devices[self.index].value
+    }
+}
+
+/// A simulated pneumatics control module.
+#[derive(Debug, Copy, Clone)]
+pub struct PcmSim {
+    module: usize,
+}
+
+/// Get the simulated PCM on a CAN module.
+pub fn pcm(module: i32) -> PcmSim {
+    PcmSim { module: check(module, NUM_PCM_MODULES, "PCM module") }
+}
+
+impl PcmSim {
+    /// Set the blacklist of shorted solenoid channels, one bit per channel.
+    pub fn set_solenoid_blacklist(&self, blacklist: i32) {
+        state().pcm[self.module].solenoid_blacklist = blacklist;
+    }
+
+    /// Set whether the solenoid output voltage is out of range. Setting a fault also sets the
+    /// sticky fault.
+    pub fn set_solenoid_voltage_fault(&self, fault: bool) {
+        let pcm = &mut state().pcm[self.module];
+        pcm.solenoid_voltage_fault = fault;
+        pcm.solenoid_voltage_sticky_fault |= fault;
+    }
+
+    /// Set whether the pressure switch reports that the tanks are full.
+    pub fn set_pressure_switch(&self, full: bool) {
+        state().pcm[self.module].pressure_switch = full;
+    }
+
+    /// Set the current drawn by the compressor, in amps.
+    pub fn set_compressor_current(&self, current: f64) {
+        state().pcm[self.module].compressor_current = current;
+    }
+
+    /// Set whether the compressor is drawing too much current. Setting a fault also sets the
+    /// sticky fault.
+    pub fn set_compressor_current_too_high_fault(&self, fault: bool) {
+        let pcm = &mut state().pcm[self.module];
+        pcm.current_too_high_fault = fault;
+        pcm.current_too_high_sticky_fault |= fault;
+    }
+
+    /// Set whether the compressor output is shorted. Setting a fault also sets the sticky fault.
+    pub fn set_compressor_shorted_fault(&self, fault: bool) {
+        let pcm = &mut state().pcm[self.module];
+        pcm.shorted_fault = fault;
+        pcm.shorted_sticky_fault |= fault;
+    }
+
+    /// Set whether the compressor is disconnected. Setting a fault also sets the sticky fault.
+    pub fn set_compressor_not_connected_fault(&self, fault: bool) {
+        let pcm = &mut state().pcm[self.module];
+        pcm.not_connected_fault = fault;
+        pcm.not_connected_sticky_fault |= fault;
+    }
+
+    /// Is closed loop control of the compressor on?
+    pub fn closed_loop_control(&self) -> bool {
+        state().pcm[self.module].closed_loop
+    }
+
+    /// Would the PCM be running the compressor right now? This is true when the robot is enabled,
+    /// closed loop control is on, and the pressure switch reports that the tanks are not full.
+    pub fn compressor_on(&self) -> bool {
+        let state = state();
+        let pcm = &state.pcm[self.module];
+        state.control_word & 1 != 0 && pcm.closed_loop && !pcm.pressure_switch
+    }
+}
+
+/// A simulated power distribution panel.
+#[derive(Debug, Copy, Clone)]
+pub struct PdpSim {
+    module: usize,
+}
+
+/// Get the simulated PDP on a CAN module.
+pub fn pdp(module: i32) -> PdpSim {
+    PdpSim { module: check(module, NUM_PDP_MODULES, "PDP module") }
+}
+
+impl PdpSim {
+    /// Set the input voltage.
+    pub fn set_voltage(&self, voltage: f64) {
+        state().pdp[self.module].voltage = voltage;
+    }
+
+    /// Set the temperature, in degrees Celsius.
+    pub fn set_temperature(&self, temperature: f64) {
+        state().pdp[self.module].temperature = temperature;
+    }
+
+    /// Get the current drawn through a channel, in amps.
+    pub fn current(&self, channel: i32) -> f64 {
+        let channel = check(channel, NUM_PDP_CHANNELS, "PDP channel");
+        state().pdp[self.module].currents[channel]
+    }
+
+    /// Set the current drawn through a channel, in amps.
+    pub fn set_current(&self, channel: i32, current: f64) {
+        let channel = check(channel, NUM_PDP_CHANNELS, "PDP channel");
+        state().pdp[self.module].currents[channel] = current;
+    }
+
+    /// Set the total energy used, in joules.
+    pub fn set_total_energy(&self, energy: f64) {
+        state().pdp[self.module].total_energy = energy;
+    }
+}
+
+/// A simulated I2C port, with any number of register-based devices attached.
+pub struct I2cSim {
+    port: usize,
+}
+
+/// Get the simulated I2C port.
+pub fn i2c(port: I2cPort) -> I2cSim {
+    I2cSim { port: port as usize }
+}
+
+impl I2cSim {
+    /// Has robot code opened this port?
+    pub fn is_initialized(&self) -> bool {
+        state().i2c[self.port].open
+    }
+
+    /// Attach a device at `address`, with every register zero. Writing a register address to it
+    /// sets its register pointer, and reads and further writes continue from there.
+    pub fn add_device(&self, address: i32) {
+        state().i2c[self.port].devices.insert(address, Default::default());
+    }
+
+    /// Detach the device at `address`, so that transfers to it are aborted.
+    pub fn remove_device(&self, address: i32) {
+        state().i2c[self.port].devices.remove(&address);
+    }
+
+    /// Set consecutive registers of the device at `address`, starting at `register`.
+    ///
+    /// # Panics
+    /// If there is no device at `address`.
+    pub fn set_registers(&self, address: i32, register: u8, values: &[u8]) {
+        let mut sim = state();
+        let device = sim.i2c[self.port].devices.get_mut(&address).expect("no simulated device");
+        for (offset, &value) in values.iter().enumerate() {
+            device.registers[(register as usize + offset) % 256] = value;
+        }
+    }
+
+    /// Get `count` consecutive registers of the device at `address`, starting at `register`.
+    ///
+    /// # Panics
+    /// If there is no device at `address`.
+    pub fn registers(&self, address: i32, register: u8, count: usize) -> Vec<u8> {
+        let sim = state();
+        let device = sim.i2c[self.port].devices.get(&address).expect("no simulated device");
+        (0..count).map(|offset| device.registers[(register as usize + offset) % 256]).collect()
+    }
+}
+
+/// A simulated SPI port, with a device on the other end that robot code talks to.
+#[derive(Debug, Copy, Clone)]
+pub struct SpiSim {
+    port: usize,
+}
+
+/// Get the simulated SPI port.
+pub fn spi(port: SpiPort) -> SpiSim {
+    SpiSim { port: port as usize }
+}
+
+impl SpiSim {
+    /// Has robot code opened this port?
+    pub fn is_initialized(&self) -> bool {
+        state().spi[self.port].open
+    }
+
+    /// Get the clock rate robot code configured, in Hz.
+    pub fn clock_rate(&self) -> i32 {
+        state().spi[self.port].speed
+    }
+
+    /// Is the most significant bit of each byte sent first?
+    pub fn msb_first(&self) -> bool {
+        state().spi[self.port].msb_first
+    }
+
+    /// Get the clock polarity and phase as an SPI mode number, from 0 to 3.
+    pub fn mode(&self) -> i32 {
+        let spi = &state().spi[self.port];
+        ((spi.clk_idle_high as i32) << 1) | spi.sample_on_trailing as i32
+    }
+
+    /// Is chip select driven high while talking to the device?
+    pub fn chip_select_active_high(&self) -> bool {
+        state().spi[self.port].chip_select_active_high
+    }
+
+    /// Queue bytes for the device to send back, one for each byte robot code transfers. Once the
+    /// queue is empty, the device sends zeros. The queue is kept when the port is opened, so a
+    /// device can reply to robot code that talks to it while being set up.
+    pub fn queue_response(&self, data: &[u8]) {
+        state().spi[self.port].responses.extend(data);
+    }
+
+    /// Take the bytes robot code has sent to the device since the last call.
+    pub fn take_sent(&self) -> Vec<u8> {
+        ::std::mem::take(&mut state().spi[self.port].sent)
+    }
+
+    /// Is the FPGA accumulator running on this port?
+    pub fn is_accumulating(&self) -> bool {
+        state().spi[self.port].accumulator.is_some()
+    }
+
+    /// Feed a sample to the accumulator, as if the FPGA had just read it from the device. This
+    /// does nothing if the accumulator is not running.
+    pub fn accumulate(&self, data: i32) {
+        if let Some(ref mut accumulator) = state().spi[self.port].accumulator {
+            accumulator.add_sample(data);
+        }
+    }
+}
+
+/// A simulated serial port, with a device on the other end that robot code talks to.
+#[derive(Debug, Copy, Clone)]
+pub struct SerialSim {
+    port: usize,
+}
+
+/// Get the simulated serial port.
+pub fn serial(port: SerialPortId) -> SerialSim {
+    SerialSim { port: port as usize }
+}
+
+impl SerialSim {
+    /// Has robot code opened this port?
+    pub fn is_initialized(&self) -> bool {
+        state().serial[self.port].open
+    }
+
+    /// Get the baud rate robot code configured.
+    pub fn baud_rate(&self) -> i32 {
+        state().serial[self.port].baud_rate
+    }
+
+    /// Send bytes from the device, for robot code to read.
+    pub fn receive(&self, data: &[u8]) {
+        state().serial[self.port].received.extend(data);
+    }
+
+    /// Take the bytes robot code has sent to the device since the last call. Bytes still sitting
+    /// in the write buffer are not included until robot code flushes them.
+    pub fn take_sent(&self) -> Vec<u8> {
+        ::std::mem::take(&mut state().serial[self.port].sent)
+    }
+}
+
+/// A simulated joystick plugged into the driver station. Setting an axis, button or POV that is
+/// past the end of the joystick extends it, as if a larger joystick had been plugged in.
+#[derive(Debug, Copy, Clone)]
+pub struct JoystickSim {
+    port: usize,
+}
+
+/// Get the simulated joystick on a driver station port.
+pub fn joystick(port: i32) -> JoystickSim {
+    JoystickSim { port: check(port, NUM_JOYSTICKS, "joystick port") }
+}
+
+impl JoystickSim {
+    /// Set the value of an axis, in [-1, 1].
+    pub fn set_axis(&self, axis: usize, value: f32) {
+        let axes = &mut state().joysticks[self.port].axes;
+        axes.axes[axis] = value;
+        axes.count = axes.count.max(axis as i16 + 1);
+    }
+
+    /// Set whether a button is pressed. Buttons are numbered from 1.
+    pub fn set_button(&self, button: usize, pressed: bool) {
+        assert!((1..=32).contains(&button), "no joystick button {}", button);
+        let buttons = &mut state().joysticks[self.port].buttons;
+        let mask = 1 << (button - 1);
+        if pressed {
+            buttons.buttons |= mask;
+        } else {
+            buttons.buttons &= !mask;
+        }
+        buttons.count = buttons.count.max(button as u8);
+    }
+
+    /// Set the angle of a POV switch in degrees, or -1 if it is not pressed.
+    pub fn set_pov(&self, pov: usize, value: i16) {
+        let povs = &mut state().joysticks[self.port].povs;
+        povs.povs[pov] = value;
+        povs.count = povs.count.max(pov as i16 + 1);
+    }
+
+    /// Unplug the joystick, removing all of its axes, buttons and POVs.
+    pub fn unplug(&self) {
+        state().joysticks[self.port] = JoystickState::default();
+    }
+
+    /// Get the output bits last sent to the joystick.
+    pub fn outputs(&self) -> i64 {
+        state().joysticks[self.port].outputs
+    }
+
+    /// Get the left and right rumble values last sent to the joystick.
+    pub fn rumble(&self) -> (i32, i32) {
+        let stick = &state().joysticks[self.port];
+        (stick.left_rumble, stick.right_rumble)
+    }
+}
+
+/// The simulated driver station's control word.
+#[derive(Debug, Copy, Clone)]
+pub struct DriverStationSim;
+
+/// Get the simulated driver station.
+pub fn driver_station() -> DriverStationSim {
+    DriverStationSim
+}
+
+impl DriverStationSim {
+    fn set_bit(&self, bit: u32, value: bool) {
+        let mut sim = state();
+        if value {
+            sim.control_word |= bit;
+        } else {
+            sim.control_word &= !bit;
+        }
+    }
+
+    /// Enable or disable the robot.
+    pub fn set_enabled(&self, enabled: bool) {
+        self.set_bit(ENABLED, enabled);
+    }
+
+    /// Switch between autonomous and teleop.
+    pub fn set_autonomous(&self, autonomous: bool) {
+        self.set_bit(AUTONOMOUS, autonomous);
+    }
+
+    /// Switch in or out of test mode.
+    pub fn set_test(&self, test: bool) {
+        self.set_bit(TEST, test);
+    }
+
+    /// Set or clear the emergency stop.
+    pub fn set_estop(&self, estop: bool) {
+        self.set_bit(ESTOP, estop);
+    }
+
+    /// Attach or detach the FMS.
+    pub fn set_fms_attached(&self, attached: bool) {
+        self.set_bit(FMS_ATTACHED, attached);
+    }
+
+    /// Attach or detach the driver station.
+    pub fn set_ds_attached(&self, attached: bool) {
+        self.set_bit(DS_ATTACHED, attached);
+    }
+}
+
+/// The roboRIO's simulated power supply and built-in accelerometer.
+#[derive(Debug, Copy, Clone)]
+pub struct RoboRioSim;
+
+/// Get the simulated roboRIO.
+pub fn roborio() -> RoboRioSim {
+    RoboRioSim
+}
+
+impl RoboRioSim {
+    /// Set the voltage coming into the roboRIO.
+    pub fn set_vin_voltage(&self, voltage: f64) {
+        state().vin_voltage = voltage;
+    }
+
+    /// Set the voltage of the 5V rail that powers sensors. It starts out at exactly 5V.
+    pub fn set_user_voltage_5v(&self, voltage: f64) {
+        state().user_voltage_5v = voltage;
+    }
+
+    /// Set whether the roboRIO is in a brownout.
+    pub fn set_browned_out(&self, browned_out: bool) {
+        state().browned_out = browned_out;
+    }
+
+    /// Set the acceleration felt by the built-in accelerometer, in g. It starts out at 1g along
+    /// the Z axis, as for a roboRIO lying flat.
+    pub fn set_acceleration(&self, x: f64, y: f64, z: f64) {
+        let accelerometer = &mut state().accelerometer;
+        accelerometer.x = x;
+        accelerometer.y = y;
+        accelerometer.z = z;
+    }
+}
@@ -0,0 +1,242 @@
+use wpilib::wpilib_hal::*;
+use wpilib::hal_call::*;
+use wpilib::sensor;
+use wpilib::usage::*;
+use std::ptr;
+
+/// The PCM module used when none is specified.
+const DEFAULT_MODULE: i32 = 0;
+
+/// Initialize a single solenoid channel on a PCM.
+fn initialize_solenoid(module: i32, channel: i32) -> HalResult<HAL_SolenoidHandle> {
+    if !sensor::check_solenoid_module(module) || !sensor::check_solenoid_channel(channel) {
+        return Err(HalError(0));
+    }
+
+    let port = unsafe { Hal::HAL_GetPortWithModule(module, channel) };
+    let handle = hal_call!(HAL_InitializeSolenoidPort(port))?;
+    report_usage_extras(ResourceType::Solenoid, channel, module, ptr::null());
+    Ok(handle)
+}
+
+/// Get the blacklist of a PCM, with a bit set for each channel disabled because of a short.
+fn get_pcm_blacklist(module: i32) -> HalResult<i32> {
+    hal_call!(HAL_GetPCMSolenoidBlackList(module))
+}
+
+/// A solenoid on a single channel of a PCM.
+pub struct Solenoid {
+    module: i32,
+    channel: i32,
+    handle: HAL_SolenoidHandle,
+}
+
+impl Solenoid {
+    /// Create a new solenoid on the specified channel of the default PCM, returning an error if
+    /// initialization fails.
+    pub fn new(channel: i32) -> HalResult<Solenoid> {
+        Solenoid::with_module(DEFAULT_MODULE, channel)
+    }
+
+    /// Create a new solenoid on the specified PCM and channel, returning an error if
+    /// initialization fails.
+    pub fn with_module(module: i32, channel: i32) -> HalResult<Solenoid> {
+        let handle = initialize_solenoid(module, channel)?;
+        Ok(Solenoid {
+            module,
+            channel,
+            handle,
+        })
+    }
+
+    /// Turn the solenoid on or off.
+    pub fn set(&mut self, on: bool) -> HalResult<()> {
+        hal_call!(HAL_SetSolenoid(self.handle, on as i32))
+    }
+
+    /// Get the value last written to the solenoid.
+    pub fn get(&self) -> HalResult<bool> {
+        Ok(hal_call!(HAL_GetSolenoid(self.handle))? != 0)
+    }
+
+    /// Get the channel for this solenoid.
+    pub fn get_channel(&self) -> i32 {
+        self.channel
+    }
+
+    /// Get the PCM module for this solenoid.
+    pub fn get_module(&self) -> i32 {
+        self.module
+    }
+
+    /// Get the state of every solenoid on this solenoid's PCM, one bit per channel.
+    pub fn get_all(&self) -> HalResult<i32> {
+        hal_call!(HAL_GetAllSolenoids(self.module))
+    }
+
+    /// Get the blacklist of this solenoid's PCM. Channels are blacklisted when they are shorted,
+    /// and stay disabled until sticky faults are cleared.
+    pub fn get_pcm_blacklist(&self) -> HalResult<i32> {
+        get_pcm_blacklist(self.module)
+    }
+
+    /// Has this solenoid been disabled because its channel is shorted?
+    pub fn is_blacklisted(&self) -> HalResult<bool> {
+        Ok(get_pcm_blacklist(self.module)? & (1 << self.channel) != 0)
+    }
+
+    /// Is the PCM's solenoid output voltage out of range?
+    pub fn get_voltage_fault(&self) -> HalResult<bool> {
+        Ok(hal_call!(HAL_GetPCMSolenoidVoltageFault(self.module))? != 0)
+    }
+
+    /// Has the PCM's solenoid output voltage been out of range since sticky faults were cleared?
+    pub fn get_voltage_sticky_fault(&self) -> HalResult<bool> {
+        Ok(hal_call!(HAL_GetPCMSolenoidVoltageStickyFault(self.module))? != 0)
+    }
+
+    /// Clear the sticky faults on the PCM, re-enabling any blacklisted channels.
+    pub fn clear_all_sticky_faults(&mut self) -> HalResult<()> {
+        hal_call!(HAL_ClearAllPCMStickyFaults(self.module))
+    }
+}
+
+impl Drop for Solenoid {
+    fn drop(&mut self) {
+        unsafe {
+            Hal::HAL_FreeSolenoidPort(self.handle);
+        }
+    }
+}
+
+/// The state of a double solenoid.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DoubleSolenoidValue {
+    /// Neither side is powered.
+    Off,
+    /// The forward side is powered.
+    Forward,
+    /// The reverse side is powered.
+    Reverse,
+}
+
+/// A double solenoid, which is driven by two channels of a PCM. At most one of the channels is on
+/// at a time.
+pub struct DoubleSolenoid {
+    module: i32,
+    forward_channel: i32,
+    reverse_channel: i32,
+    forward_handle: HAL_SolenoidHandle,
+    reverse_handle: HAL_SolenoidHandle,
+}
+
+impl DoubleSolenoid {
+    /// Create a new double solenoid on the specified channels of the default PCM, returning an
+    /// error if initialization fails.
+    pub fn new(forward_channel: i32, reverse_channel: i32) -> HalResult<DoubleSolenoid> {
+        DoubleSolenoid::with_module(DEFAULT_MODULE, forward_channel, reverse_channel)
+    }
+
+    /// Create a new double solenoid on the specified PCM and channels, returning an error if
+    /// initialization fails.
+    pub fn with_module(module: i32,
+                       forward_channel: i32,
+                       reverse_channel: i32)
+                       -> HalResult<DoubleSolenoid> {
+        let forward_handle = initialize_solenoid(module, forward_channel)?;
+        let reverse_handle = match initialize_solenoid(module, reverse_channel) {
+            Ok(handle) => handle,
+            Err(err) => {
+                unsafe {
+                    Hal::HAL_FreeSolenoidPort(forward_handle);
+                }
+                return Err(err);
+            }
+        };
+
+        Ok(DoubleSolenoid {
+            module,
+            forward_channel,
+            reverse_channel,
+            forward_handle,
+            reverse_handle,
+        })
+    }
+
+    /// Set which side of the solenoid is powered, turning off the other side.
+    pub fn set(&mut self, value: DoubleSolenoidValue) -> HalResult<()> {
+        let (forward, reverse) = match value {
+            DoubleSolenoidValue::Off => (false, false),
+            DoubleSolenoidValue::Forward => (true, false),
+            DoubleSolenoidValue::Reverse => (false, true),
+        };
+        // Turn a side off before turning the other on, so both are never on together.
+        if forward {
+            hal_call!(HAL_SetSolenoid(self.reverse_handle, reverse as i32))?;
+            hal_call!(HAL_SetSolenoid(self.forward_handle, forward as i32))
+        } else {
+            hal_call!(HAL_SetSolenoid(self.forward_handle, forward as i32))?;
+            hal_call!(HAL_SetSolenoid(self.reverse_handle, reverse as i32))
+        }
+    }
+
+    /// Get the value last written to the solenoid.
+    pub fn get(&self) -> HalResult<DoubleSolenoidValue> {
+        if hal_call!(HAL_GetSolenoid(self.forward_handle))? != 0 {
+            Ok(DoubleSolenoidValue::Forward)
+        } else if hal_call!(HAL_GetSolenoid(self.reverse_handle))? != 0 {
+            Ok(DoubleSolenoidValue::Reverse)
+        } else {
+            Ok(DoubleSolenoidValue::Off)
+        }
+    }
+
+    /// Get the forward channel for this solenoid.
+    pub fn get_forward_channel(&self) -> i32 {
+        self.forward_channel
+    }
+
+    /// Get the reverse channel for this solenoid.
+    pub fn get_reverse_channel(&self) -> i32 {
+        self.reverse_channel
+    }
+
+    /// Get the PCM module for this solenoid.
+    pub fn get_module(&self) -> i32 {
+        self.module
+    }
+
+    /// Has the forward side been disabled because its channel is shorted?
+    pub fn is_forward_blacklisted(&self) -> HalResult<bool> {
+        Ok(get_pcm_blacklist(self.module)? & (1 << self.forward_channel) != 0)
+    }
+
+    /// Has the reverse side been disabled because its channel is shorted?
+    pub fn is_reverse_blacklisted(&self) -> HalResult<bool> {
+        Ok(get_pcm_blacklist(self.module)? & (1 << self.reverse_channel) != 0)
+    }
+
+    /// Is the PCM's solenoid output voltage out of range?
+    pub fn get_voltage_fault(&self) -> HalResult<bool> {
+        Ok(hal_call!(HAL_GetPCMSolenoidVoltageFault(self.module))? != 0)
+    }
+
+    /// Has the PCM's solenoid output voltage been out of range since sticky faults were cleared?
+    pub fn get_voltage_sticky_fault(&self) -> HalResult<bool> {
+        Ok(hal_call!(HAL_GetPCMSolenoidVoltageStickyFault(self.module))? != 0)
+    }
+
+    /// Clear the sticky faults on the PCM, re-enabling any blacklisted channels.
+    pub fn clear_all_sticky_faults(&mut self) -> HalResult<()> {
+        hal_call!(HAL_ClearAllPCMStickyFaults(self.module))
+    }
+}
+
+impl Drop for DoubleSolenoid {
+    fn drop(&mut self) {
+        unsafe {
+            Hal::HAL_FreeSolenoidPort(self.forward_handle);
+            Hal::HAL_FreeSolenoidPort(self.reverse_handle);
+        }
+    }
+}
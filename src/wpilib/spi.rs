@@ -1,22 +1,139 @@
-use wpilib::wpilib_hal::*;
 use wpilib::hal_call::*;
 use wpilib::usage::*;
-use std::mem;
+use std::{error, fmt};
 
 #[repr(C)]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 /// Some SPI port on the RoboRIO
+#[allow(clippy::upper_case_acronyms)]
 pub enum SpiPort {
+    /// Chip select 0 on the onboard SPI header.
     Onboard0 = 0,
+    /// Chip select 1 on the onboard SPI header.
     Onboard1 = 1,
+    /// Chip select 2 on the onboard SPI header.
     Onboard2 = 2,
+    /// Chip select 3 on the onboard SPI header.
     Onboard3 = 3,
+    /// The SPI pins on the MXP port.
     MXP = 4,
 }
 
+/// The clock polarity and phase used on the bus, named by the usual SPI mode numbers.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SpiMode {
+    /// The clock idles low, and data is sampled on the rising edge.
+    Mode0,
+    /// The clock idles low, and data is sampled on the falling edge.
+    Mode1,
+    /// The clock idles high, and data is sampled on the falling edge.
+    Mode2,
+    /// The clock idles high, and data is sampled on the rising edge.
+    Mode3,
+}
+
+impl SpiMode {
+    /// Is data sampled on the trailing edge of each clock pulse?
+    fn sample_on_trailing(self) -> bool {
+        self == SpiMode::Mode1 || self == SpiMode::Mode3
+    }
+
+    /// Is the clock high while idle?
+    fn clock_idle_high(self) -> bool {
+        self == SpiMode::Mode2 || self == SpiMode::Mode3
+    }
+}
+
+/// The order bits of each byte are sent in.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BitOrder {
+    /// The most significant bit is sent first.
+    MsbFirst,
+    /// The least significant bit is sent first.
+    LsbFirst,
+}
+
+/// An error from an SPI transfer.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SpiError {
+    /// The send and receive buffers of a transaction had different lengths.
+    LengthMismatch,
+    /// The transfer failed, for example because the port was not open.
+    TransferFailed,
+}
+
+impl fmt::Display for SpiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SpiError::LengthMismatch => write!(f, "SPI send and receive buffers differ in length"),
+            SpiError::TransferFailed => write!(f, "SPI transfer failed"),
+        }
+    }
+}
+
+impl error::Error for SpiError {}
+
+/// How the FPGA polls a device and extracts a value from each response, for the SPI accumulator.
+///
+/// Every `period` seconds the FPGA sends `command` and reads back `transfer_size` bytes. If the
+/// response masked with `valid_mask` equals `valid_value`, `data_size` bits starting `data_shift`
+/// bits from the least significant end are accumulated.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SpiAccumulatorConfig {
+    /// The time between samples, in seconds.
+    pub period: f64,
+    /// The command to send, left-justified in the first `transfer_size` bytes.
+    pub command: i32,
+    /// The number of bytes in each transfer, including the command.
+    pub transfer_size: i32,
+    /// The bits of the response that show whether it is valid.
+    pub valid_mask: i32,
+    /// The value of the masked bits in a valid response.
+    pub valid_value: i32,
+    /// The position of the data in the response, in bits from the least significant end.
+    pub data_shift: i32,
+    /// The width of the data, in bits.
+    pub data_size: i32,
+    /// Is the data signed?
+    pub is_signed: bool,
+    /// Is the response sent most significant byte first?
+    pub big_endian: bool,
+}
+
+/// Turn the byte count returned by the HAL into a result.
+fn transfer_result(count: i32) -> Result<usize, SpiError> {
+    if count < 0 {
+        Err(SpiError::TransferFailed)
+    } else {
+        Ok(count as usize)
+    }
+}
+
 /// An interface to the SPI bus
+///
+/// Ports start in mode 0, sending the most significant bit first, with chip select active low.
+///
+/// The FPGA can also poll a device on its own and accumulate the values it reads, which lets a
+/// gyro be integrated at kHz rates without involving the CPU. See `init_accumulator`.
+///
+/// # Usage
+/// ```
+/// # use wpilib::*;
+/// let mut spi = SpiInterface::new(SpiPort::Onboard0).unwrap();
+/// spi.set_clock_rate(1_000_000);
+/// spi.set_mode(SpiMode::Mode3);
+///
+/// // Read a register by sending its address with the read bit set.
+/// # sim::spi(SpiPort::Onboard0).queue_response(&[0x00, 0xe5]);
+/// let mut received = [0; 2];
+/// spi.transaction(&[0x80, 0x00], &mut received).unwrap();
+/// # assert_eq!(received[1], 0xe5);
+/// ```
 pub struct SpiInterface {
     port: i32,
+    mode: SpiMode,
+    bit_order: BitOrder,
+    accumulating: bool,
 }
 
 impl SpiInterface {
@@ -28,45 +145,48 @@ impl SpiInterface {
 
         report_usage(ResourceType::SPI, port);
 
-        Ok(SpiInterface { port: port })
+        let mut spi = SpiInterface {
+            port,
+            mode: SpiMode::Mode0,
+            bit_order: BitOrder::MsbFirst,
+            accumulating: false,
+        };
+        spi.set_options();
+        spi.set_chip_select_active_low()?;
+        Ok(spi)
     }
 
     /// Do a transaction on the SPI interface. `send` and `receive` must have the same length or
-    /// the transaction will fail. Return the number of bytes read, or `None` if the transaction
-    /// failed.
-    pub fn transaction(&mut self, send: &[u8], receive: &mut [u8]) -> Option<i32> {
+    /// the transaction will fail. Return the number of bytes read.
+    pub fn transaction(&mut self, send: &[u8], receive: &mut [u8]) -> Result<usize, SpiError> {
         if send.len() != receive.len() {
-            return None;
+            return Err(SpiError::LengthMismatch);
         }
-
-        let result = unsafe {
-            HAL_TransactionSPI(self.port,
-                               mem::transmute(&send[0] as *const u8),
-                               &mut receive[0] as *mut u8,
-                               send.len() as i32)
-        };
-        match result {
-            -1 => None,
-            b => Some(b),
+        if send.is_empty() {
+            return Ok(0);
         }
+
+        transfer_result(unsafe {
+            Hal::HAL_TransactionSPI(self.port,
+                                    send.as_ptr() as *mut u8,
+                                    receive.as_mut_ptr(),
+                                    send.len() as i32)
+        })
     }
 
-    /// Write to the SPI interface and return the number of bytes written, or `None` for a failed
-    /// write.
-    pub fn write(&mut self, data: &[u8]) -> Option<i32> {
-        let result = unsafe {
-            HAL_WriteSPI(self.port,
-                         mem::transmute(&data[0] as *const u8),
-                         data.len() as i32)
-        };
-        match result {
-            -1 => None,
-            b => Some(b),
+    /// Write to the SPI interface and return the number of bytes written.
+    pub fn write(&mut self, data: &[u8]) -> Result<usize, SpiError> {
+        if data.is_empty() {
+            return Ok(0);
         }
+
+        transfer_result(unsafe {
+            Hal::HAL_WriteSPI(self.port, data.as_ptr() as *mut u8, data.len() as i32)
+        })
     }
 
     /// Initate a read sequence by putting a 0 in the transmit buffer and doing a transfer.
-    pub fn initiate_read(&mut self, buffer: &mut [u8]) -> Option<i32> {
+    pub fn initiate_read(&mut self, buffer: &mut [u8]) -> Result<usize, SpiError> {
         let send = vec![0; buffer.len()];
         self.transaction(&send, buffer)
     }
@@ -74,26 +194,164 @@ impl SpiInterface {
     /// Returns the number of bytes read from the receive queue (filled as a response to a previous
     /// write), or waits for the current transfer to complete if the queue is empty. If you want to
     /// initiate reading yourself, you probably want initiate_read.
-    pub fn read(&self, data: &mut [u8]) -> Option<i32> {
-        let result = unsafe { HAL_ReadSPI(self.port, &mut data[0] as *mut u8, data.len() as i32) };
-        match result {
-            -1 => None,
-            b => Some(b),
+    pub fn read(&self, data: &mut [u8]) -> Result<usize, SpiError> {
+        if data.is_empty() {
+            return Ok(0);
         }
+
+        transfer_result(unsafe {
+            Hal::HAL_ReadSPI(self.port, data.as_mut_ptr(), data.len() as i32)
+        })
     }
 
     /// Set the SPI clock rate, up to 4MHz
     pub fn set_clock_rate(&mut self, rate: i32) {
         unsafe {
-            HAL_SetSPISpeed(self.port, rate);
+            Hal::HAL_SetSPISpeed(self.port, rate);
         }
     }
+
+    /// Set the clock polarity and phase.
+    pub fn set_mode(&mut self, mode: SpiMode) {
+        self.mode = mode;
+        self.set_options();
+    }
+
+    /// Get the clock polarity and phase.
+    pub fn get_mode(&self) -> SpiMode {
+        self.mode
+    }
+
+    /// Set the order bits of each byte are sent in.
+    pub fn set_bit_order(&mut self, bit_order: BitOrder) {
+        self.bit_order = bit_order;
+        self.set_options();
+    }
+
+    /// Get the order bits of each byte are sent in.
+    pub fn get_bit_order(&self) -> BitOrder {
+        self.bit_order
+    }
+
+    /// Send the mode and bit order to the HAL.
+    fn set_options(&mut self) {
+        unsafe {
+            Hal::HAL_SetSPIOpts(self.port,
+                                (self.bit_order == BitOrder::MsbFirst) as i32,
+                                self.mode.sample_on_trailing() as i32,
+                                self.mode.clock_idle_high() as i32);
+        }
+    }
+
+    /// Drive chip select high while talking to the device.
+    pub fn set_chip_select_active_high(&mut self) -> HalResult<()> {
+        hal_call!(HAL_SetSPIChipSelectActiveHigh(self.port))
+    }
+
+    /// Drive chip select low while talking to the device. This is the default.
+    pub fn set_chip_select_active_low(&mut self) -> HalResult<()> {
+        hal_call!(HAL_SetSPIChipSelectActiveLow(self.port))
+    }
+
+    /// Start the FPGA polling the device and accumulating the values it reads. The accumulator
+    /// starts at zero, with no center or deadband.
+    ///
+    /// # Usage
+    /// ```
+    /// # use wpilib::*;
+    /// let mut spi = SpiInterface::new(SpiPort::Onboard0).unwrap();
+    /// // Read a 16 bit signed rate from a gyro at 1kHz.
+    /// spi.init_accumulator(&SpiAccumulatorConfig {
+    ///     period: 0.001,
+    ///     command: 0x2000_0000,
+    ///     transfer_size: 4,
+    ///     valid_mask: 0x0c00_0000,
+    ///     valid_value: 0x0400_0000,
+    ///     data_shift: 10,
+    ///     data_size: 16,
+    ///     is_signed: true,
+    ///     big_endian: true,
+    /// }).unwrap();
+    /// # sim::spi(SpiPort::Onboard0).accumulate(100);
+    /// # sim::spi(SpiPort::Onboard0).accumulate(-50);
+    ///
+    /// let (value, count) = spi.get_accumulator_output().unwrap();
+    /// # assert_eq!((value, count), (50, 2));
+    /// ```
+    pub fn init_accumulator(&mut self, config: &SpiAccumulatorConfig) -> HalResult<()> {
+        hal_call!(HAL_InitSPIAccumulator(self.port,
+                                         (config.period * 1e6) as i32,
+                                         config.command,
+                                         config.transfer_size,
+                                         config.valid_mask,
+                                         config.valid_value,
+                                         config.data_shift,
+                                         config.data_size,
+                                         config.is_signed as i32,
+                                         config.big_endian as i32))?;
+        self.accumulating = true;
+        Ok(())
+    }
+
+    /// Stop the accumulator.
+    pub fn free_accumulator(&mut self) -> HalResult<()> {
+        hal_call!(HAL_FreeSPIAccumulator(self.port))?;
+        self.accumulating = false;
+        Ok(())
+    }
+
+    /// Reset the accumulated value and count to zero.
+    pub fn reset_accumulator(&mut self) -> HalResult<()> {
+        hal_call!(HAL_ResetSPIAccumulator(self.port))
+    }
+
+    /// Set a value subtracted from each sample before it is accumulated, such as the reading of a
+    /// gyro at rest.
+    pub fn set_accumulator_center(&mut self, center: i32) -> HalResult<()> {
+        hal_call!(HAL_SetSPIAccumulatorCenter(self.port, center))
+    }
+
+    /// Ignore samples within `deadband` of the center.
+    pub fn set_accumulator_deadband(&mut self, deadband: i32) -> HalResult<()> {
+        hal_call!(HAL_SetSPIAccumulatorDeadband(self.port, deadband))
+    }
+
+    /// Get the last sample read, without the center subtracted.
+    pub fn get_accumulator_last_value(&self) -> HalResult<i32> {
+        hal_call!(HAL_GetSPIAccumulatorLastValue(self.port))
+    }
+
+    /// Get the accumulated value.
+    pub fn get_accumulator_value(&self) -> HalResult<i64> {
+        hal_call!(HAL_GetSPIAccumulatorValue(self.port))
+    }
+
+    /// Get the number of samples accumulated.
+    pub fn get_accumulator_count(&self) -> HalResult<i64> {
+        hal_call!(HAL_GetSPIAccumulatorCount(self.port))
+    }
+
+    /// Get the average of the accumulated samples.
+    pub fn get_accumulator_average(&self) -> HalResult<f64> {
+        hal_call!(HAL_GetSPIAccumulatorAverage(self.port))
+    }
+
+    /// Get the accumulated value and the number of samples, read together so that they match.
+    pub fn get_accumulator_output(&self) -> HalResult<(i64, i64)> {
+        let mut value = 0;
+        let mut count = 0;
+        hal_call!(HAL_GetSPIAccumulatorOutput(self.port, &mut value, &mut count))?;
+        Ok((value, count))
+    }
 }
 
 impl Drop for SpiInterface {
     fn drop(&mut self) {
+        if self.accumulating {
+            let _ = self.free_accumulator();
+        }
         unsafe {
-            HAL_CloseSPI(self.port);
+            Hal::HAL_CloseSPI(self.port);
         }
     }
 }
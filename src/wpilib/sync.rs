@@ -0,0 +1,7 @@
+use std::sync::{Mutex, MutexGuard};
+
+/// Lock a mutex, ignoring poisoning. Only use this for state that every update leaves
+/// consistent, such as a bus or a device's latest reading, so that a panic cannot break it.
+pub fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
@@ -13,14 +13,14 @@ impl Throttler<f64, f64> {
     pub fn new(now: f64, interval: f64) -> Throttler<f64, f64> {
         Throttler {
             next_send: now + interval,
-            interval: interval,
+            interval,
         }
     }
 
     /// Update the throttler. Returns true if the task should be performed.
     pub fn update(&mut self, now: f64) -> bool {
         if now > self.next_send {
-            self.next_send = self.next_send + self.interval;
+            self.next_send += self.interval;
             true
         } else {
             false
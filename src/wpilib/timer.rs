@@ -12,6 +12,12 @@ pub struct Timer {
     running: bool,
 }
 
+impl Default for Timer {
+    fn default() -> Timer {
+        Timer::new()
+    }
+}
+
 impl Timer {
     /// Create a new timer with zero time on the clock. The timer will not start running until
     /// start() is called.
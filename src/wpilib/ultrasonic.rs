@@ -0,0 +1,184 @@
+use wpilib::hal_call::*;
+use wpilib::counter::{Counter, CounterMode};
+use wpilib::digital_input::DigitalInput;
+use wpilib::digital_output::DigitalOutput;
+use wpilib::digital_source::DigitalSource;
+use wpilib::sync::lock;
+use wpilib::usage::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// The length of the pulse that starts a ping, in seconds.
+const PING_TIME: f64 = 10e-6;
+
+/// How long to wait for an echo before pinging the next sensor.
+const MAX_ULTRASONIC_TIME: Duration = Duration::from_millis(100);
+
+const SPEED_OF_SOUND_INCHES_PER_SECOND: f64 = 1130.0 * 12.0;
+
+/// The part of a sensor that the automatic mode thread uses.
+struct Sensor {
+    ping: Mutex<DigitalOutput>,
+    counter: Mutex<Counter>,
+    enabled: AtomicBool,
+}
+
+/// Every sensor, in the order they are pinged in automatic mode.
+static SENSORS: Mutex<Vec<Arc<Sensor>>> = Mutex::new(Vec::new());
+
+/// The automatic mode thread, and the flag that stops it.
+static AUTOMATIC: Mutex<Option<(Arc<AtomicBool>, thread::JoinHandle<()>)>> = Mutex::new(None);
+
+/// Ping each enabled sensor in turn until `stop` is set.
+fn ping_sensors(stop: Arc<AtomicBool>) {
+    let mut next = 0;
+    while !stop.load(Ordering::SeqCst) {
+        {
+            let sensors = lock(&SENSORS);
+            if !sensors.is_empty() {
+                let sensor = &sensors[next % sensors.len()];
+                if sensor.enabled.load(Ordering::SeqCst) {
+                    let _ = lock(&sensor.ping).pulse(PING_TIME);
+                }
+                next = (next + 1) % sensors.len();
+            }
+        }
+        thread::sleep(MAX_ULTRASONIC_TIME);
+    }
+}
+
+/// A ping/echo ultrasonic rangefinder, such as the Devantech SRF04 or Vex ultrasonic sensor.
+/// Pulsing the ping output sends out a burst of sound, and the echo input is held high until its
+/// echo returns, so the range comes from how long the echo input was high.
+///
+/// Sensors near each other hear each other's pings, so in automatic mode a background thread
+/// pings each enabled sensor in turn, leaving time for the echo to return in between.
+///
+/// # Usage
+/// ```
+/// # use wpilib::*;
+/// let mut rangefinder = Ultrasonic::new(0, 1).unwrap();
+/// Ultrasonic::set_automatic_mode(true);
+/// # sim::counter(0).set_count(2);
+/// # sim::counter(0).set_period(2.0 * 12.0 / 13560.0);
+///
+/// if rangefinder.is_range_valid().unwrap() {
+///     let inches = rangefinder.get_range_inches().unwrap();
+/// #   assert!((inches - 12.0).abs() < 1e-9);
+/// }
+/// # Ultrasonic::set_automatic_mode(false);
+/// ```
+pub struct Ultrasonic {
+    sensor: Arc<Sensor>,
+}
+
+impl Ultrasonic {
+    /// Create a new sensor with its ping output and echo input on the specified channels,
+    /// returning an error if initialization fails.
+    pub fn new(ping_channel: i32, echo_channel: i32) -> HalResult<Ultrasonic> {
+        let ping = DigitalOutput::new(ping_channel)?;
+        Ultrasonic::from_sources(ping, DigitalInput::new(echo_channel)?)
+    }
+
+    /// Create a new sensor from a ping output and an echo source that are already owned,
+    /// returning an error if initialization fails.
+    pub fn from_sources<S>(ping: DigitalOutput, echo: S) -> HalResult<Ultrasonic>
+        where S: DigitalSource + Send + 'static
+    {
+        let mut counter = Counter::new(CounterMode::Semiperiod)?;
+        counter.set_up_source(echo)?;
+        counter.set_semi_period_mode(true)?;
+        counter.set_max_period(1.0)?;
+        counter.reset()?;
+
+        let sensor = Arc::new(Sensor {
+            ping: Mutex::new(ping),
+            counter: Mutex::new(counter),
+            enabled: AtomicBool::new(true),
+        });
+        let instances = {
+            let mut sensors = lock(&SENSORS);
+            sensors.push(sensor.clone());
+            sensors.len()
+        };
+
+        report_usage(ResourceType::Ultrasonic, instances as i32);
+        Ok(Ultrasonic { sensor })
+    }
+
+    /// Turn automatic mode on or off for every sensor. While it is on, each enabled sensor is
+    /// pinged in turn; while it is off, sensors are only pinged by `ping`.
+    pub fn set_automatic_mode(enabled: bool) {
+        let mut automatic = lock(&AUTOMATIC);
+        if enabled == automatic.is_some() {
+            return;
+        }
+
+        // Throw away any measurements from before the change.
+        if let Some((stop, join)) = automatic.take() {
+            stop.store(true, Ordering::SeqCst);
+            let _ = join.join();
+        }
+        for sensor in lock(&SENSORS).iter() {
+            let _ = lock(&sensor.counter).reset();
+        }
+
+        if enabled {
+            let stop = Arc::new(AtomicBool::new(false));
+            let thread_stop = stop.clone();
+            *automatic = Some((stop, thread::spawn(move || ping_sensors(thread_stop))));
+        }
+    }
+
+    /// Is automatic mode on?
+    pub fn is_automatic_mode() -> bool {
+        lock(&AUTOMATIC).is_some()
+    }
+
+    /// Ping this sensor once. This turns automatic mode off, since pings from other sensors would
+    /// interfere.
+    pub fn ping(&mut self) -> HalResult<()> {
+        Ultrasonic::set_automatic_mode(false);
+        lock(&self.sensor.counter).reset()?;
+        lock(&self.sensor.ping).pulse(PING_TIME)
+    }
+
+    /// Set whether this sensor is pinged in automatic mode.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.sensor.enabled.store(enabled, Ordering::SeqCst);
+    }
+
+    /// Is this sensor pinged in automatic mode?
+    pub fn is_enabled(&self) -> bool {
+        self.sensor.enabled.load(Ordering::SeqCst)
+    }
+
+    /// Has an echo been measured since the last ping, or since automatic mode was turned on?
+    pub fn is_range_valid(&self) -> HalResult<bool> {
+        Ok(lock(&self.sensor.counter).get()? > 1)
+    }
+
+    /// Get the range to the nearest object, in inches. This is zero if the range is not valid.
+    pub fn get_range_inches(&self) -> HalResult<f64> {
+        if !self.is_range_valid()? {
+            return Ok(0.0);
+        }
+        // The echo input is high while the sound travels there and back.
+        Ok(lock(&self.sensor.counter).get_period()? * SPEED_OF_SOUND_INCHES_PER_SECOND / 2.0)
+    }
+
+    /// Get the range to the nearest object, in millimeters. This is zero if the range is not
+    /// valid.
+    pub fn get_range_mm(&self) -> HalResult<f64> {
+        Ok(self.get_range_inches()? * 25.4)
+    }
+}
+
+impl Drop for Ultrasonic {
+    fn drop(&mut self) {
+        // Once removed, the automatic mode thread can no longer ping this sensor.
+        lock(&SENSORS).retain(|sensor| !Arc::ptr_eq(sensor, &self.sensor));
+    }
+}
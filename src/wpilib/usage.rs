@@ -1,6 +1,6 @@
 #![allow(missing_docs)]
 
-use wpilib::wpilib_hal::*;
+use wpilib::hal_call::*;
 use std::ptr;
 use std::os::raw;
 
@@ -76,16 +76,17 @@ pub const LANGUAGE_RUST: i32 = 6;
 /// Report the usage of a specific resource type with an `instance` value attached.
 pub fn report_usage(resource: ResourceType, instance: i32) {
     unsafe {
-        HAL_Report(resource as i32, instance, 0, ptr::null());
+        Hal::HAL_Report(resource as i32, instance, 0, ptr::null());
     }
 }
 
 /// Just a safe wrapper around HAL_Report
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 pub fn report_usage_extras(resource: ResourceType,
                            instance: i32,
                            context: i32,
                            feature: *const raw::c_char) {
     unsafe {
-        HAL_Report(resource as i32, instance, context, feature);
+        Hal::HAL_Report(resource as i32, instance, context, feature);
     }
 }
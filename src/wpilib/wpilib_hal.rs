@@ -1,9 +1,11 @@
 /* automatically generated by rust-bindgen */
 
 #![allow(dead_code,
+         overflowing_literals,
          non_camel_case_types,
          non_upper_case_globals,
          non_snake_case)]
+#![allow(clippy::all)]
 pub const HAL_kInvalidHandle: ::std::os::raw::c_uchar = 0;
 pub const CAN_SEND_PERIOD_NO_REPEAT: ::std::os::raw::c_uchar = 0;
 pub const CAN_SEND_PERIOD_STOP_REPEATING: ::std::os::raw::c_char = 255;
@@ -240,19 +242,19 @@ impl HAL_ControlWord {
         (self.field & (1 << 0)) != 0
     }
     pub fn autonomous(&self) -> bool {
-        (self.field & (1 << 0)) != 0
+        (self.field & (1 << 1)) != 0
     }
     pub fn test(&self) -> bool {
-        (self.field & (1 << 0)) != 0
+        (self.field & (1 << 2)) != 0
     }
     pub fn eStop(&self) -> bool {
-        (self.field & (1 << 0)) != 0
+        (self.field & (1 << 3)) != 0
     }
     pub fn fmsAttached(&self) -> bool {
-        (self.field & (1 << 0)) != 0
+        (self.field & (1 << 4)) != 0
     }
     pub fn dsAttached(&self) -> bool {
-        (self.field & (1 << 0)) != 0
+        (self.field & (1 << 5)) != 0
     }
 }
 impl ::std::default::Default for HAL_ControlWord {
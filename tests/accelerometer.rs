@@ -0,0 +1,22 @@
+#![cfg(not(feature = "athena"))]
+
+extern crate wpilib;
+
+use wpilib::*;
+
+#[test]
+fn adxl362_rejects_another_part() {
+    sim::spi(SpiPort::Onboard1).queue_response(&[0x00, 0x00, 0xe5]);
+    match ADXL362::new(SpiPort::Onboard1, AccelerometerRange::TwoG) {
+        Err(DeviceError::WrongDevice) => {}
+        other => panic!("expected the wrong device, got {:?}", other.err()),
+    }
+}
+
+#[test]
+fn adxl345_reports_a_missing_device() {
+    match ADXL345I2c::with_address(I2cPort::MXP, 0x53, AccelerometerRange::TwoG) {
+        Err(DeviceError::I2c(I2cError::TransferAbort)) => {}
+        other => panic!("expected an aborted transfer, got {:?}", other.err()),
+    }
+}
@@ -0,0 +1,17 @@
+#![cfg(not(feature = "athena"))]
+
+extern crate wpilib;
+
+use wpilib::*;
+
+#[test]
+fn dead_rail_is_an_error() {
+    let pot = AnalogPotentiometer::new(1, 3600.0, 90.0).unwrap();
+    sim::analog_in(1).set_voltage(0.25);
+    sim::roborio().set_user_voltage_5v(5.0);
+    assert!((pot.get().unwrap() - 270.0).abs() < 1e-9);
+
+    sim::roborio().set_user_voltage_5v(0.0);
+    assert!(pot.get().is_err());
+    sim::roborio().set_user_voltage_5v(5.0);
+}
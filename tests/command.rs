@@ -0,0 +1,297 @@
+#![cfg(not(feature = "athena"))]
+
+extern crate wpilib;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::{Mutex, MutexGuard};
+use wpilib::command::*;
+use wpilib::*;
+
+/// Every test shares the driver station, so they take turns.
+static DRIVER_STATION: Mutex<()> = Mutex::new(());
+
+/// Enable the robot, and wait until the driver station has seen it.
+fn enabled() -> MutexGuard<'static, ()> {
+    let guard = DRIVER_STATION.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    sim::driver_station().set_enabled(true);
+    let ds = DriverStation::instance();
+    ds.wait_for_data();
+    ds.wait_for_data();
+    guard
+}
+
+type Log = Rc<RefCell<Vec<String>>>;
+
+struct Arm;
+
+impl Subsystem for Arm {}
+
+/// A command that logs what the scheduler does to it, and finishes after executing `runs` times.
+struct Probe {
+    name: &'static str,
+    log: Log,
+    requirements: Vec<SubsystemId>,
+    runs: Option<u32>,
+    executed: u32,
+}
+
+impl Probe {
+    fn new(name: &'static str, log: &Log, requirements: &[SubsystemId], runs: Option<u32>) -> Probe {
+        Probe {
+            name,
+            log: log.clone(),
+            requirements: requirements.to_vec(),
+            runs,
+            executed: 0,
+        }
+    }
+
+    fn record(&self, event: &str) {
+        self.log.borrow_mut().push(format!("{} {}", self.name, event));
+    }
+}
+
+impl Command for Probe {
+    fn initialize(&mut self) {
+        self.executed = 0;
+        self.record("init");
+    }
+
+    fn execute(&mut self) {
+        self.executed += 1;
+        self.record("exec");
+    }
+
+    fn is_finished(&mut self) -> bool {
+        self.runs.is_some_and(|runs| self.executed >= runs)
+    }
+
+    fn end(&mut self, interrupted: bool) {
+        self.record(if interrupted { "interrupted" } else { "end" });
+    }
+
+    fn requirements(&self) -> Vec<SubsystemId> {
+        self.requirements.clone()
+    }
+}
+
+/// Take the events logged so far.
+fn take(log: &Log) -> Vec<String> {
+    log.borrow_mut().drain(..).collect()
+}
+
+#[test]
+fn command_finishes_and_releases_its_requirements() {
+    let _enabled = enabled();
+    let log = Log::default();
+    let mut scheduler = Scheduler::new();
+    let arm = scheduler.register_subsystem(Rc::new(RefCell::new(Arm)));
+    let command = scheduler.add(Probe::new("a", &log, &[arm], Some(2)));
+
+    scheduler.schedule(command);
+    assert_eq!(scheduler.requiring(arm), Some(command));
+    scheduler.run();
+    scheduler.run();
+    assert!(!scheduler.is_scheduled(command));
+    assert_eq!(scheduler.requiring(arm), None);
+    assert_eq!(take(&log), ["a init", "a exec", "a exec", "a end"]);
+
+    scheduler.run();
+    assert!(take(&log).is_empty());
+}
+
+#[test]
+fn requirement_conflict_interrupts_the_running_command() {
+    let _enabled = enabled();
+    let log = Log::default();
+    let mut scheduler = Scheduler::new();
+    let arm = scheduler.register_subsystem(Rc::new(RefCell::new(Arm)));
+    let wrist = scheduler.register_subsystem(Rc::new(RefCell::new(Arm)));
+    let first = scheduler.add(Probe::new("a", &log, &[arm], None));
+    let second = scheduler.add(Probe::new("b", &log, &[arm, wrist], None));
+    let unrelated = scheduler.add(Probe::new("c", &log, &[], None));
+
+    scheduler.schedule(first);
+    scheduler.schedule(unrelated);
+    scheduler.run();
+    scheduler.schedule(second);
+    assert!(!scheduler.is_scheduled(first));
+    assert!(scheduler.is_scheduled(second));
+    assert!(scheduler.is_scheduled(unrelated));
+    assert_eq!(scheduler.requiring(arm), Some(second));
+    assert_eq!(scheduler.requiring(wrist), Some(second));
+
+    scheduler.run();
+    assert_eq!(take(&log),
+               ["a init", "c init", "a exec", "c exec", "a interrupted", "b init", "c exec",
+                "b exec"]);
+}
+
+#[test]
+fn cancel_interrupts_a_running_command() {
+    let _enabled = enabled();
+    let log = Log::default();
+    let mut scheduler = Scheduler::new();
+    let first = scheduler.add(Probe::new("a", &log, &[], None));
+    let second = scheduler.add(Probe::new("b", &log, &[], None));
+
+    scheduler.schedule(first);
+    scheduler.run();
+    scheduler.cancel(first);
+    assert!(!scheduler.is_scheduled(first));
+    // Cancelling a command that is not running does nothing.
+    scheduler.cancel(first);
+    scheduler.run();
+    assert_eq!(take(&log), ["a init", "a exec", "a interrupted"]);
+
+    scheduler.schedule(first);
+    scheduler.schedule(second);
+    scheduler.cancel_all();
+    assert_eq!(take(&log), ["a init", "b init", "a interrupted", "b interrupted"]);
+}
+
+#[test]
+fn default_command_is_rescheduled_when_its_subsystem_is_free() {
+    let _enabled = enabled();
+    let log = Log::default();
+    let mut scheduler = Scheduler::new();
+    let arm = scheduler.register_subsystem(Rc::new(RefCell::new(Arm)));
+    let default = scheduler.add(Probe::new("default", &log, &[arm], None));
+    let command = scheduler.add(Probe::new("a", &log, &[arm], Some(1)));
+    scheduler.set_default_command(arm, default);
+
+    scheduler.run();
+    assert_eq!(scheduler.requiring(arm), Some(default));
+    scheduler.run();
+    assert_eq!(take(&log), ["default init", "default exec"]);
+
+    scheduler.schedule(command);
+    scheduler.run();
+    assert_eq!(scheduler.requiring(arm), Some(default));
+    scheduler.run();
+    assert_eq!(take(&log),
+               ["default interrupted", "a init", "a exec", "a end", "default init",
+                "default exec"]);
+}
+
+#[test]
+fn sequential_group_runs_commands_in_order() {
+    let _enabled = enabled();
+    let log = Log::default();
+    let mut scheduler = Scheduler::new();
+    let arm = scheduler.register_subsystem(Rc::new(RefCell::new(Arm)));
+    let wrist = scheduler.register_subsystem(Rc::new(RefCell::new(Arm)));
+    let group = scheduler.add(SequentialGroup::new(vec![
+        Box::new(Probe::new("a", &log, &[arm], Some(1))),
+        Box::new(Probe::new("b", &log, &[wrist], Some(2))),
+    ]));
+
+    scheduler.schedule(group);
+    assert_eq!(scheduler.requiring(arm), Some(group));
+    assert_eq!(scheduler.requiring(wrist), Some(group));
+    for _ in 0..3 {
+        scheduler.run();
+    }
+    assert!(!scheduler.is_scheduled(group));
+    assert_eq!(take(&log),
+               ["a init", "a exec", "a end", "b init", "b exec", "b exec", "b end"]);
+
+    // Interrupting the group interrupts the command it is running.
+    scheduler.schedule(group);
+    scheduler.run();
+    scheduler.cancel(group);
+    assert_eq!(take(&log), ["a init", "a exec", "a end", "b init", "b interrupted"]);
+}
+
+#[test]
+fn parallel_group_waits_for_every_command() {
+    let _enabled = enabled();
+    let log = Log::default();
+    let mut scheduler = Scheduler::new();
+    let group = scheduler.add(ParallelGroup::new(vec![
+        Box::new(Probe::new("a", &log, &[], Some(1))),
+        Box::new(Probe::new("b", &log, &[], Some(2))),
+    ]));
+
+    scheduler.schedule(group);
+    scheduler.run();
+    assert!(scheduler.is_scheduled(group));
+    scheduler.run();
+    assert!(!scheduler.is_scheduled(group));
+    assert_eq!(take(&log),
+               ["a init", "b init", "a exec", "a end", "b exec", "b exec", "b end"]);
+}
+
+#[test]
+fn parallel_race_ends_with_the_first_command() {
+    let _enabled = enabled();
+    let log = Log::default();
+    let mut scheduler = Scheduler::new();
+    let race = scheduler.add(ParallelGroup::race(vec![
+        Box::new(Probe::new("a", &log, &[], Some(2))),
+        Box::new(Probe::new("b", &log, &[], None)),
+    ]));
+
+    scheduler.schedule(race);
+    scheduler.run();
+    scheduler.run();
+    assert!(!scheduler.is_scheduled(race));
+    assert_eq!(take(&log),
+               ["a init", "b init", "a exec", "b exec", "a exec", "a end", "b exec",
+                "b interrupted"]);
+
+    let empty = scheduler.add(ParallelGroup::race(Vec::new()));
+    scheduler.schedule(empty);
+    scheduler.run();
+    assert!(!scheduler.is_scheduled(empty));
+}
+
+#[test]
+fn parallel_deadline_ends_with_the_deadline() {
+    let _enabled = enabled();
+    let log = Log::default();
+    let mut scheduler = Scheduler::new();
+    let group = scheduler.add(ParallelGroup::deadline(
+        Box::new(Probe::new("deadline", &log, &[], Some(2))),
+        vec![Box::new(Probe::new("a", &log, &[], Some(1))),
+             Box::new(Probe::new("b", &log, &[], None))],
+    ));
+
+    scheduler.schedule(group);
+    scheduler.run();
+    assert!(scheduler.is_scheduled(group));
+    scheduler.run();
+    assert!(!scheduler.is_scheduled(group));
+    assert_eq!(take(&log),
+               ["deadline init", "a init", "b init", "deadline exec", "a exec", "a end",
+                "b exec", "deadline exec", "deadline end", "b exec", "b interrupted"]);
+}
+
+#[test]
+fn conditional_command_runs_the_chosen_command() {
+    let _enabled = enabled();
+    let log = Log::default();
+    let choice = Rc::new(RefCell::new(true));
+    let mut scheduler = Scheduler::new();
+    let arm = scheduler.register_subsystem(Rc::new(RefCell::new(Arm)));
+    let wrist = scheduler.register_subsystem(Rc::new(RefCell::new(Arm)));
+    let condition = choice.clone();
+    let command = scheduler.add(ConditionalCommand::new(
+        Box::new(Probe::new("yes", &log, &[arm], Some(1))),
+        Box::new(Probe::new("no", &log, &[wrist], Some(1))),
+        move || *condition.borrow(),
+    ));
+
+    // It requires everything either command might need.
+    scheduler.schedule(command);
+    assert_eq!(scheduler.requiring(arm), Some(command));
+    assert_eq!(scheduler.requiring(wrist), Some(command));
+    scheduler.run();
+    assert_eq!(take(&log), ["yes init", "yes exec", "yes end"]);
+
+    *choice.borrow_mut() = false;
+    scheduler.schedule(command);
+    scheduler.run();
+    assert_eq!(take(&log), ["no init", "no exec", "no end"]);
+}
@@ -0,0 +1,23 @@
+#![cfg(not(feature = "athena"))]
+
+extern crate wpilib;
+
+use wpilib::*;
+
+#[test]
+fn period_converts_between_nanoseconds_and_cycles() {
+    let mut filter = DigitalGlitchFilter::new().unwrap();
+
+    // At 40 ticks per microsecond, each four tick cycle is 100ns.
+    filter.set_period_nanoseconds(1_000).unwrap();
+    assert_eq!(filter.get_period_cycles().unwrap(), 10);
+    assert_eq!(filter.get_period_nanoseconds().unwrap(), 1_000);
+
+    // Periods are rounded down to a whole cycle.
+    filter.set_period_nanoseconds(2_550).unwrap();
+    assert_eq!(filter.get_period_cycles().unwrap(), 25);
+    assert_eq!(filter.get_period_nanoseconds().unwrap(), 2_500);
+
+    filter.set_period_cycles(3).unwrap();
+    assert_eq!(filter.get_period_nanoseconds().unwrap(), 300);
+}
@@ -0,0 +1,32 @@
+#![cfg(not(feature = "athena"))]
+
+extern crate wpilib;
+
+use wpilib::*;
+
+#[test]
+fn duty_cycle_range_must_be_ordered() {
+    let mut encoder = DutyCycleEncoder::new(2).unwrap();
+    assert!(encoder.set_duty_cycle_range(0.5, 0.5).is_err());
+    assert!(encoder.set_duty_cycle_range(0.9, 0.1).is_err());
+    assert!(encoder.set_duty_cycle_range(-0.1, 0.9).is_err());
+    assert!(encoder.set_duty_cycle_range(0.1, 1.1).is_err());
+
+    // A rejected range leaves the old one in place.
+    sim::counter(0).set_period(0.0005);
+    sim::counter(1).set_period(0.001);
+    assert!((encoder.get_absolute_position().unwrap() - 0.5).abs() < 1e-9);
+
+    encoder.set_duty_cycle_range(0.25, 0.75).unwrap();
+    sim::counter(0).set_period(0.000375);
+    assert!((encoder.get_absolute_position().unwrap() - 0.25).abs() < 1e-9);
+}
+
+#[test]
+fn connected_frequency_threshold_must_be_positive() {
+    let mut encoder = DutyCycleEncoder::new(3).unwrap();
+    assert!(encoder.set_connected_frequency_threshold(0.0).is_err());
+    assert!(encoder.set_connected_frequency_threshold(-50.0).is_err());
+    assert!(encoder.set_connected_frequency_threshold(f64::NAN).is_err());
+    encoder.set_connected_frequency_threshold(50.0).unwrap();
+}
@@ -0,0 +1,24 @@
+#![cfg(not(feature = "athena"))]
+
+extern crate wpilib;
+
+use wpilib::*;
+
+#[test]
+fn adxrs450_rejects_another_part() {
+    sim::spi(SpiPort::Onboard2).queue_response(&[0x00, 0x00, 0x00, 0x00]);
+    match ADXRS450Gyro::new(SpiPort::Onboard2) {
+        Err(DeviceError::WrongDevice) => {}
+        other => panic!("expected the wrong device, got {:?}", other.err()),
+    }
+}
+
+#[test]
+fn navx_rejects_an_update_rate_out_of_range() {
+    for &rate in &[0, 3, 201] {
+        match NavX::from_i2c(I2cPort::OnBoard, rate) {
+            Err(DeviceError::OutOfRange) => {}
+            other => panic!("expected {} Hz to be out of range, got {:?}", rate, other.err()),
+        }
+    }
+}
@@ -0,0 +1,45 @@
+#![cfg(not(feature = "athena"))]
+
+extern crate wpilib;
+
+use std::sync::{Arc, Mutex};
+use wpilib::*;
+
+// The FPGA reports edges in a different bit for each interrupt, so each test allocates a spare
+// interrupt first to make sure the one under test is not interrupt 0.
+
+#[test]
+fn wait_decodes_edges_on_any_interrupt() {
+    let mut spare = Interrupt::from_channel(0).unwrap();
+    spare.register_sync_interrupt().unwrap();
+
+    let mut interrupt = Interrupt::from_channel(1).unwrap();
+    interrupt.set_up_source_edge(true, true).unwrap();
+    interrupt.register_sync_interrupt().unwrap();
+
+    sim::dio(1).set_value(false);
+    assert_eq!(interrupt.wait(0.0, false).unwrap(), WaitResult::FallingEdge);
+    sim::dio(1).set_value(true);
+    assert_eq!(interrupt.wait(0.0, false).unwrap(), WaitResult::RisingEdge);
+    sim::dio(1).set_value(false);
+    sim::dio(1).set_value(true);
+    assert_eq!(interrupt.wait(0.0, false).unwrap(), WaitResult::Both);
+    assert_eq!(interrupt.wait(0.0, false).unwrap(), WaitResult::Timeout);
+}
+
+#[test]
+fn handler_runs_on_any_interrupt() {
+    let mut spare = Interrupt::from_channel(2).unwrap();
+    spare.register_sync_interrupt().unwrap();
+
+    let results = Arc::new(Mutex::new(Vec::new()));
+    let mut interrupt = Interrupt::from_channel(3).unwrap();
+    interrupt.set_up_source_edge(true, true).unwrap();
+    let seen = results.clone();
+    interrupt.register_handler(move |result| seen.lock().unwrap().push(result)).unwrap();
+    interrupt.enable().unwrap();
+
+    sim::dio(3).set_value(false);
+    sim::dio(3).set_value(true);
+    assert_eq!(*results.lock().unwrap(), [WaitResult::FallingEdge, WaitResult::RisingEdge]);
+}
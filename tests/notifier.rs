@@ -0,0 +1,31 @@
+#![cfg(not(feature = "athena"))]
+
+extern crate wpilib;
+
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use wpilib::*;
+
+#[test]
+fn handler_can_drop_another_notifier() {
+    let other = Arc::new(Mutex::new(Some(Notifier::new(|| {}).unwrap())));
+    let (done, finished) = mpsc::channel();
+
+    let dropping = other.clone();
+    let mut notifier = Notifier::new(move || {
+        drop(dropping.lock().unwrap().take());
+        let _ = done.send(());
+    }).unwrap();
+    notifier.start_single(1000).unwrap();
+
+    finished.recv_timeout(Duration::from_secs(5)).expect("the handler deadlocked");
+    assert!(other.lock().unwrap().is_none());
+}
+
+#[test]
+fn zero_period_is_rejected() {
+    let mut notifier = Notifier::new(|| {}).unwrap();
+    assert!(notifier.start_periodic(0).is_err());
+    notifier.start_periodic(1000).unwrap();
+}
@@ -0,0 +1,102 @@
+#![cfg(not(feature = "athena"))]
+
+extern crate wpilib;
+
+use std::sync::{Mutex, MutexGuard};
+use wpilib::*;
+
+/// `sim::reset` clears every device, so the tests take turns.
+static SIM: Mutex<()> = Mutex::new(());
+
+fn sim() -> MutexGuard<'static, ()> {
+    SIM.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn assert_speed(channel: i32, speed: f64) {
+    let actual = sim::pwm(channel).speed();
+    assert!((actual - speed).abs() < 0.01,
+            "expected PWM {} at {}, got {}",
+            channel,
+            speed,
+            actual);
+}
+
+#[test]
+fn speed_controller_commands_reach_the_pwm() {
+    let _sim = sim();
+    let mut talon = PwmSpeedController::talon(4, false).unwrap();
+    assert!(sim::pwm(4).is_initialized());
+    assert_speed(4, 0.0);
+
+    talon.set(0.75);
+    assert_speed(4, 0.75);
+    talon.set(-0.25);
+    assert_speed(4, -0.25);
+    talon.set(0.0);
+    assert_speed(4, 0.0);
+
+    talon.invert(true);
+    talon.set(0.5);
+    assert_speed(4, -0.5);
+
+    talon.disable();
+    assert_eq!(sim::pwm(4).raw(), 0);
+
+    drop(talon);
+    assert!(!sim::pwm(4).is_initialized());
+}
+
+#[test]
+fn solenoid_commands_reach_the_pcm() {
+    let _sim = sim();
+    let mut solenoid = Solenoid::new(5).unwrap();
+    assert!(sim::solenoid(0, 5).is_initialized());
+    assert!(!sim::solenoid(0, 5).value());
+
+    solenoid.set(true).unwrap();
+    assert!(sim::solenoid(0, 5).value());
+    solenoid.set(false).unwrap();
+    assert!(!sim::solenoid(0, 5).value());
+
+    let mut double = DoubleSolenoid::new(6, 7).unwrap();
+    double.set(DoubleSolenoidValue::Forward).unwrap();
+    assert!(sim::solenoid(0, 6).value() && !sim::solenoid(0, 7).value());
+    double.set(DoubleSolenoidValue::Reverse).unwrap();
+    assert!(!sim::solenoid(0, 6).value() && sim::solenoid(0, 7).value());
+    double.set(DoubleSolenoidValue::Off).unwrap();
+    assert!(!sim::solenoid(0, 6).value() && !sim::solenoid(0, 7).value());
+}
+
+#[test]
+fn digital_output_commands_reach_the_dio() {
+    let _sim = sim();
+    let mut output = DigitalOutput::new(8).unwrap();
+    assert!(sim::dio(8).is_initialized());
+    assert!(!sim::dio(8).is_input());
+
+    output.set(true).unwrap();
+    assert!(sim::dio(8).value());
+    output.set(false).unwrap();
+    assert!(!sim::dio(8).value());
+}
+
+#[test]
+fn reset_returns_devices_to_power_on() {
+    let _sim = sim();
+    {
+        let mut victor = PwmSpeedController::victor_sp(9, false).unwrap();
+        let mut solenoid = Solenoid::new(0).unwrap();
+        let mut output = DigitalOutput::new(9).unwrap();
+        victor.set(1.0);
+        solenoid.set(true).unwrap();
+        output.set(true).unwrap();
+    }
+    sim::reset();
+
+    assert!(!sim::pwm(9).is_initialized());
+    assert_eq!(sim::pwm(9).raw(), 0);
+    assert!(!sim::solenoid(0, 0).is_initialized());
+    assert!(!sim::solenoid(0, 0).value());
+    assert!(!sim::dio(9).is_initialized());
+    assert!(!sim::dio(9).value());
+}